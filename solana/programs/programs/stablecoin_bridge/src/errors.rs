@@ -19,6 +19,12 @@ pub enum BridgeError {
     #[msg("Bridge lock already released")]
     AlreadyReleased,
 
+    #[msg("Bridge lock already completed")]
+    AlreadyCompleted,
+
+    #[msg("Bridge lock is neither released nor completed")]
+    LockNotSettled,
+
     #[msg("Invalid bridge lock for this pool")]
     InvalidBridgeLock,
 
@@ -42,4 +48,151 @@ pub enum BridgeError {
 
     #[msg("Stablecoin amount must be greater than zero")]
     ZeroStablecoinAmount,
+
+    #[msg("Pool has no outstanding LP supply")]
+    NoLpSupply,
+
+    #[msg("Slippage exceeded: output below caller's minimum")]
+    SlippageExceeded,
+
+    #[msg("Pool is draining: deposits and new locks are disabled")]
+    PoolIsDraining,
+
+    #[msg("Unauthorized: pending admin required")]
+    UnauthorizedPendingAdmin,
+
+    #[msg("No auto-revert sweep is active for this pool")]
+    AutoRevertNotActive,
+
+    #[msg("Bridge lock is newer than the auto-revert cutoff")]
+    LockNotStale,
+
+    #[msg("Relayer set is full")]
+    RelayerSetFull,
+
+    #[msg("Relayer is already authorized")]
+    RelayerAlreadyAuthorized,
+
+    #[msg("Relayer is not in the authorized set")]
+    RelayerNotFound,
+
+    #[msg("Cannot remove the last authorized relayer")]
+    CannotRemoveLastRelayer,
+
+    #[msg("Insurance vault is not configured or already configured")]
+    InvalidInsuranceVault,
+
+    #[msg("Insufficient insurance fund balance")]
+    InsufficientInsurance,
+
+    #[msg("Refund account must be the lock sender's stablecoin token account")]
+    InvalidRefundAccount,
+
+    #[msg("Lock would exceed the pool's rolling window limit")]
+    WindowLimitExceeded,
+
+    #[msg("Lock amount is below the pool minimum")]
+    LockAmountBelowMinimum,
+
+    #[msg("Destination chain is not supported")]
+    UnsupportedChain,
+
+    #[msg("Recipient address must not be all zeros")]
+    InvalidRecipient,
+
+    #[msg("Insufficient fee vault balance")]
+    InsufficientFeeBalance,
+
+    #[msg("Deposit would exceed the pool's liquidity cap")]
+    PoolCapExceeded,
+
+    #[msg("LP lockup period has not elapsed since the last deposit")]
+    LpLockupActive,
+
+    #[msg("Reentrant call into a pool instruction")]
+    ReentrancyDetected,
+
+    #[msg("Release amount exceeds the lock's outstanding amount")]
+    ReleaseExceedsLocked,
+
+    #[msg("Missing or invalid relayer Ed25519 attestation")]
+    InvalidAttestation,
+
+    #[msg("Not enough relayer approvals to release this lock")]
+    ReleaseThresholdNotMet,
+
+    #[msg("Relayer has already approved this release")]
+    DuplicateApproval,
+
+    #[msg("Lock has no room for more approvals")]
+    ApprovalsFull,
+
+    #[msg("Release threshold exceeds the number of authorized relayers")]
+    InvalidReleaseThreshold,
+
+    #[msg("Approval does not match the action and amount already being approved")]
+    ApprovalMismatch,
+
+    #[msg("Fee rate timelock has not elapsed")]
+    FeeTimelockActive,
+
+    #[msg("No pending fee rate proposal")]
+    NoPendingFeeRate,
+
+    #[msg("Batch is empty, too large, or its accounts do not match its items")]
+    InvalidBatchSize,
+
+    #[msg("Lock has not reached the stuck-lock timeout")]
+    LockNotExpired,
+
+    #[msg("LP mint decimals must match the stablecoin mint decimals")]
+    DecimalMismatch,
+
+    #[msg("Vault must be empty at pool initialization")]
+    VaultNotEmpty,
+
+    #[msg("Vault must have no delegate or close authority")]
+    VaultHasAuthority,
+
+    #[msg("LP token balance is lower than the amount to withdraw")]
+    InsufficientLpBalance,
+
+    #[msg("Pool has outstanding LP supply")]
+    LpSupplyOutstanding,
+
+    #[msg("Token account is frozen")]
+    TokenAccountFrozen,
+
+    #[msg("Relayer fee must be less than the escrowed lock amount")]
+    RelayerFeeExceedsAmount,
+
+    #[msg("Stablecoin mint has a freeze authority")]
+    FrozenAuthorityPresent,
+
+    #[msg("A lock with this client tag was submitted recently")]
+    DuplicateLock,
+
+    #[msg("Pool stablecoin is not the wrapped SOL mint")]
+    NotNativeMint,
+
+    #[msg("Lock would exceed the pool's cap on locked liquidity")]
+    LockedCapExceeded,
+
+    #[msg("ChainLockCounter account does not match the lock's pool and destination chain")]
+    InvalidChainLockCounter,
+
+    #[msg("Pool must be paused")]
+    PoolNotPaused,
+    #[msg("Pool still holds liquidity, fees, insurance, or pending locks")]
+    PoolNotEmpty,
+    #[msg("Lock is younger than the minimum age for release")]
+    LockTooYoung,
+    #[msg("Account must be migrated to the current version")]
+    AccountVersionMismatch,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Fee rate exceeds the protocol maximum")]
+    FeeExceedsProtocolMax,
+    #[msg("Token-2022 mints with a transfer hook are not allowed")]
+    TransferHookNotAllowed,
 }
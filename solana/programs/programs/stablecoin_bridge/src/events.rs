@@ -1,15 +1,64 @@
 //! Events emitted by the stablecoin bridge program.
 //! Indexers and relayers consume these for cross-chain coordination.
+//!
+//! Every event carries an `event_seq` taken from the account it is about: events naming a pool
+//! draw from that pool's counter, protocol-level events (config, relayers, supported chains)
+//! from the Config's. Each counter increases by one per event, giving indexers a gap-free
+//! total order per pool and for the protocol that does not depend on slot or timestamp ties.
 
 use anchor_lang::prelude::*;
 
+use crate::state::{ApprovalAction, PauseReason, ReleaseReason};
+
 #[event]
 pub struct LiquidityDeposited {
     pub pool: Pubkey,
     pub depositor: Pubkey,
+    /// Owner of the token account the LP was minted to.
+    pub beneficiary: Pubkey,
     pub stablecoin_amount: u64,
     pub lp_tokens_minted: u64,
     pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct DepositFeeCollected {
+    pub pool: Pubkey,
+    pub payer: Pubkey,
+    pub fee_amount: u64,
+    /// Share of fee_amount routed to the pool fee vault.
+    pub protocol_amount: u64,
+    /// Share of fee_amount retained in the pool as LP yield.
+    pub lp_amount: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct LiquidityLockedForever {
+    pub pool: Pubkey,
+    pub donor: Pubkey,
+    pub stablecoin_amount: u64,
+    /// LP that the deposit would have minted; burned instead, so supply is unchanged.
+    pub lp_tokens_burned: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct YieldDistributed {
+    pub pool: Pubkey,
+    pub source: Pubkey,
+    /// Stablecoin credited to available_liquidity.
+    pub amount: u64,
+    /// Share price after the distribution, scaled by Pool::SHARE_PRICE_SCALE.
+    pub share_price: u64,
+    pub timestamp: i64,
+    pub lifetime_yield_distributed: u64,
+    /// Timestamp of the previous distribution (0 if this is the first), for time-weighted APY.
+    pub last_yield_timestamp: i64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -19,18 +68,65 @@ pub struct LiquidityWithdrawn {
     pub stablecoin_amount: u64,
     pub lp_tokens_burned: u64,
     pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub lp_tokens_burned: u64,
+    /// Stablecoin the owner will be paid (net of fee).
+    pub stablecoin_amount: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct WithdrawalFulfilled {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub stablecoin_amount: u64,
+    /// When the request was queued.
+    pub requested_at: i64,
+    pub timestamp: i64,
+    pub event_seq: u64,
 }
 
 #[event]
 pub struct BridgeIntent {
     pub pool: Pubkey,
     pub sender: Pubkey,
+    /// Net amount escrowed (gross_amount minus bridge fee). The destination delivers
+    /// amount - relayer_fee.
     pub amount: u64,
     pub destination_chain_id: u64,
     /// Recipient on destination chain (opaque; 32 bytes).
     pub recipient_address: [u8; 32],
     pub nonce: u64,
     pub timestamp: i64,
+    /// Registered short name of the destination chain (None if not registered).
+    pub destination_chain_name: Option<[u8; 16]>,
+    /// Amount debited from the sender, including the bridge fee.
+    pub gross_amount: u64,
+    pub lifetime_locked_volume: u64,
+    /// Slot the lock was created in, for off-chain confirmation-depth checks.
+    pub locked_slot: u64,
+    /// Part of amount reserved to reimburse the completing relayer.
+    pub relayer_fee: u64,
+    /// Per-chain index of the lock (see ChainLockCounter).
+    pub chain_index: u64,
+    pub event_seq: u64,
+}
+
+/// Trimmed BridgeIntent for pools with compact_events; fetch the BridgeLock for full data.
+#[event]
+pub struct BridgeIntentCompact {
+    pub pool: Pubkey,
+    pub nonce: u64,
+    /// BridgeLock::intent_hash of the created lock.
+    pub payload_hash: [u8; 32],
+    pub event_seq: u64,
 }
 
 #[event]
@@ -40,4 +136,491 @@ pub struct BridgeReverted {
     pub amount: u64,
     pub nonce: u64,
     pub timestamp: i64,
+    pub lifetime_released_volume: u64,
+    /// Amount still escrowed after this release (0 once fully released).
+    pub remaining_amount: u64,
+    pub reason: ReleaseReason,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BridgeCompleted {
+    pub pool: Pubkey,
+    pub bridge_lock: Pubkey,
+    pub amount: u64,
+    /// Part of amount paid as the relayer's destination gas reimbursement.
+    pub relayer_fee: u64,
+    pub nonce: u64,
+    pub destination_chain_id: u64,
+    pub timestamp: i64,
+    pub lifetime_released_volume: u64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolDraining {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+    pub stablecoin_mint: Pubkey,
+    pub admin: Pubkey,
+    /// Vault balance swept to the destination (value behind the dead LP shares).
+    pub residual: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+/// Emitted for both Config and Pool; `target` is the account whose admin is changing.
+#[event]
+pub struct AdminTransferProposed {
+    pub target: Pubkey,
+    pub current_admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct AdminTransferAccepted {
+    pub target: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RelayerUpdated {
+    pub config: Pubkey,
+    pub old_relayer: Pubkey,
+    pub new_relayer: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct AuthoritiesRotated {
+    pub config: Pubkey,
+    pub current_admin: Pubkey,
+    /// Proposed admin; takes over once it calls accept_config_admin.
+    pub pending_admin: Pubkey,
+    /// Sole relayer after the rotation.
+    pub new_relayer: Pubkey,
+    /// Size of the relayer set that was replaced.
+    pub previous_relayer_count: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RelayerAdded {
+    pub config: Pubkey,
+    pub relayer: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RelayerRemoved {
+    pub config: Pubkey,
+    pub relayer: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct InsuranceDeposited {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub insurance_balance: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct InsurancePaidOut {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub insurance_balance: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolPaused {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub reason: PauseReason,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct AutoPauseTriggered {
+    pub pool: Pubkey,
+    pub outflow_window_volume: u64,
+    pub max_outflow_per_window: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolResumed {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FeeRateProposed {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+    pub bridge_fee_bps: u16,
+    pub effective_at: i64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FeeRateApplied {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_deposit_fee_bps: u16,
+    pub new_deposit_fee_bps: u16,
+    pub old_withdraw_fee_bps: u16,
+    pub new_withdraw_fee_bps: u16,
+    pub old_bridge_fee_bps: u16,
+    pub new_bridge_fee_bps: u16,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FeeRateUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_deposit_fee_bps: u16,
+    pub new_deposit_fee_bps: u16,
+    pub old_withdraw_fee_bps: u16,
+    pub new_withdraw_fee_bps: u16,
+    pub old_bridge_fee_bps: u16,
+    pub new_bridge_fee_bps: u16,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BatchItemFailed {
+    pub pool: Pubkey,
+    pub index: u16,
+    pub reason_code: u16,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ProtocolPaused {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub reason: PauseReason,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ProtocolResumed {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct SupportedChainAdded {
+    pub chain_id: u64,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct SupportedChainRemoved {
+    pub chain_id: u64,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FeesCollected {
+    pub pool: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_fees_collected: u64,
+    pub timestamp: i64,
+    /// Share of the sweep routed to the insurance vault instead of destination.
+    pub insurance_amount: u64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BridgeFeeCollected {
+    pub pool: Pubkey,
+    pub sender: Pubkey,
+    pub fee_amount: u64,
+    /// Share of fee_amount routed to the pool fee vault.
+    pub protocol_amount: u64,
+    /// Share of fee_amount retained in the pool as LP yield.
+    pub lp_amount: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct WithdrawFeeCollected {
+    pub pool: Pubkey,
+    pub withdrawer: Pubkey,
+    pub fee_amount: u64,
+    /// Share of fee_amount routed to the pool fee vault.
+    pub protocol_amount: u64,
+    /// Share of fee_amount retained in the pool as LP yield.
+    pub lp_amount: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EmergencyModeEnabled {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EmergencyModeDisabled {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RateLimitsUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_max_lock_per_tx: u64,
+    pub new_max_lock_per_tx: u64,
+    pub old_lock_cooldown_seconds: u32,
+    pub new_lock_cooldown_seconds: u32,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MaxLockedLiquidityUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_max: u64,
+    pub new_max: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolCapUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_cap: u64,
+    pub new_cap: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ReleaseApproved {
+    pub pool: Pubkey,
+    pub bridge_lock: Pubkey,
+    pub relayer: Pubkey,
+    pub action: ApprovalAction,
+    pub amount: u64,
+    pub approval_count: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ReleaseThresholdUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_threshold: u8,
+    pub new_threshold: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MaxFeeBpsUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_max_fee_bps: u16,
+    pub new_max_fee_bps: u16,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct AllowTransferHookMintsUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_allowed: bool,
+    pub new_allowed: bool,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FeeTimelockUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_seconds: u32,
+    pub new_seconds: u32,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ForceReleased {
+    pub pool: Pubkey,
+    pub bridge_lock: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub timestamp: i64,
+    /// Always ForceReleaseTimeout; shared with BridgeReverted so indexers can match on it.
+    pub reason: ReleaseReason,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct StuckLockTimeoutUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_seconds: u32,
+    pub new_seconds: u32,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MinLockAgeUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_seconds: u32,
+    pub new_seconds: u32,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ProtocolFeeShareUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_share_bps: u16,
+    pub new_share_bps: u16,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RequireLiquidityBackingUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_required: bool,
+    pub new_required: bool,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RequireUnfreezableMintUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub old_required: bool,
+    pub new_required: bool,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct LpSupplyResynced {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_lp_supply: u64,
+    pub new_lp_supply: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct LpMintMigrated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct VaultReconciled {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    /// Vault balance in excess of total_liquidity.
+    pub surplus: u64,
+    /// True if the surplus was credited to LPs, false if swept to the fee vault.
+    pub credited_to_lps: bool,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct DustCollected {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    /// Stablecoin moved from the vault to the fee vault.
+    pub amount: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct CollectRoundingDustUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub old_enabled: bool,
+    pub new_enabled: bool,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+/// Emitted by migrate_pool, migrate_config, and migrate_bridge_lock; `account` is the account
+/// migrated.
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub admin: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
 }
@@ -8,20 +8,66 @@
 //! ## Security
 //! - Anchor account validation and constraints
 //! - Role-based access (admin, relayer)
-//! - Rate limits on lock_for_bridge (max_lock_per_tx, optional cooldown)
+//! - Rate limits on lock_for_bridge (max_lock_per_tx, optional cooldown). Limits are raw
+//!   stablecoin base units; use `state::to_base_units` to derive them from whole tokens,
+//!   since pools may hold 6- or 9-decimal mints.
 //! - Emergency pause at config and pool level
-//! - No re-entrancy (single CPI per instruction; no callback pattern)
+//! - No re-entrancy (no callback pattern), backed by a per-pool in_progress guard
+//!
+//! ## Rounding
+//! Every conversion rounds against the user by at most one unit: fees round up, while LP
+//! minted on deposit and stablecoin returned on withdraw round down. A deposit immediately
+//! followed by a full withdraw therefore never returns more than was deposited.
+//!
+//! ## Token programs
+//! Pools accept mints under the classic SPL Token program or Token-2022. The LP mint must
+//! live under the same program as the stablecoin. Inbound transfers credit the vault's
+//! balance delta, so transfer-fee mints never over-credit liquidity.
+//! Mints with the TransferHook extension are rejected unless the protocol admin opts in via
+//! set_allow_transfer_hook_mints, since a hook runs arbitrary code inside vault transfers.
+//! Wrapped-SOL pools also accept native lamports through deposit_liquidity_sol and
+//! lock_for_bridge_sol, and pay out lamports through withdraw_liquidity_sol.
+//!
+//! ## Clock reads
+//! Every `Clock::get` is a separate sysvar syscall. Each instruction reads the clock at most
+//! once and reuses the cached value for both stored timestamps and emitted events, so an
+//! account field and the event reporting it always agree. Helpers that need the time take it
+//! as a parameter instead of reading the clock again.
 
 pub mod errors;
 pub mod events;
 pub mod state;
+#[cfg(test)]
+mod tests;
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, TokenProgram, Transfer};
-use state::{BridgeLock, Config, Pool};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::solana_program::{ed25519_program, hash::hashv};
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_spl::metadata::{
+    self, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata,
+    UpdateMetadataAccountsV2,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, MintTo, SyncNative, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+use state::{
+    AddressFormat, ApprovalAction, BridgeLock, ChainInfo, ChainLockCounter, Config, LockParams,
+    LockReceipt, LockStatus, OperationKind, PauseReason, Pool, PoolTombstone, PoolView,
+    ReleaseReason, UserLockIndex, UserLockState, UserLpState, WithdrawalRequest,
+};
 
-use events::*;
 use errors::*;
+use events::*;
 
 declare_id!("Brdg1111111111111111111111111111111111111111");
 
@@ -31,12 +77,27 @@ pub mod stablecoin_bridge {
 
     /// Initialize global protocol config. Must be called once before any pool.
     /// Admin can update fee rates and pause; relayer can call release_locked_liquidity.
+    /// The given relayer becomes the first member of the relayer set.
     pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
         let config = &mut ctx.accounts.config;
         config.admin = ctx.accounts.admin.key();
-        config.relayer = ctx.accounts.relayer.key();
+        config.relayers = [Pubkey::default(); Config::MAX_RELAYERS];
+        config.relayers[0] = ctx.accounts.relayer.key();
+        config.relayer_count = 1;
         config.paused = false;
         config.bump = ctx.bumps.config;
+        config.pending_admin = Pubkey::default();
+        config.release_threshold = 0;
+        config.fee_timelock_seconds = 0;
+        config.stuck_lock_timeout_seconds = 0;
+        config.pause_reason = PauseReason::Unspecified;
+        config.require_unfreezable_mint = false;
+        config.protocol_fee_share_bps = 10_000;
+        config.event_seq = 0;
+        config.min_lock_age_seconds = 0;
+        config.max_fee_bps = Config::DEFAULT_MAX_FEE_BPS;
+        config.allow_transfer_hook_mints = false;
+        config.version = Config::CURRENT_VERSION;
         Ok(())
     }
 
@@ -51,14 +112,37 @@ pub mod stablecoin_bridge {
         fee_rate_bps: u16,
         max_lock_per_tx: u64,
         lock_cooldown_seconds: u32,
+        min_lock_amount: u64,
     ) -> Result<()> {
-        require!(fee_rate_bps <= 10000, BridgeError::InvalidFeeRate);
+        require!(
+            fee_rate_bps <= ctx.accounts.config.max_fee_bps,
+            BridgeError::FeeExceedsProtocolMax
+        );
+        // A mint issuer with a freeze authority could freeze the vault and brick the pool.
+        if ctx.accounts.config.require_unfreezable_mint {
+            require!(
+                ctx.accounts.stablecoin_mint.freeze_authority.is_none(),
+                BridgeError::FrozenAuthorityPresent
+            );
+        }
+        // A transfer hook runs an arbitrary program inside every vault transfer.
+        if !ctx.accounts.config.allow_transfer_hook_mints {
+            require!(
+                !has_transfer_hook(&ctx.accounts.stablecoin_mint.to_account_info())?
+                    && !has_transfer_hook(&ctx.accounts.lp_token_mint.to_account_info())?,
+                BridgeError::TransferHookNotAllowed
+            );
+        }
 
         let pool = &mut ctx.accounts.pool;
         pool.stablecoin_mint = ctx.accounts.stablecoin_mint.key();
         pool.vault = ctx.accounts.vault.key();
+        pool.fee_vault = ctx.accounts.fee_vault.key();
         pool.lp_token_mint = ctx.accounts.lp_token_mint.key();
         pool.fee_rate_bps = fee_rate_bps;
+        pool.deposit_fee_bps = fee_rate_bps;
+        pool.withdraw_fee_bps = 0;
+        pool.bridge_fee_bps = 0;
         pool.admin = ctx.accounts.admin.key();
         pool.config = ctx.accounts.config.key();
         pool.paused = false;
@@ -69,60 +153,162 @@ pub mod stablecoin_bridge {
         pool.total_liquidity = 0;
         pool.available_liquidity = 0;
         pool.locked_liquidity = 0;
+        pool.lp_supply = 0;
+        pool.draining = false;
+        pool.pending_admin = Pubkey::default();
+        pool.auto_revert_after_seconds = 0;
+        pool.paused_at = 0;
+        pool.auto_revert_pending = false;
+        pool.auto_revert_cutoff = 0;
+        pool.insurance_vault = Pubkey::default();
+        pool.insurance_balance = 0;
+        pool.max_lock_per_window = 0;
+        pool.lock_window_seconds = 0;
+        pool.window_start = 0;
+        pool.window_volume = 0;
+        pool.min_lock_amount = min_lock_amount;
+        pool.total_fees_collected = 0;
+        pool.emergency_mode = false;
+        pool.lifetime_locked_volume = 0;
+        pool.lifetime_released_volume = 0;
+        pool.max_total_liquidity = 0;
+        pool.min_lp_hold_seconds = 0;
+        pool.in_progress = false;
+        pool.pending_deposit_fee_bps = 0;
+        pool.pending_withdraw_fee_bps = 0;
+        pool.pending_bridge_fee_bps = 0;
+        pool.fee_effective_at = 0;
+        pool.outflow_window_seconds = 0;
+        pool.max_outflow_per_window = 0;
+        pool.outflow_window_start = 0;
+        pool.outflow_window_volume = 0;
+        pool.stablecoin_decimals = ctx.accounts.stablecoin_mint.decimals;
+        pool.lp_decimals = ctx.accounts.lp_token_mint.decimals;
+        pool.lifetime_yield_distributed = 0;
+        pool.last_yield_timestamp = 0;
+        pool.insurance_fee_bps = 0;
+        pool.compact_events = false;
+        pool.pause_reason = PauseReason::Unspecified;
+        pool.relayer_fee = 0;
+        pool.max_locked_liquidity = 0;
+        pool.queued_withdrawals = 0;
+        pool.require_liquidity_backing = false;
+        pool.reserved_liquidity = 0;
+        pool.event_seq = 0;
+        // A pool re-created after close_pool resumes the closed pool's counters.
+        let tombstone = &ctx.accounts.pool_tombstone;
+        if tombstone.owner == &crate::ID && !tombstone.data_is_empty() {
+            let tombstone = PoolTombstone::try_deserialize(&mut &tombstone.try_borrow_data()?[..])?;
+            pool.next_lock_nonce = tombstone.next_lock_nonce;
+            pool.event_seq = tombstone.event_seq;
+        }
+        pool.collect_rounding_dust = false;
+        pool.version = Pool::CURRENT_VERSION;
 
         Ok(())
     }
 
     /// Deposit stablecoins into the pool and receive LP tokens (proportional share).
-    /// First depositor gets 1:1 LP:stablecoin; subsequent deposits use (amount * total_lp_supply) / available_liquidity.
-    pub fn deposit_liquidity(ctx: Context<DepositLiquidity>, amount: u64) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        let config = &ctx.accounts.config;
-
-        require!(!config.paused, BridgeError::PoolPaused);
-        require!(!pool.paused, BridgeError::PoolPaused);
-        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
-
-        let fee_bps = pool.fee_rate_bps as u64;
-        let fee = (amount * fee_bps) / 10_000;
-        let amount_after_fee = amount.checked_sub(fee).ok_or(BridgeError::MathOverflow)?;
+    /// First depositor gets 1:1 LP:stablecoin minus Pool::MINIMUM_LIQUIDITY dead shares;
+    /// subsequent deposits use (amount * lp_supply) / available_liquidity.
+    /// The deposit fee is routed to the pool's fee vault; only the net amount is credited as liquidity.
+    /// Reverts with SlippageExceeded if fewer than min_lp_out LP tokens would be minted.
+    /// LP goes to user_lp_ata, which may belong to a beneficiary other than the depositor.
+    /// Ordering is validate, then move funds, then mint; any revert leaves no partial state.
+    pub fn deposit_liquidity(
+        ctx: Context<DepositLiquidity>,
+        amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
 
-        let total_lp_supply = ctx.accounts.lp_token_mint.supply;
-        let lp_tokens = if pool.available_liquidity == 0 {
-            amount_after_fee
-        } else {
-            total_lp_supply
-                .checked_mul(amount_after_fee)
-                .ok_or(BridgeError::MathOverflow)?
-                .checked_div(pool.available_liquidity)
-                .ok_or(BridgeError::MathOverflow)?
-        };
+        let pool = &ctx.accounts.pool;
+        let config = &ctx.accounts.config;
 
-        require!(lp_tokens > 0, BridgeError::ZeroLpAmount);
+        // Reject dust deposits (lp_tokens == 0) and certain slippage failures before any funds
+        // move. The estimate assumes the vault receives amount_after_fee in full, so it is an
+        // upper bound on the LP actually minted below.
+        let DepositAmounts {
+            fee,
+            amount_after_fee,
+            lp_tokens: estimated_lp_tokens,
+        } = validate_deposit(config, pool, ctx.accounts.lp_token_mint.supply, amount)?;
+        let (protocol_fee, lp_fee) = split_fee(config, fee)?;
+        require!(
+            estimated_lp_tokens >= min_lp_out,
+            BridgeError::SlippageExceeded
+        );
+        // Catch a frozen LP account up front rather than at the final mint.
+        require!(
+            !ctx.accounts.user_lp_ata.is_frozen(),
+            BridgeError::TokenAccountFrozen
+        );
+        let vault_balance_before = ctx.accounts.vault.amount;
 
-        // Transfer stablecoin from user to pool vault
-        let cpi_accounts = Transfer {
+        // Transfer net stablecoin from user to pool vault
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
             to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.depositor.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount_after_fee,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
 
-        // Mint LP tokens to depositor
-        let pool_bump = pool.bump;
-        let seeds = &[
-            b"pool",
-            pool.stablecoin_mint.as_ref(),
-            &[pool_bump],
-        ];
+        // Transfer-fee mints withhold part of a transfer; credit only what the vault received.
+        let credited = received_since(&mut ctx.accounts.vault, vault_balance_before)?;
+
+        // Transfer the protocol share of the fee from depositor to pool fee vault
+        if protocol_fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                protocol_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        // Transfer the LP share of the fee from depositor to pool vault; it is retained as LP yield
+        let lp_fee_credited = if lp_fee > 0 {
+            let vault_balance_before = ctx.accounts.vault.amount;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                lp_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+            received_since(&mut ctx.accounts.vault, vault_balance_before)?
+        } else {
+            0
+        };
+
+        let pool = &ctx.accounts.pool;
+        let (lp_tokens, dead_lp_shares) = lp_for_deposit(pool, credited)?;
+        require!(lp_tokens >= min_lp_out, BridgeError::SlippageExceeded);
+
+        // Mint LP tokens to the beneficiary
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
         let signer = &[&seeds[..]];
         let cpi_accounts = MintTo {
             mint: ctx.accounts.lp_token_mint.to_account_info(),
             to: ctx.accounts.user_lp_ata.to_account_info(),
-            authority: ctx.accounts.pool.to_account_info(),
+            authority: pool.to_account_info(),
         };
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 cpi_accounts,
@@ -131,439 +317,6117 @@ pub mod stablecoin_bridge {
             lp_tokens,
         )?;
 
+        let dust = if pool.collect_rounding_dust {
+            deposit_dust(pool, credited, lp_tokens)?
+        } else {
+            0
+        };
+        if dust > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                dust,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        // The depositor's LP is priced before the LP fee share lands, so that share accrues to
+        // every LP, the depositor included.
+        let added = credited
+            .checked_sub(dust)
+            .and_then(|kept| kept.checked_add(lp_fee_credited))
+            .ok_or(BridgeError::MathOverflow)?;
+        let pool = &mut ctx.accounts.pool;
         pool.total_liquidity = pool
             .total_liquidity
-            .checked_add(amount)
+            .checked_add(added)
             .ok_or(BridgeError::MathOverflow)?;
         pool.available_liquidity = pool
             .available_liquidity
-            .checked_add(amount)
+            .checked_add(added)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.lp_supply = pool
+            .lp_supply
+            .checked_add(lp_tokens)
+            .and_then(|supply| supply.checked_add(dead_lp_shares))
             .ok_or(BridgeError::MathOverflow)?;
 
+        let timestamp = Clock::get()?.unix_timestamp;
+        let user_lp_state = &mut ctx.accounts.user_lp_state;
+        if user_lp_state.pool == Pubkey::default() {
+            user_lp_state.pool = pool.key();
+            user_lp_state.user = ctx.accounts.user_lp_ata.owner;
+            user_lp_state.bump = ctx.bumps.user_lp_state;
+        }
+        // user_lp_ata has not been reloaded, so amount is the balance before this mint.
+        user_lp_state.record_deposit(ctx.accounts.user_lp_ata.amount, lp_tokens, timestamp);
+
         emit!(LiquidityDeposited {
             pool: pool.key(),
+            event_seq: pool.next_event_seq(),
             depositor: ctx.accounts.depositor.key(),
+            beneficiary: ctx.accounts.user_lp_ata.owner,
             stablecoin_amount: amount,
             lp_tokens_minted: lp_tokens,
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp,
         });
 
+        if dust > 0 {
+            emit!(DustCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                depositor: ctx.accounts.depositor.key(),
+                amount: dust,
+                timestamp,
+            });
+        }
+
+        if fee > 0 {
+            emit!(DepositFeeCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                payer: ctx.accounts.depositor.key(),
+                fee_amount: fee,
+                protocol_amount: protocol_fee,
+                lp_amount: lp_fee,
+                timestamp,
+            });
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
         Ok(())
     }
 
-    /// Withdraw stablecoins by burning LP tokens. Proportional share of available_liquidity.
-    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, lp_amount: u64) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
+    /// deposit_liquidity for a wrapped-SOL pool, funded with native lamports: wraps lamports
+    /// into the depositor's wSOL account first. Adds two CPIs (system transfer, sync_native)
+    /// ahead of the regular deposit; neither can call back into this program.
+    pub fn deposit_liquidity_sol(
+        ctx: Context<DepositLiquidity>,
+        lamports: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        require_native_mint(&ctx.accounts.pool)?;
+        wrap_sol(
+            ctx.accounts.depositor.to_account_info(),
+            ctx.accounts.user_stablecoin_ata.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            lamports,
+        )?;
+        deposit_liquidity(ctx, lamports, min_lp_out)
+    }
+
+    /// Deposit stablecoins and burn the resulting LP, permanently donating the liquidity to
+    /// existing LPs (raises per-LP value; can never be withdrawn). Minting and burning in the
+    /// same instruction nets to zero, so no LP is minted at all. Requires existing LP supply so
+    /// the donation cannot leave liquidity with no LP to claim it.
+    pub fn deposit_and_burn_lp(ctx: Context<DepositAndBurnLp>, amount: u64) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &ctx.accounts.pool;
         let config = &ctx.accounts.config;
 
-        require!(!config.paused, BridgeError::PoolPaused);
-        require!(!pool.paused, BridgeError::PoolPaused);
-        require!(lp_amount > 0, BridgeError::ZeroLpAmount);
+        require!(pool.lp_supply > 0, BridgeError::NoLpSupply);
+
+        let DepositAmounts {
+            fee,
+            amount_after_fee,
+            ..
+        } = validate_deposit(config, pool, ctx.accounts.lp_token_mint.supply, amount)?;
+        let (protocol_fee, lp_fee) = split_fee(config, fee)?;
+        let vault_balance_before = ctx.accounts.vault.amount;
+
+        // Transfer net stablecoin from donor to pool vault
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.donor.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            amount_after_fee,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
+
+        // Transfer-fee mints withhold part of a transfer; credit only what the vault received.
+        let credited = received_since(&mut ctx.accounts.vault, vault_balance_before)?;
+        let (lp_tokens, _) = lp_for_deposit(pool, credited)?;
+
+        // Transfer the protocol share of the fee from donor to pool fee vault
+        if protocol_fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.donor.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                protocol_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        // Transfer the LP share of the fee from donor to pool vault; it is retained as LP yield
+        let lp_fee_credited = if lp_fee > 0 {
+            let vault_balance_before = ctx.accounts.vault.amount;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.donor.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                lp_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+            received_since(&mut ctx.accounts.vault, vault_balance_before)?
+        } else {
+            0
+        };
+
+        let added = credited
+            .checked_add(lp_fee_credited)
+            .ok_or(BridgeError::MathOverflow)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_liquidity = pool
+            .total_liquidity
+            .checked_add(added)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.available_liquidity = pool
+            .available_liquidity
+            .checked_add(added)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(LiquidityLockedForever {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            donor: ctx.accounts.donor.key(),
+            stablecoin_amount: amount,
+            lp_tokens_burned: lp_tokens,
+            timestamp,
+        });
+
+        if fee > 0 {
+            emit!(DepositFeeCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                payer: ctx.accounts.donor.key(),
+                fee_amount: fee,
+                protocol_amount: protocol_fee,
+                lp_amount: lp_fee,
+                timestamp,
+            });
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Add external yield to the pool: stablecoin moves from the admin's token account into the
+    /// vault and is credited to available_liquidity without minting LP, raising the share
+    /// price for every LP. No fee is charged. Requires existing LP supply. Pool-admin-only.
+    pub fn distribute_yield(ctx: Context<DistributeYield>, amount: u64) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+        require!(ctx.accounts.pool.lp_supply > 0, BridgeError::NoLpSupply);
 
-        let total_lp_supply = ctx.accounts.lp_token_mint.supply;
-        require!(total_lp_supply > 0, BridgeError::MathOverflow);
+        // Transfer yield from admin to pool vault
+        let vault_balance_before = ctx.accounts.vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.source_stablecoin_ata.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
+        let credited = received_since(&mut ctx.accounts.vault, vault_balance_before)?;
 
-        let stablecoin_out = pool
+        let pool = &mut ctx.accounts.pool;
+        pool.total_liquidity = pool
+            .total_liquidity
+            .checked_add(credited)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.available_liquidity = pool
             .available_liquidity
-            .checked_mul(lp_amount)
-            .ok_or(BridgeError::MathOverflow)?
-            .checked_div(total_lp_supply)
+            .checked_add(credited)
             .ok_or(BridgeError::MathOverflow)?;
+        pool.lifetime_yield_distributed = pool.lifetime_yield_distributed.saturating_add(credited);
+        let last_yield_timestamp = pool.last_yield_timestamp;
+        let timestamp = Clock::get()?.unix_timestamp;
+        pool.last_yield_timestamp = timestamp;
+
+        emit!(YieldDistributed {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            source: ctx.accounts.admin.key(),
+            amount: credited,
+            share_price: pool.share_price(),
+            timestamp,
+            lifetime_yield_distributed: pool.lifetime_yield_distributed,
+            last_yield_timestamp,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Account for tokens sent straight to the vault (vault.amount above total_liquidity).
+    /// The surplus is either credited to LPs as yield (requires existing LP supply) or swept
+    /// to the fee vault. Admin-only.
+    pub fn reconcile_vault(ctx: Context<ReconcileVault>, credit_to_lps: bool) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &ctx.accounts.pool;
+        let surplus = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(pool.total_liquidity)
+            .ok_or(BridgeError::InvalidPoolState)?;
+        require!(surplus > 0, BridgeError::ZeroStablecoinAmount);
+
+        if credit_to_lps {
+            require!(pool.lp_supply > 0, BridgeError::NoLpSupply);
+        } else {
+            // Transfer surplus from vault to pool fee vault
+            let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                surplus,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        let pool = &mut ctx.accounts.pool;
+        if credit_to_lps {
+            pool.total_liquidity = pool
+                .total_liquidity
+                .checked_add(surplus)
+                .ok_or(BridgeError::MathOverflow)?;
+            pool.available_liquidity = pool
+                .available_liquidity
+                .checked_add(surplus)
+                .ok_or(BridgeError::MathOverflow)?;
+        }
+
+        emit!(VaultReconciled {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            surplus,
+            credited_to_lps: credit_to_lps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        require!(stablecoin_out > 0, BridgeError::ZeroStablecoinAmount);
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Withdraw stablecoins by burning LP tokens. Proportional share of the pool's LP value,
+    /// paid from available_liquidity (liquidity reserved to back pending locks is not redeemable,
+    /// and what queued withdrawals are owed is not LP value; see request_withdrawal).
+    /// The withdraw fee is taken from that share and routed to the pool's fee vault.
+    /// Reverts with SlippageExceeded if less than min_stablecoin_out (net of fee) would be
+    /// returned.
+    pub fn withdraw_liquidity(
+        ctx: Context<WithdrawLiquidity>,
+        lp_amount: u64,
+        min_stablecoin_out: u64,
+    ) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &ctx.accounts.pool;
+        let config = &ctx.accounts.config;
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let WithdrawAmounts {
+            stablecoin_out,
+            fee,
+            amount_after_fee,
+        } = validate_withdraw(
+            config,
+            pool,
+            Some(&ctx.accounts.user_lp_state),
+            ctx.accounts.lp_token_mint.supply,
+            lp_amount,
+            timestamp,
+        )?;
         require!(
-            stablecoin_out <= pool.available_liquidity,
-            BridgeError::InsufficientLiquidity
+            amount_after_fee >= min_stablecoin_out,
+            BridgeError::SlippageExceeded
         );
+        require!(
+            ctx.accounts.user_lp_ata.amount >= lp_amount,
+            BridgeError::InsufficientLpBalance
+        );
+        // The LP share of the fee never leaves the vault, so it stays with the remaining LPs.
+        let (protocol_fee, lp_fee) = split_fee(config, fee)?;
+        let removed = stablecoin_out
+            .checked_sub(lp_fee)
+            .ok_or(BridgeError::MathOverflow)?;
 
         // Burn LP tokens
-        let cpi_accounts = token::Burn {
+        let cpi_accounts = Burn {
             mint: ctx.accounts.lp_token_mint.to_account_info(),
             from: ctx.accounts.user_lp_ata.to_account_info(),
             authority: ctx.accounts.withdrawer.to_account_info(),
         };
-        token::burn(
+        token_interface::burn(
             CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
             lp_amount,
         )?;
 
         // Transfer stablecoin from vault to user
-        let pool_bump = pool.bump;
-        let seeds = &[
-            b"pool",
-            pool.stablecoin_mint.as_ref(),
-            &[pool_bump],
-        ];
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
         let signer = &[&seeds[..]];
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
             to: ctx.accounts.user_stablecoin_ata.to_account_info(),
-            authority: ctx.accounts.pool.to_account_info(),
+            authority: pool.to_account_info(),
         };
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 cpi_accounts,
                 signer,
             ),
-            stablecoin_out,
+            amount_after_fee,
+            ctx.accounts.stablecoin_mint.decimals,
         )?;
 
+        // Transfer the protocol share of the fee from vault to pool fee vault
+        if protocol_fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                protocol_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        let pool = &mut ctx.accounts.pool;
         pool.total_liquidity = pool
             .total_liquidity
-            .checked_sub(stablecoin_out)
+            .checked_sub(removed)
             .ok_or(BridgeError::MathOverflow)?;
         pool.available_liquidity = pool
             .available_liquidity
-            .checked_sub(stablecoin_out)
+            .checked_sub(removed)
             .ok_or(BridgeError::MathOverflow)?;
+        pool.lp_supply = pool
+            .lp_supply
+            .checked_sub(lp_amount)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        if record_outflow(pool, removed, timestamp)? {
+            emit!(AutoPauseTriggered {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                outflow_window_volume: pool.outflow_window_volume,
+                max_outflow_per_window: pool.max_outflow_per_window,
+                timestamp,
+            });
+        }
+
+        let user_lp_state = &mut ctx.accounts.user_lp_state;
+        if user_lp_state.pool == Pubkey::default() {
+            user_lp_state.pool = pool.key();
+            user_lp_state.user = ctx.accounts.withdrawer.key();
+            user_lp_state.bump = ctx.bumps.user_lp_state;
+        }
 
         emit!(LiquidityWithdrawn {
             pool: pool.key(),
+            event_seq: pool.next_event_seq(),
             withdrawer: ctx.accounts.withdrawer.key(),
-            stablecoin_amount: stablecoin_out,
+            stablecoin_amount: amount_after_fee,
             lp_tokens_burned: lp_amount,
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp,
+        });
+
+        if fee > 0 {
+            emit!(WithdrawFeeCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                withdrawer: ctx.accounts.withdrawer.key(),
+                fee_amount: fee,
+                protocol_amount: protocol_fee,
+                lp_amount: lp_fee,
+                timestamp,
+            });
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        // Outstanding locks must still be fully backed by vault tokens after the payout.
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.amount >= ctx.accounts.pool.locked_liquidity,
+            BridgeError::InvalidPoolState
+        );
+
+        Ok(())
+    }
+
+    /// withdraw_liquidity for a wrapped-SOL pool, paid out as native lamports: after the regular
+    /// withdraw, closes the withdrawer's wSOL account to the withdrawer, unwrapping its whole
+    /// balance. Adds one CPI (close_account) after the withdraw has released its guard.
+    pub fn withdraw_liquidity_sol(
+        ctx: Context<WithdrawLiquidity>,
+        lp_amount: u64,
+        min_stablecoin_out: u64,
+    ) -> Result<()> {
+        require_native_mint(&ctx.accounts.pool)?;
+        let wsol_account = ctx.accounts.user_stablecoin_ata.to_account_info();
+        let withdrawer = ctx.accounts.withdrawer.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        withdraw_liquidity(ctx, lp_amount, min_stablecoin_out)?;
+
+        let cpi_accounts = CloseAccount {
+            account: wsol_account,
+            destination: withdrawer.clone(),
+            authority: withdrawer,
+        };
+        token_interface::close_account(CpiContext::new(token_program, cpi_accounts))
+    }
+
+    /// Queue a withdrawal the pool cannot pay yet (withdraw_liquidity fails with
+    /// InsufficientLiquidity while LP value is reserved to back locks). Burns lp_amount now, at
+    /// the current share price and with the same lockup and fee as withdraw_liquidity, and
+    /// records what the owner is owed in a WithdrawalRequest; the LP share of the fee accrues to
+    /// the pool at once. The owed amount stops counting as LP value and is paid by
+    /// fulfill_withdrawal.
+    pub fn request_withdrawal(
+        ctx: Context<RequestWithdrawal>,
+        lp_amount: u64,
+        min_stablecoin_out: u64,
+    ) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &ctx.accounts.pool;
+        let config = &ctx.accounts.config;
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let WithdrawAmounts {
+            stablecoin_out,
+            fee,
+            amount_after_fee,
+        } = price_withdraw(
+            config,
+            pool,
+            Some(&ctx.accounts.user_lp_state),
+            ctx.accounts.lp_token_mint.supply,
+            lp_amount,
+            timestamp,
+        )?;
+        require!(
+            amount_after_fee >= min_stablecoin_out,
+            BridgeError::SlippageExceeded
+        );
+        require!(
+            ctx.accounts.user_lp_ata.amount >= lp_amount,
+            BridgeError::InsufficientLpBalance
+        );
+        let (protocol_fee, lp_fee) = split_fee(config, fee)?;
+        let owed = stablecoin_out
+            .checked_sub(lp_fee)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.lp_token_mint.to_account_info(),
+            from: ctx.accounts.user_lp_ata.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token_interface::burn(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            lp_amount,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.lp_supply = pool
+            .lp_supply
+            .checked_sub(lp_amount)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.queued_withdrawals = pool
+            .queued_withdrawals
+            .checked_add(owed)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        let request = &mut ctx.accounts.withdrawal_request;
+        request.pool = pool.key();
+        request.owner = ctx.accounts.owner.key();
+        request.lp_tokens_burned = lp_amount;
+        request.amount = amount_after_fee;
+        request.protocol_fee = protocol_fee;
+        request.requested_at = timestamp;
+        request.bump = ctx.bumps.withdrawal_request;
+
+        let user_lp_state = &mut ctx.accounts.user_lp_state;
+        if user_lp_state.pool == Pubkey::default() {
+            user_lp_state.pool = pool.key();
+            user_lp_state.user = ctx.accounts.owner.key();
+            user_lp_state.bump = ctx.bumps.user_lp_state;
+        }
+
+        emit!(WithdrawalRequested {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            owner: ctx.accounts.owner.key(),
+            lp_tokens_burned: lp_amount,
+            stablecoin_amount: amount_after_fee,
+            timestamp,
+        });
+
+        if fee > 0 {
+            emit!(WithdrawFeeCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                withdrawer: ctx.accounts.owner.key(),
+                fee_amount: fee,
+                protocol_amount: protocol_fee,
+                lp_amount: lp_fee,
+                timestamp,
+            });
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)
+    }
+
+    /// Pay out a queued WithdrawalRequest once available_liquidity covers it (e.g. after backed
+    /// locks complete or are reverted), then close the request to its owner. Permissionless, so
+    /// anyone may crank it; the payout always goes to the owner's token account.
+    pub fn fulfill_withdrawal(ctx: Context<FulfillWithdrawal>) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &ctx.accounts.pool;
+        let request = &ctx.accounts.withdrawal_request;
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(!ctx.accounts.config.paused, BridgeError::PoolPaused);
+        let owed = request.owed();
+        require!(
+            owed <= pool.available_liquidity,
+            BridgeError::InsufficientLiquidity
+        );
+
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.owner_stablecoin_ata.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            request.amount,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
+
+        if request.protocol_fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                request.protocol_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        let amount = request.amount;
+        let requested_at = request.requested_at;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_liquidity = pool
+            .total_liquidity
+            .checked_sub(owed)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.available_liquidity = pool
+            .available_liquidity
+            .checked_sub(owed)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.queued_withdrawals = pool
+            .queued_withdrawals
+            .checked_sub(owed)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        if record_outflow(pool, owed, timestamp)? {
+            emit!(AutoPauseTriggered {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                outflow_window_volume: pool.outflow_window_volume,
+                max_outflow_per_window: pool.max_outflow_per_window,
+                timestamp,
+            });
+        }
+
+        emit!(WithdrawalFulfilled {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            owner: ctx.accounts.owner.key(),
+            stablecoin_amount: amount,
+            requested_at,
+            timestamp,
         });
 
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        // Outstanding locks must still be fully backed by vault tokens after the payout.
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.amount >= ctx.accounts.pool.locked_liquidity,
+            BridgeError::InvalidPoolState
+        );
+
         Ok(())
     }
 
     /// Lock stablecoins for bridge: transfer from user to pool vault and record a BridgeLock.
-    /// Decreases effective available_liquidity (increases locked_liquidity). Relayer observes
-    /// BridgeIntent event and releases funds on destination; on failure, relayer calls
-    /// release_locked_liquidity.
+    /// The sender's funds are escrowed in locked_liquidity and never become LP liquidity, so
+    /// LP redemption value is unaffected by bridge flow. The bridge fee is taken from amount
+    /// and routed to the fee vault; only the net is escrowed and delivered. Relayer observes
+    /// BridgeIntent and releases funds on destination; on failure, relayer calls
+    /// release_locked_liquidity. Returns the created lock's PDA and nonce via return data.
+    /// A non-zero client_tag makes retries idempotent: reusing a tag within
+    /// UserLockState::TAG_WINDOW_SECONDS fails with DuplicateLock. An optional
+    /// recipient_checksum (see AddressFormat::checksum) guards against corrupted recipient bytes.
     pub fn lock_for_bridge(
         ctx: Context<LockForBridge>,
         amount: u64,
         destination_chain_id: u64,
         recipient_address: [u8; 32],
-    ) -> Result<()> {
+        client_tag: [u8; 16],
+        recipient_checksum: Option<u8>,
+    ) -> Result<LockReceipt> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
         let pool = &mut ctx.accounts.pool;
         let config = &ctx.accounts.config;
+        let clock = Clock::get()?;
 
-        require!(!config.paused, BridgeError::PoolPaused);
-        require!(!pool.paused, BridgeError::PoolPaused);
-        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
-        require!(
-            amount <= pool.max_lock_per_tx,
-            BridgeError::LockAmountExceedsLimit
-        );
-
-        // Cooldown: check last lock time for this user (we'd need a separate "last_lock" account per user;
-        // for MVP we skip per-user cooldown to avoid extra account or use clock).
-        // Spec said "rate limits"; we enforce max_lock_per_tx. Cooldown could be added via UserLockState account.
-        // Here we leave cooldown as pool-level and not per-user to avoid extra accounts.
+        let params = LockParams {
+            amount,
+            destination_chain_id,
+            recipient_address,
+            client_tag,
+            recipient_checksum,
+        };
+        let LockAmounts {
+            fee,
+            amount_after_fee,
+        } = validate_lock(
+            config,
+            pool,
+            Some(&ctx.accounts.user_lock_state),
+            Some(&ctx.accounts.chain_info),
+            &params,
+            clock.unix_timestamp,
+        )?;
 
         let nonce = pool.next_lock_nonce;
-        pool.next_lock_nonce = pool.next_lock_nonce.saturating_add(1);
+        pool.next_lock_nonce = next_nonce(nonce)?;
+
+        let (window_start, window_volume) = lock_window_after(pool, amount, clock.unix_timestamp)?;
+        pool.window_start = window_start;
+        pool.window_volume = window_volume;
 
-        // Transfer user stablecoin to pool vault
-        let cpi_accounts = Transfer {
+        let vault_balance_before = ctx.accounts.vault.amount;
+
+        // Transfer net user stablecoin to pool vault
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
             to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.sender.to_account_info(),
         };
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
-            amount,
+            amount_after_fee,
+            ctx.accounts.stablecoin_mint.decimals,
         )?;
 
-        let clock = Clock::get()?;
+        // Transfer-fee mints withhold part of a transfer; credit only what the vault received.
+        let credited = received_since(&mut ctx.accounts.vault, vault_balance_before)?;
+        require!(credited > 0, BridgeError::ZeroStablecoinAmount);
+        let relayer_fee = pool.relayer_fee;
+        require!(credited > relayer_fee, BridgeError::RelayerFeeExceedsAmount);
+
+        let (protocol_fee, lp_fee) = split_fee(config, fee)?;
+        // Transfer the protocol share of the bridge fee from user to pool fee vault
+        if protocol_fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                protocol_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        // Transfer the LP share of the bridge fee from user to pool vault as LP yield
+        let lp_fee_credited = if lp_fee > 0 {
+            let vault_balance_before = ctx.accounts.vault.amount;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                lp_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+            received_since(&mut ctx.accounts.vault, vault_balance_before)?
+        } else {
+            0
+        };
+
+        let user_lock_state = &mut ctx.accounts.user_lock_state;
+        if user_lock_state.pool == Pubkey::default() {
+            user_lock_state.pool = pool.key();
+            user_lock_state.user = ctx.accounts.sender.key();
+            user_lock_state.bump = ctx.bumps.user_lock_state;
+        }
+        user_lock_state.last_lock_at = clock.unix_timestamp;
+        user_lock_state.record_tag(client_tag, clock.unix_timestamp);
+
+        let user_lock_index = &mut ctx.accounts.user_lock_index;
+        if user_lock_index.pool == Pubkey::default() {
+            user_lock_index.pool = pool.key();
+            user_lock_index.user = ctx.accounts.sender.key();
+            user_lock_index.bump = ctx.bumps.user_lock_index;
+        }
+        user_lock_index.push(nonce);
+
+        let chain_lock_counter = &mut ctx.accounts.chain_lock_counter;
+        if chain_lock_counter.pool == Pubkey::default() {
+            chain_lock_counter.pool = pool.key();
+            chain_lock_counter.chain_id = destination_chain_id;
+            chain_lock_counter.bump = ctx.bumps.chain_lock_counter;
+        }
+        let chain_index = chain_lock_counter.next_index;
+        chain_lock_counter.next_index = next_nonce(chain_index)?;
+
         let bridge_lock = &mut ctx.accounts.bridge_lock;
         bridge_lock.pool = pool.key();
-        bridge_lock.amount = amount;
+        bridge_lock.amount = credited;
         bridge_lock.nonce = nonce;
         bridge_lock.destination_chain_id = destination_chain_id;
         bridge_lock.recipient_address = recipient_address;
         bridge_lock.sender = ctx.accounts.sender.key();
         bridge_lock.released = false;
         bridge_lock.locked_at = clock.unix_timestamp;
+        bridge_lock.completed = false;
+        bridge_lock.released_amount = 0;
+        bridge_lock.clear_approvals();
+        bridge_lock.locked_slot = clock.slot;
+        bridge_lock.relayer_fee = relayer_fee;
+        bridge_lock.client_tag = client_tag;
+        bridge_lock.chain_index = chain_index;
+        bridge_lock.backed = reserve_backing(pool, credited)?;
+        bridge_lock.version = BridgeLock::CURRENT_VERSION;
 
         pool.total_liquidity = pool
             .total_liquidity
-            .checked_add(amount)
+            .checked_add(credited)
             .ok_or(BridgeError::MathOverflow)?;
         pool.locked_liquidity = pool
             .locked_liquidity
-            .checked_add(amount)
+            .checked_add(credited)
             .ok_or(BridgeError::MathOverflow)?;
+        credit_lp_fee(pool, lp_fee_credited)?;
+        pool.lifetime_locked_volume = pool.lifetime_locked_volume.saturating_add(credited);
+
+        if fee > 0 {
+            emit!(BridgeFeeCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                sender: ctx.accounts.sender.key(),
+                fee_amount: fee,
+                protocol_amount: protocol_fee,
+                lp_amount: lp_fee,
+                timestamp: clock.unix_timestamp,
+            });
+        }
 
-        emit!(BridgeIntent {
+        let intent = BridgeIntent {
             pool: pool.key(),
+            event_seq: pool.next_event_seq(),
             sender: ctx.accounts.sender.key(),
-            amount,
+            amount: credited,
             destination_chain_id,
             recipient_address,
             nonce,
             timestamp: clock.unix_timestamp,
-        });
+            destination_chain_name: Some(ctx.accounts.chain_info.name),
+            gross_amount: amount,
+            lifetime_locked_volume: pool.lifetime_locked_volume,
+            locked_slot: clock.slot,
+            relayer_fee,
+            chain_index,
+        };
+        emit_bridge_intent(pool, &ctx.accounts.bridge_lock, intent);
 
-        Ok(())
-    }
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
 
-    /// Release a previously locked amount (bridge revert). Callable only by authorized relayer.
-    /// Marks BridgeLock as released and returns the amount to available_liquidity.
-    pub fn release_locked_liquidity(ctx: Context<ReleaseLockedLiquidity>) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        let bridge_lock = &mut ctx.accounts.bridge_lock;
+        Ok(LockReceipt {
+            bridge_lock: ctx.accounts.bridge_lock.key(),
+            nonce,
+        })
+    }
 
-        require!(bridge_lock.pool == pool.key(), BridgeError::InvalidBridgeLock);
-        require!(!bridge_lock.released, BridgeError::AlreadyReleased);
+    /// lock_for_bridge for a wrapped-SOL pool, funded with native lamports: wraps lamports
+    /// into the sender's wSOL account first. Adds two CPIs (system transfer, sync_native)
+    /// ahead of the regular lock; neither can call back into this program.
+    pub fn lock_for_bridge_sol(
+        ctx: Context<LockForBridge>,
+        lamports: u64,
+        destination_chain_id: u64,
+        recipient_address: [u8; 32],
+        client_tag: [u8; 16],
+        recipient_checksum: Option<u8>,
+    ) -> Result<LockReceipt> {
+        require_native_mint(&ctx.accounts.pool)?;
+        wrap_sol(
+            ctx.accounts.sender.to_account_info(),
+            ctx.accounts.user_stablecoin_ata.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            lamports,
+        )?;
+        lock_for_bridge(
+            ctx,
+            lamports,
+            destination_chain_id,
+            recipient_address,
+            client_tag,
+            recipient_checksum,
+        )
+    }
+
+    /// Create several bridge locks with consecutive nonces in one transaction. The batch's
+    /// gross total is checked against max_lock_per_tx and the lock window; each item is
+    /// otherwise validated like lock_for_bridge and emits its own BridgeIntent. Pass one
+    /// (BridgeLock PDA for the item's nonce, ChainInfo of its destination, ChainLockCounter of
+    /// its destination) triple per item in remaining_accounts; counters are created as needed.
+    /// Atomic: if item K fails, BatchItemFailed { index: K, .. } is logged.
+    pub fn lock_for_bridge_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LockForBridgeBatch<'info>>,
+        locks: Vec<LockParams>,
+    ) -> Result<Vec<LockReceipt>> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        require!(
+            !locks.is_empty() && locks.len() <= BridgeLock::MAX_BATCH_LOCKS,
+            BridgeError::InvalidBatchSize
+        );
+        require!(
+            ctx.remaining_accounts.len() == locks.len() * 3,
+            BridgeError::InvalidBatchSize
+        );
+
+        let clock = Clock::get()?;
+        let total_amount = locks
+            .iter()
+            .try_fold(0u64, |total, params| total.checked_add(params.amount))
+            .ok_or(BridgeError::MathOverflow)?;
+        let pool = &ctx.accounts.pool;
+        require!(
+            total_amount <= pool.max_lock_per_tx,
+            BridgeError::LockAmountExceedsLimit
+        );
+        let (window_start, window_volume) =
+            lock_window_after(pool, total_amount, clock.unix_timestamp)?;
+        if pool.max_lock_per_window > 0 {
+            require!(
+                window_volume <= pool.max_lock_per_window,
+                BridgeError::WindowLimitExceeded
+            );
+        }
+
+        let mut receipts = Vec::with_capacity(locks.len());
+        let mut total_fee: u64 = 0;
+        let item_accounts = ctx.remaining_accounts.chunks(3);
+        for (index, (params, item)) in locks.iter().zip(item_accounts).enumerate() {
+            match lock_batch_item(ctx.accounts, params, item, &clock) {
+                Ok((receipt, fee)) => {
+                    receipts.push(receipt);
+                    total_fee = total_fee
+                        .checked_add(fee)
+                        .ok_or(BridgeError::MathOverflow)?;
+                }
+                Err(err) => {
+                    emit!(BatchItemFailed {
+                        pool: ctx.accounts.pool.key(),
+                        event_seq: ctx.accounts.pool.next_event_seq(),
+                        index: index as u16,
+                        reason_code: error_code(&err),
+                    });
+                    return Err(err);
+                }
+            }
+        }
+
+        let (protocol_fee, lp_fee) = split_fee(&ctx.accounts.config, total_fee)?;
+        // Transfer the protocol share of the batch's bridge fees from user to pool fee vault
+        if protocol_fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                protocol_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        // Transfer the LP share of the batch's bridge fees from user to pool vault as LP yield
+        let lp_fee_credited = if lp_fee > 0 {
+            let vault_balance_before = ctx.accounts.vault.amount;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.user_stablecoin_ata.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                lp_fee,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+            received_since(&mut ctx.accounts.vault, vault_balance_before)?
+        } else {
+            0
+        };
+
+        let pool = &mut ctx.accounts.pool;
+        pool.window_start = window_start;
+        pool.window_volume = window_volume;
+        credit_lp_fee(pool, lp_fee_credited)?;
+
+        if total_fee > 0 {
+            emit!(BridgeFeeCollected {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                sender: ctx.accounts.sender.key(),
+                fee_amount: total_fee,
+                protocol_amount: protocol_fee,
+                lp_amount: lp_fee,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        let user_lock_state = &mut ctx.accounts.user_lock_state;
+        if user_lock_state.pool == Pubkey::default() {
+            user_lock_state.pool = pool.key();
+            user_lock_state.user = ctx.accounts.sender.key();
+            user_lock_state.bump = ctx.bumps.user_lock_state;
+        }
+        user_lock_state.last_lock_at = clock.unix_timestamp;
+
+        let user_lock_index = &mut ctx.accounts.user_lock_index;
+        if user_lock_index.pool == Pubkey::default() {
+            user_lock_index.pool = pool.key();
+            user_lock_index.user = ctx.accounts.sender.key();
+            user_lock_index.bump = ctx.bumps.user_lock_index;
+        }
+        for receipt in &receipts {
+            user_lock_index.push(receipt.nonce);
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(receipts)
+    }
+
+    /// Release a previously locked amount (bridge revert). Callable only by authorized relayer.
+    /// Refunds the lock's outstanding amount to the sender and marks BridgeLock as released.
+    pub fn release_locked_liquidity(ctx: Context<ReleaseLockedLiquidity>) -> Result<()> {
+        release_lock(ctx, None)
+    }
+
+    /// Last-resort liveness path when relayers are gone: refund a lock's outstanding amount
+    /// to the sender once it is config.stuck_lock_timeout_seconds old. Protocol-admin-only;
+    /// does not require relayer approvals.
+    pub fn force_release(ctx: Context<ForceRelease>) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &mut ctx.accounts.pool;
+        let bridge_lock = &mut ctx.accounts.bridge_lock;
+        let config = &ctx.accounts.config;
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        require!(
+            config.stuck_lock_timeout_seconds > 0
+                && timestamp.saturating_sub(bridge_lock.locked_at)
+                    >= i64::from(config.stuck_lock_timeout_seconds),
+            BridgeError::LockNotExpired
+        );
+
+        let amount = bridge_lock.outstanding_amount();
+        revert_lock(pool, bridge_lock, amount)?;
+        // Approvals pending for a relayer settlement no longer apply to a settled lock.
+        bridge_lock.clear_approvals();
+        refund_lock(
+            pool,
+            &ctx.accounts.stablecoin_mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.sender_stablecoin_ata.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(ForceReleased {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            bridge_lock: bridge_lock.key(),
+            admin: ctx.accounts.admin.key(),
+            amount,
+            nonce: bridge_lock.nonce,
+            timestamp,
+            reason: ReleaseReason::ForceReleaseTimeout,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Set the lock age after which force_release is allowed (0 disables it). Admin-only.
+    pub fn set_stuck_lock_timeout(ctx: Context<SetStuckLockTimeout>, seconds: u32) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_seconds = config.stuck_lock_timeout_seconds;
+        config.stuck_lock_timeout_seconds = seconds;
+
+        emit!(StuckLockTimeoutUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_seconds,
+            new_seconds: seconds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the minimum lock age before relayers may revert a lock (0 = none). force_release
+    /// is unaffected. Admin-only.
+    pub fn set_min_lock_age(ctx: Context<SetMinLockAge>, seconds: u32) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_seconds = config.min_lock_age_seconds;
+        config.min_lock_age_seconds = seconds;
+
+        emit!(MinLockAgeUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_seconds,
+            new_seconds: seconds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the share of every deposit, withdraw, and bridge fee (bps) routed to pool fee
+    /// vaults; the rest stays in the pool as LP yield. Admin-only.
+    pub fn set_protocol_fee_share(
+        ctx: Context<SetProtocolFeeShare>,
+        protocol_fee_share_bps: u16,
+    ) -> Result<()> {
+        require!(
+            protocol_fee_share_bps <= 10_000,
+            BridgeError::InvalidFeeRate
+        );
+        let config = &mut ctx.accounts.config;
+        let old_share_bps = config.protocol_fee_share_bps;
+        config.protocol_fee_share_bps = protocol_fee_share_bps;
+
+        emit!(ProtocolFeeShareUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_share_bps,
+            new_share_bps: protocol_fee_share_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Require (or stop requiring) that new pools' stablecoin mints have no freeze authority.
+    /// Existing pools are unaffected. Admin-only.
+    pub fn set_require_unfreezable_mint(
+        ctx: Context<SetRequireUnfreezableMint>,
+        required: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_required = config.require_unfreezable_mint;
+        config.require_unfreezable_mint = required;
+
+        emit!(RequireUnfreezableMintUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_required,
+            new_required: required,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Record the calling relayer's approval for one settlement of a lock: `action` moving
+    /// `amount`. Releases and complete_bridge are gated on config.release_threshold approvals
+    /// from currently authorized relayers for exactly the action and amount they perform.
+    /// Approvals for a different action or amount are rejected until the pending ones are used.
+    pub fn approve_release(
+        ctx: Context<ApproveRelease>,
+        action: ApprovalAction,
+        amount: u64,
+    ) -> Result<()> {
+        let bridge_lock = &mut ctx.accounts.bridge_lock;
+        let relayer = ctx.accounts.relayer.key();
+        require!(!bridge_lock.released, BridgeError::AlreadyReleased);
+        require!(!bridge_lock.completed, BridgeError::AlreadyCompleted);
+        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+        require!(
+            amount <= bridge_lock.outstanding_amount(),
+            BridgeError::ReleaseExceedsLocked
+        );
+
+        let count = bridge_lock.approval_count as usize;
+        if count == 0 {
+            bridge_lock.approved_action = action;
+            bridge_lock.approved_amount = amount;
+        }
+        require!(
+            bridge_lock.approved_action == action && bridge_lock.approved_amount == amount,
+            BridgeError::ApprovalMismatch
+        );
+        require!(
+            !bridge_lock.approvals[..count].contains(&relayer),
+            BridgeError::DuplicateApproval
+        );
+        require!(count < Config::MAX_RELAYERS, BridgeError::ApprovalsFull);
+        bridge_lock.approvals[count] = relayer;
+        bridge_lock.approval_count += 1;
+
+        emit!(ReleaseApproved {
+            pool: ctx.accounts.pool.key(),
+            event_seq: ctx.accounts.pool.next_event_seq(),
+            bridge_lock: bridge_lock.key(),
+            relayer,
+            action,
+            amount,
+            approval_count: bridge_lock.approval_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Revert part of a bridge (e.g. a partial fill on destination). Callable only by authorized
+    /// relayer. Refunds amount to the sender; the lock is marked released once partial releases
+    /// sum to its full amount.
+    pub fn release_partial(ctx: Context<ReleaseLockedLiquidity>, amount: u64) -> Result<()> {
+        release_lock(ctx, Some(amount))
+    }
+
+    /// Release (revert) a lock on the strength of an authorized relayer's Ed25519 signature over
+    /// attestation_message(pool, nonce, outstanding amount), so anyone can submit it on the
+    /// relayer's behalf.
+    /// The instruction immediately before this one must be an Ed25519 program instruction
+    /// verifying that signature. The attestation counts as the signer's approval towards
+    /// config.release_threshold. Refunds the outstanding amount to the sender.
+    pub fn release_with_attestation(
+        ctx: Context<ReleaseWithAttestation>,
+        signature: [u8; 64],
+    ) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let bridge_lock = &ctx.accounts.bridge_lock;
+        let amount = bridge_lock.outstanding_amount();
+        let message = attestation_message(&ctx.accounts.pool.key(), bridge_lock.nonce, amount);
+        let attester = verify_relayer_attestation(
+            &ctx.accounts.config,
+            &ctx.accounts.instructions,
+            &message,
+            &signature,
+        )?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        require_lock_age(&ctx.accounts.config, bridge_lock, timestamp)?;
+
+        let pool = &mut ctx.accounts.pool;
+        let bridge_lock = &mut ctx.accounts.bridge_lock;
+        use_approvals(
+            &ctx.accounts.config,
+            bridge_lock,
+            ApprovalAction::Release,
+            amount,
+            Some(&attester),
+        )?;
+        revert_lock(pool, bridge_lock, amount)?;
+        refund_lock(
+            pool,
+            &ctx.accounts.stablecoin_mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.sender_stablecoin_ata.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(BridgeReverted {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            bridge_lock: bridge_lock.key(),
+            amount,
+            nonce: bridge_lock.nonce,
+            timestamp,
+            lifetime_released_volume: pool.lifetime_released_volume,
+            remaining_amount: 0,
+            reason: ReleaseReason::RelayerRevert,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Settle a lock after the bridge succeeded on the destination chain. Callable only by
+    /// authorized relayer. Marks BridgeLock as completed, removes the outstanding amount from
+    /// pool liquidity, and pays it out of the vault to the relayer's payout account. Gated on
+    /// config.release_threshold approvals like releases, since this path pays escrow to a relayer.
+    /// The payout covers the net the relayer delivered plus the lock's reserved relayer_fee.
+    pub fn complete_bridge(ctx: Context<CompleteBridge>) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let bridge_lock = &ctx.accounts.bridge_lock;
+
+        require!(
+            bridge_lock.pool == ctx.accounts.pool.key(),
+            BridgeError::InvalidBridgeLock
+        );
+        require!(!bridge_lock.released, BridgeError::AlreadyReleased);
+        require!(!bridge_lock.completed, BridgeError::AlreadyCompleted);
+        // Completions count toward the outflow breaker, so a tripped pause must stop them too.
+        require!(
+            !ctx.accounts.config.paused && !ctx.accounts.pool.paused,
+            BridgeError::PoolPaused
+        );
 
-        let amount = bridge_lock.amount;
+        let amount = bridge_lock.outstanding_amount();
+        let relayer_fee = bridge_lock.relayer_fee.min(amount);
+        let backed = bridge_lock.backed;
+
+        // Transfer stablecoin from vault to relayer payout account
+        let pool = &ctx.accounts.pool;
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.relayer_payout.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            amount,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
 
-        bridge_lock.released = true;
+        let bridge_lock = &mut ctx.accounts.bridge_lock;
+        use_approvals(
+            &ctx.accounts.config,
+            bridge_lock,
+            ApprovalAction::Complete,
+            amount,
+            None,
+        )?;
+        bridge_lock.completed = true;
 
+        let pool = &mut ctx.accounts.pool;
         pool.locked_liquidity = pool
             .locked_liquidity
             .checked_sub(amount)
             .ok_or(BridgeError::MathOverflow)?;
-        pool.available_liquidity = pool
-            .available_liquidity
-            .checked_add(amount)
+        pool.total_liquidity = pool
+            .total_liquidity
+            .checked_sub(amount)
             .ok_or(BridgeError::MathOverflow)?;
+        if backed {
+            release_backing(pool, amount)?;
+        }
+        pool.lifetime_released_volume = pool.lifetime_released_volume.saturating_add(amount);
 
-        emit!(BridgeReverted {
+        let timestamp = Clock::get()?.unix_timestamp;
+        if record_outflow(pool, amount, timestamp)? {
+            emit!(AutoPauseTriggered {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                outflow_window_volume: pool.outflow_window_volume,
+                max_outflow_per_window: pool.max_outflow_per_window,
+                timestamp,
+            });
+        }
+
+        emit!(BridgeCompleted {
             pool: pool.key(),
+            event_seq: pool.next_event_seq(),
             bridge_lock: bridge_lock.key(),
             amount,
+            relayer_fee,
             nonce: bridge_lock.nonce,
+            destination_chain_id: bridge_lock.destination_chain_id,
+            timestamp,
+            lifetime_released_volume: pool.lifetime_released_volume,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Close a released or completed BridgeLock and refund its rent to the original sender.
+    /// Permissionless: the refund destination is fixed to bridge_lock.sender. Closed locks cannot
+    /// be re-created because their PDA seed nonce is never reused.
+    pub fn close_bridge_lock(_ctx: Context<CloseBridgeLock>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Propose new pool fee rates (bps). Increases wait config.fee_timelock_seconds and are
+    /// applied with apply_fee_rate; a proposal that raises no rate takes effect immediately and
+    /// cancels any pending increase. Admin-only.
+    pub fn propose_fee_rate(
+        ctx: Context<UpdateFeeRate>,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        bridge_fee_bps: u16,
+    ) -> Result<()> {
+        require_fees_within_max(
+            &ctx.accounts.config,
+            deposit_fee_bps,
+            withdraw_fee_bps,
+            bridge_fee_bps,
+        )?;
+        let pool = &mut ctx.accounts.pool;
+        let admin = ctx.accounts.admin.key();
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let increase = deposit_fee_bps > pool.deposit_fee_bps
+            || withdraw_fee_bps > pool.withdraw_fee_bps
+            || bridge_fee_bps > pool.bridge_fee_bps;
+        if !increase {
+            emit!(FeeRateUpdated {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                admin,
+                old_deposit_fee_bps: pool.deposit_fee_bps,
+                new_deposit_fee_bps: deposit_fee_bps,
+                old_withdraw_fee_bps: pool.withdraw_fee_bps,
+                new_withdraw_fee_bps: withdraw_fee_bps,
+                old_bridge_fee_bps: pool.bridge_fee_bps,
+                new_bridge_fee_bps: bridge_fee_bps,
+                timestamp,
+            });
+            pool.fee_effective_at = 0;
+            set_fee_rates(pool, deposit_fee_bps, withdraw_fee_bps, bridge_fee_bps);
+            return Ok(());
+        }
+
+        let effective_at = timestamp
+            .checked_add(ctx.accounts.config.fee_timelock_seconds as i64)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.pending_deposit_fee_bps = deposit_fee_bps;
+        pool.pending_withdraw_fee_bps = withdraw_fee_bps;
+        pool.pending_bridge_fee_bps = bridge_fee_bps;
+        pool.fee_effective_at = effective_at;
+
+        emit!(FeeRateProposed {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin,
+            deposit_fee_bps,
+            withdraw_fee_bps,
+            bridge_fee_bps,
+            effective_at,
+            timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Apply the pending fee-rate proposal once its timelock has elapsed. Admin-only.
+    pub fn apply_fee_rate(ctx: Context<UpdateFeeRate>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let admin = ctx.accounts.admin.key();
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(pool.fee_effective_at != 0, BridgeError::NoPendingFeeRate);
+        require!(
+            timestamp >= pool.fee_effective_at,
+            BridgeError::FeeTimelockActive
+        );
+
+        // The protocol maximum may have been lowered while the proposal waited.
+        require_fees_within_max(
+            &ctx.accounts.config,
+            pool.pending_deposit_fee_bps,
+            pool.pending_withdraw_fee_bps,
+            pool.pending_bridge_fee_bps,
+        )?;
+        emit!(FeeRateApplied {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin,
+            old_deposit_fee_bps: pool.deposit_fee_bps,
+            new_deposit_fee_bps: pool.pending_deposit_fee_bps,
+            old_withdraw_fee_bps: pool.withdraw_fee_bps,
+            new_withdraw_fee_bps: pool.pending_withdraw_fee_bps,
+            old_bridge_fee_bps: pool.bridge_fee_bps,
+            new_bridge_fee_bps: pool.pending_bridge_fee_bps,
+            timestamp,
+        });
+        pool.fee_effective_at = 0;
+        let (deposit_fee_bps, withdraw_fee_bps, bridge_fee_bps) = (
+            pool.pending_deposit_fee_bps,
+            pool.pending_withdraw_fee_bps,
+            pool.pending_bridge_fee_bps,
+        );
+        set_fee_rates(pool, deposit_fee_bps, withdraw_fee_bps, bridge_fee_bps);
+
+        Ok(())
+    }
+
+    /// Set the protocol maximum for pool fee rates, up to Config::MAX_FEE_BPS_CEILING.
+    /// Pools already above a lowered maximum keep their rates until their next fee change.
+    /// Admin-only.
+    pub fn set_max_fee_bps(ctx: Context<SetMaxFeeBps>, max_fee_bps: u16) -> Result<()> {
+        require!(
+            max_fee_bps <= Config::MAX_FEE_BPS_CEILING,
+            BridgeError::InvalidFeeRate
+        );
+        let config = &mut ctx.accounts.config;
+        let old_max_fee_bps = config.max_fee_bps;
+        config.max_fee_bps = max_fee_bps;
+
+        emit!(MaxFeeBpsUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_max_fee_bps,
+            new_max_fee_bps: max_fee_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Allow (or stop allowing) Token-2022 mints with the TransferHook extension in new pools
+    /// and LP mint migrations. Existing pools are unaffected. Admin-only.
+    pub fn set_allow_transfer_hook_mints(
+        ctx: Context<SetAllowTransferHookMints>,
+        allowed: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_allowed = config.allow_transfer_hook_mints;
+        config.allow_transfer_hook_mints = allowed;
+
+        emit!(AllowTransferHookMintsUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_allowed,
+            new_allowed: allowed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the delay fee-rate increases must wait before apply_fee_rate. Proposals already
+    /// pending keep their effective_at. Admin-only.
+    pub fn set_fee_timelock(ctx: Context<SetFeeTimelock>, seconds: u32) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_seconds = config.fee_timelock_seconds;
+        config.fee_timelock_seconds = seconds;
+
+        emit!(FeeTimelockUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_seconds,
+            new_seconds: seconds,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Update pool fee rate (basis points). Admin-only.
-    pub fn update_fee_rate(ctx: Context<UpdateFeeRate>, fee_rate_bps: u16) -> Result<()> {
-        require!(fee_rate_bps <= 10000, BridgeError::InvalidFeeRate);
-        ctx.accounts.pool.fee_rate_bps = fee_rate_bps;
-        Ok(())
-    }
+    /// Pause pool: no deposits, withdrawals, or lock_for_bridge. Admin-only (circuit breaker).
+    /// reason is stored on the pool and emitted for monitoring.
+    pub fn pause_pool(ctx: Context<PauseResumePool>, reason: PauseReason) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let timestamp = Clock::get()?.unix_timestamp;
+        pool.paused = true;
+        pool.paused_at = timestamp;
+        pool.auto_revert_pending = false;
+        pool.pause_reason = reason;
+
+        emit!(PoolPaused {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            reason,
+            timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Resume pool after pause. Admin-only.
+    /// If auto-revert is configured and the pause lasted at least auto_revert_after_seconds,
+    /// arms sweep_stale_locks for locks older than that threshold.
+    pub fn resume_pool(ctx: Context<PauseResumePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let now = Clock::get()?.unix_timestamp;
+        let was_paused = pool.paused;
+        pool.paused = false;
+        // Start a fresh outflow window so the circuit breaker does not re-trip immediately.
+        pool.outflow_window_start = now;
+        pool.outflow_window_volume = 0;
+
+        if was_paused && pool.auto_revert_after_seconds > 0 {
+            let threshold = i64::from(pool.auto_revert_after_seconds);
+            if now.saturating_sub(pool.paused_at) >= threshold {
+                pool.auto_revert_pending = true;
+                pool.auto_revert_cutoff = now.saturating_sub(threshold);
+            }
+        }
+
+        emit!(PoolResumed {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Enter emergency exit mode: LPs may withdraw pro-rata from available_liquidity even while
+    /// the pool is paused. Deposits and locks stay blocked. Admin-only.
+    pub fn enable_emergency_mode(ctx: Context<PauseResumePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.emergency_mode = true;
+
+        emit!(EmergencyModeEnabled {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Leave emergency exit mode; a paused pool blocks withdrawals again. Admin-only.
+    pub fn disable_emergency_mode(ctx: Context<PauseResumePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.emergency_mode = false;
+
+        emit!(EmergencyModeDisabled {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Protocol-wide circuit breaker: halts deposits, withdrawals, and locks on every pool.
+    /// Admin-only; reason is stored on the config and emitted for monitoring.
+    pub fn pause_protocol(ctx: Context<PauseResumeProtocol>, reason: PauseReason) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = true;
+        config.pause_reason = reason;
+
+        emit!(ProtocolPaused {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            reason,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Lift the protocol-wide pause. Pool-level pauses are left as they are. Admin-only.
+    pub fn resume_protocol(ctx: Context<PauseResumeProtocol>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = false;
+
+        emit!(ProtocolResumed {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Configure the auto-revert threshold (seconds; 0 = disabled). Admin-only.
+    pub fn set_auto_revert_after(ctx: Context<SetAutoRevertAfter>, seconds: u32) -> Result<()> {
+        ctx.accounts.pool.auto_revert_after_seconds = seconds;
+        Ok(())
+    }
+
+    /// Configure the rolling lock volume limit (max_lock_per_window = 0 disables it). Admin-only.
+    pub fn set_lock_window(
+        ctx: Context<SetLockWindow>,
+        max_lock_per_window: u64,
+        lock_window_seconds: u32,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.max_lock_per_window = max_lock_per_window;
+        pool.lock_window_seconds = lock_window_seconds;
+        Ok(())
+    }
+
+    /// Retune the per-lock rate limits set at initialize_pool. Applies from the next lock.
+    /// Admin-only.
+    pub fn update_rate_limits(
+        ctx: Context<UpdateRateLimits>,
+        max_lock_per_tx: u64,
+        lock_cooldown_seconds: u32,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let old_max_lock_per_tx = pool.max_lock_per_tx;
+        let old_lock_cooldown_seconds = pool.lock_cooldown_seconds;
+        pool.max_lock_per_tx = max_lock_per_tx;
+        pool.lock_cooldown_seconds = lock_cooldown_seconds;
+
+        emit!(RateLimitsUpdated {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_max_lock_per_tx,
+            new_max_lock_per_tx: max_lock_per_tx,
+            old_lock_cooldown_seconds,
+            new_lock_cooldown_seconds: lock_cooldown_seconds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cap the pool's total outstanding bridge escrow (locked_liquidity); 0 removes the cap.
+    /// Locks that would exceed it fail with LockedCapExceeded. Admin-only.
+    pub fn set_max_locked_liquidity(
+        ctx: Context<SetMaxLockedLiquidity>,
+        max_locked_liquidity: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let old_max = pool.max_locked_liquidity;
+        pool.max_locked_liquidity = max_locked_liquidity;
+
+        emit!(MaxLockedLiquidityUpdated {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_max,
+            new_max: max_locked_liquidity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Require locks to be backed by LP liquidity: a lock fails with InsufficientLiquidity
+    /// unless available_liquidity not owed to queued withdrawals covers its amount, and holds what it escrows in
+    /// reserved_liquidity until reverted or completed. Locks created before a change keep
+    /// their own setting. Admin-only.
+    pub fn set_require_liquidity_backing(
+        ctx: Context<SetRequireLiquidityBacking>,
+        require_liquidity_backing: bool,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let old_required = pool.require_liquidity_backing;
+        pool.require_liquidity_backing = require_liquidity_backing;
+
+        emit!(RequireLiquidityBackingUpdated {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_required,
+            new_required: require_liquidity_backing,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Configure the outflow circuit breaker: the pool auto-pauses once withdrawals plus
+    /// bridge completions within outflow_window_seconds exceed max_outflow_per_window
+    /// (0 disables it). Admin-only.
+    pub fn set_outflow_limit(
+        ctx: Context<SetOutflowLimit>,
+        max_outflow_per_window: u64,
+        outflow_window_seconds: u32,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.max_outflow_per_window = max_outflow_per_window;
+        pool.outflow_window_seconds = outflow_window_seconds;
+        Ok(())
+    }
+
+    /// Set the flat relayer_fee reserved from each new lock (0 = none). Existing locks keep
+    /// the fee they were created with. Admin-only.
+    pub fn set_relayer_fee(ctx: Context<SetRelayerFee>, relayer_fee: u64) -> Result<()> {
+        ctx.accounts.pool.relayer_fee = relayer_fee;
+        Ok(())
+    }
+
+    /// Switch lock events between the full BridgeIntent and BridgeIntentCompact. Admin-only.
+    pub fn set_compact_events(ctx: Context<SetCompactEvents>, compact_events: bool) -> Result<()> {
+        ctx.accounts.pool.compact_events = compact_events;
+        Ok(())
+    }
+
+    /// Route deposit rounding dust to fee_vault (DustCollected) instead of leaving it in the
+    /// pool for existing LPs. Admin-only.
+    pub fn set_collect_rounding_dust(
+        ctx: Context<SetCollectRoundingDust>,
+        collect_rounding_dust: bool,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let old_enabled = pool.collect_rounding_dust;
+        pool.collect_rounding_dust = collect_rounding_dust;
+
+        emit!(CollectRoundingDustUpdated {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_enabled,
+            new_enabled: collect_rounding_dust,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the smallest amount lock_for_bridge accepts (0 = no minimum). Admin-only.
+    pub fn set_min_lock_amount(ctx: Context<SetMinLockAmount>, min_lock_amount: u64) -> Result<()> {
+        ctx.accounts.pool.min_lock_amount = min_lock_amount;
+        Ok(())
+    }
+
+    /// Set the LP lockup: seconds after a deposit before the LP holder may withdraw
+    /// (0 = no lockup). Admin-only.
+    pub fn set_min_lp_hold(ctx: Context<SetMinLpHold>, min_lp_hold_seconds: u32) -> Result<()> {
+        ctx.accounts.pool.min_lp_hold_seconds = min_lp_hold_seconds;
+        Ok(())
+    }
+
+    /// Set the cap on total_liquidity enforced on deposits (0 = unlimited). Admin-only.
+    /// Lowering the cap below current liquidity only blocks new deposits.
+    pub fn set_pool_cap(ctx: Context<SetPoolCap>, cap: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let old_cap = pool.max_total_liquidity;
+        pool.max_total_liquidity = cap;
+
+        emit!(PoolCapUpdated {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_cap,
+            new_cap: cap,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Keeper sweep after an extended pause: reverts stale locks and refunds their senders.
+    /// Pass (BridgeLock, sender stablecoin token account) pairs of this pool, both writable, in
+    /// remaining_accounts, batched to fit compute limits. Permissionless; only locks at or
+    /// before auto_revert_cutoff are eligible. Pairs are processed in remaining_accounts order
+    /// and the batch is atomic: if pair K fails, BatchItemFailed { index: K, .. } is logged
+    /// and pairs 0..K are rolled back too.
+    pub fn sweep_stale_locks<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepStaleLocks<'info>>,
+    ) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.auto_revert_pending, BridgeError::AutoRevertNotActive);
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let vault = ctx.accounts.vault.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        for (index, pair) in ctx.remaining_accounts.chunks(2).enumerate() {
+            if let Err(err) = sweep_stale_lock(
+                pool,
+                &ctx.accounts.stablecoin_mint,
+                &vault,
+                &token_program,
+                pair,
+                timestamp,
+            ) {
+                emit!(BatchItemFailed {
+                    pool: pool.key(),
+                    event_seq: pool.next_event_seq(),
+                    index: index as u16,
+                    reason_code: error_code(&err),
+                });
+                return Err(err);
+            }
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Attach the pool's insurance vault (token account owned by the pool PDA). Admin-only;
+    /// can be set once. Each pool insures its own stablecoin, so the fund lives per pool.
+    pub fn initialize_insurance_vault(ctx: Context<InitializeInsuranceVault>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(
+            pool.insurance_vault == Pubkey::default(),
+            BridgeError::InvalidInsuranceVault
+        );
+        pool.insurance_vault = ctx.accounts.insurance_vault.key();
+        pool.insurance_balance = 0;
+        Ok(())
+    }
+
+    /// Add stablecoins to the pool's insurance fund. Permissionless.
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+
+        let insurance_balance_before = ctx.accounts.insurance_vault.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.funder_stablecoin_ata.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.insurance_vault.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
+        let credited = received_since(&mut ctx.accounts.insurance_vault, insurance_balance_before)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.insurance_balance = pool
+            .insurance_balance
+            .checked_add(credited)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        emit!(InsuranceDeposited {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            funder: ctx.accounts.funder.key(),
+            amount: credited,
+            insurance_balance: pool.insurance_balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+
+        Ok(())
+    }
+
+    /// Pay out of the insurance fund into the pool vault to make LPs whole after a loss.
+    /// Credited to available_liquidity without minting LP. Protocol-admin-only.
+    pub fn claim_insurance(ctx: Context<ClaimInsurance>, amount: u64) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let pool = &ctx.accounts.pool;
+        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+        require!(
+            amount <= pool.insurance_balance,
+            BridgeError::InsufficientInsurance
+        );
+
+        let vault_balance_before = ctx.accounts.vault.amount;
+
+        // Transfer stablecoin from insurance vault to pool vault
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.insurance_vault.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ),
+            amount,
+            ctx.accounts.stablecoin_mint.decimals,
+        )?;
+
+        // Transfer-fee mints withhold part of a transfer; credit only what the vault received.
+        let credited = received_since(&mut ctx.accounts.vault, vault_balance_before)?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.insurance_balance = pool
+            .insurance_balance
+            .checked_sub(amount)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.total_liquidity = pool
+            .total_liquidity
+            .checked_add(credited)
+            .ok_or(BridgeError::MathOverflow)?;
+        pool.available_liquidity = pool
+            .available_liquidity
+            .checked_add(credited)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        emit!(InsurancePaidOut {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            amount,
+            insurance_balance: pool.insurance_balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+        assert_pool_invariant(&ctx.accounts.pool)?;
+
+        Ok(())
+    }
+
+    /// Sweep accumulated protocol fees from the pool's fee vault, bounded by the fee vault
+    /// balance. insurance_fee_bps of the sweep goes to the pool's insurance vault and the rest
+    /// to an admin-chosen token account. Protocol-admin-only.
+    pub fn collect_fees(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+        require!(
+            amount <= ctx.accounts.fee_vault.amount,
+            BridgeError::InsufficientFeeBalance
+        );
+
+        let insurance_amount = fee_amount(amount, ctx.accounts.pool.insurance_fee_bps)?;
+        let destination_amount = amount
+            .checked_sub(insurance_amount)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        // Transfer stablecoin from fee vault to destination
+        let pool = &ctx.accounts.pool;
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        if destination_amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                destination_amount,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+
+        // Transfer the insurance share from fee vault to insurance vault
+        let mut insurance_credited = 0;
+        if insurance_amount > 0 {
+            let insurance_vault = ctx
+                .accounts
+                .insurance_vault
+                .as_mut()
+                .ok_or(BridgeError::InvalidInsuranceVault)?;
+            let insurance_balance_before = insurance_vault.amount;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: insurance_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                insurance_amount,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+            insurance_credited = received_since(insurance_vault, insurance_balance_before)?;
+        }
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_fees_collected = pool
+            .total_fees_collected
+            .checked_add(amount)
+            .ok_or(BridgeError::MathOverflow)?;
+
+        if insurance_credited > 0 {
+            pool.insurance_balance = pool
+                .insurance_balance
+                .checked_add(insurance_credited)
+                .ok_or(BridgeError::MathOverflow)?;
+            emit!(InsuranceDeposited {
+                pool: pool.key(),
+                event_seq: pool.next_event_seq(),
+                funder: ctx.accounts.fee_vault.key(),
+                amount: insurance_credited,
+                insurance_balance: pool.insurance_balance,
+                timestamp,
+            });
+        }
+
+        emit!(FeesCollected {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            destination: ctx.accounts.destination.key(),
+            amount: destination_amount,
+            total_fees_collected: pool.total_fees_collected,
+            timestamp,
+            insurance_amount,
+        });
+
+        release_guard(&mut ctx.accounts.pool);
+
+        Ok(())
+    }
+
+    /// Set the share of collected fees routed to the insurance vault (bps). Requires an
+    /// initialized insurance vault when non-zero. Admin-only.
+    pub fn set_insurance_fee(ctx: Context<SetInsuranceFee>, insurance_fee_bps: u16) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(insurance_fee_bps <= 10000, BridgeError::InvalidFeeRate);
+        require!(
+            insurance_fee_bps == 0 || pool.insurance_vault != Pubkey::default(),
+            BridgeError::InvalidInsuranceVault
+        );
+        pool.insurance_fee_bps = insurance_fee_bps;
+        Ok(())
+    }
+
+    /// Replace the pool's LP mint. Only allowed before any LP exists (lp_supply == 0); the new
+    /// mint must satisfy the same checks as at initialize_pool. Admin-only.
+    pub fn migrate_lp_mint(ctx: Context<MigrateLpMint>) -> Result<()> {
+        if !ctx.accounts.config.allow_transfer_hook_mints {
+            require!(
+                !has_transfer_hook(&ctx.accounts.new_lp_token_mint.to_account_info())?,
+                BridgeError::TransferHookNotAllowed
+            );
+        }
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.lp_supply == 0, BridgeError::LpSupplyOutstanding);
+
+        let old_mint = pool.lp_token_mint;
+        let new_mint = &ctx.accounts.new_lp_token_mint;
+        pool.lp_token_mint = new_mint.key();
+        pool.lp_decimals = new_mint.decimals;
+
+        emit!(LpMintMigrated {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_mint,
+            new_mint: new_mint.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Create or update the LP mint's Token Metadata so wallets can display it. The pool PDA is
+    /// mint and update authority; exactly one CPI is made, under the reentrancy guard.
+    /// Admin-only.
+    pub fn set_lp_metadata(
+        ctx: Context<SetLpMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        acquire_guard(&mut ctx.accounts.pool)?;
+
+        let data = DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        let pool = &ctx.accounts.pool;
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let metadata_program = ctx.accounts.token_metadata_program.to_account_info();
+        if ctx.accounts.metadata.data_is_empty() {
+            let cpi_accounts = CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.lp_token_mint.to_account_info(),
+                mint_authority: pool.to_account_info(),
+                payer: ctx.accounts.admin.to_account_info(),
+                update_authority: pool.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            };
+            metadata::create_metadata_accounts_v3(
+                CpiContext::new_with_signer(metadata_program, cpi_accounts, signer),
+                data,
+                true,
+                true,
+                None,
+            )?;
+        } else {
+            let cpi_accounts = UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: pool.to_account_info(),
+            };
+            metadata::update_metadata_accounts_v2(
+                CpiContext::new_with_signer(metadata_program, cpi_accounts, signer),
+                None,
+                Some(data),
+                None,
+                None,
+            )?;
+        }
+
+        release_guard(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// Recovery for an LP mint whose supply diverged from lp_supply (deposits and withdrawals
+    /// fail with InvalidPoolState until then): adopt the live mint supply, keeping the dead
+    /// shares. Only while the pool is paused, so the admin can investigate first. Admin-only.
+    pub fn resync_lp_supply(ctx: Context<ResyncLpSupply>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.paused, BridgeError::PoolNotPaused);
+
+        let old_lp_supply = pool.lp_supply;
+        let mint_supply = ctx.accounts.lp_token_mint.supply;
+        pool.lp_supply = if old_lp_supply == 0 && mint_supply == 0 {
+            0
+        } else {
+            mint_supply
+                .checked_add(Pool::MINIMUM_LIQUIDITY)
+                .ok_or(BridgeError::MathOverflow)?
+        };
+
+        emit!(LpSupplyResynced {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_lp_supply,
+            new_lp_supply: pool.lp_supply,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Begin retiring the pool: blocks deposits and lock_for_bridge while withdrawals and
+    /// release_locked_liquidity stay open so LPs and in-flight bridges can wind down.
+    /// One-way; admin-only.
+    pub fn begin_draining(ctx: Context<BeginDraining>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.draining = true;
+
+        emit!(PoolDraining {
+            pool: pool.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Bring a Pool created under an older layout up to Pool::CURRENT_VERSION: the account is
+    /// grown to the current size (rent topped up by the admin), fields it predates start at
+    /// zero unless restored below, and the version is bumped. A version-0 pool gets its fee
+    /// vault, LP supply, and split fee rates from the passed fee_vault, the LP mint, and
+    /// fee_rate_bps. Every other mutating instruction rejects a pool or config that is not at
+    /// the current version (AccountVersionMismatch). Admin-only.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let info = ctx.accounts.pool.to_account_info();
+        grow_account(
+            &info,
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            8 + Pool::LEN,
+        )?;
+
+        let mut pool = Pool::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            pool.admin == ctx.accounts.admin.key(),
+            BridgeError::UnauthorizedAdmin
+        );
+        require!(
+            pool.version < Pool::CURRENT_VERSION,
+            BridgeError::AlreadyMigrated
+        );
+        if pool.version == 0 {
+            require!(
+                ctx.accounts.lp_token_mint.key() == pool.lp_token_mint,
+                BridgeError::InvalidPoolState
+            );
+            // The recorded mint decimals predate versioning; a zeroed pair is read back from
+            // the mints.
+            if pool.stablecoin_decimals == 0 {
+                pool.stablecoin_decimals = ctx.accounts.stablecoin_mint.decimals;
+                pool.lp_decimals = ctx.accounts.lp_token_mint.decimals;
+            }
+            if pool.fee_vault == Pubkey::default() {
+                let fee_vault = &ctx.accounts.fee_vault;
+                require!(
+                    fee_vault.key() != pool.vault
+                        && fee_vault.mint == pool.stablecoin_mint
+                        && fee_vault.owner == info.key(),
+                    BridgeError::InvalidPoolState
+                );
+                require!(
+                    fee_vault.delegate.is_none() && fee_vault.close_authority.is_none(),
+                    BridgeError::VaultHasAuthority
+                );
+                pool.fee_vault = fee_vault.key();
+            }
+            // Older pools minted LP without dead shares; account for them so
+            // expected_mint_supply() matches the circulating LP.
+            let mint_supply = ctx.accounts.lp_token_mint.supply;
+            if pool.lp_supply == 0 && mint_supply > 0 {
+                pool.lp_supply = mint_supply
+                    .checked_add(Pool::MINIMUM_LIQUIDITY)
+                    .ok_or(BridgeError::MathOverflow)?;
+            }
+            // fee_rate_bps was the single rate for deposits, withdrawals, and bridging.
+            if pool.deposit_fee_bps == 0 && pool.withdraw_fee_bps == 0 && pool.bridge_fee_bps == 0 {
+                pool.deposit_fee_bps = pool.fee_rate_bps;
+                pool.withdraw_fee_bps = pool.fee_rate_bps;
+                pool.bridge_fee_bps = pool.fee_rate_bps;
+            }
+        }
+        let from_version = pool.version;
+        pool.version = Pool::CURRENT_VERSION;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            from_version,
+            to_version: pool.version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        pool.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    /// migrate_pool for the Config account. Protocol-admin-only.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let info = ctx.accounts.config.to_account_info();
+        // The original layout held a single relayer ahead of paused/bump, so it is not a
+        // prefix of the current one: read it before growing and rebuild the account.
+        let legacy = if info.data_len() == 8 + Config::LEGACY_LEN {
+            let data = info.try_borrow_data()?;
+            require!(
+                data[..8] == <Config as anchor_lang::Discriminator>::DISCRIMINATOR,
+                BridgeError::InvalidPoolState
+            );
+            Some((
+                Pubkey::try_from(&data[8..40]).map_err(|_| BridgeError::InvalidPoolState)?,
+                Pubkey::try_from(&data[40..72]).map_err(|_| BridgeError::InvalidPoolState)?,
+                data[72] != 0,
+                data[73],
+            ))
+        } else {
+            None
+        };
+        grow_account(
+            &info,
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            8 + Config::LEN,
+        )?;
+
+        let mut config = Config::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        if let Some((admin, relayer, paused, bump)) = legacy {
+            config = Config {
+                admin,
+                paused,
+                bump,
+                ..Config::default()
+            };
+            config.relayers[0] = relayer;
+            config.relayer_count = 1;
+        }
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            BridgeError::UnauthorizedAdmin
+        );
+        require!(
+            config.version < Config::CURRENT_VERSION,
+            BridgeError::AlreadyMigrated
+        );
+        // protocol_fee_share_bps predates versioning but a zeroed value would send every fee
+        // to LPs; restore the initialize_config default.
+        if config.version == 0 && config.protocol_fee_share_bps == 0 {
+            config.protocol_fee_share_bps = 10_000;
+        }
+        if config.version < 2 {
+            config.max_fee_bps = Config::DEFAULT_MAX_FEE_BPS;
+        }
+        let from_version = config.version;
+        config.version = Config::CURRENT_VERSION;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            from_version,
+            to_version: config.version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    /// Bring a BridgeLock created under an older layout up to BridgeLock::CURRENT_VERSION, like
+    /// migrate_pool: the account is grown (rent topped up by the admin) and the fields it
+    /// predates are defaulted. A lock released before partial releases existed counts as
+    /// released in full. Until then no instruction can read the lock. Pool-admin-only.
+    pub fn migrate_bridge_lock(ctx: Context<MigrateBridgeLock>) -> Result<()> {
+        let info = ctx.accounts.bridge_lock.to_account_info();
+        grow_account(
+            &info,
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            8 + BridgeLock::LEN,
+        )?;
+
+        let mut bridge_lock = BridgeLock::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let pool = &mut ctx.accounts.pool;
+        require!(
+            bridge_lock.pool == pool.key(),
+            BridgeError::InvalidBridgeLock
+        );
+        require!(
+            bridge_lock.version < BridgeLock::CURRENT_VERSION,
+            BridgeError::AlreadyMigrated
+        );
+        if bridge_lock.version == 0 && bridge_lock.released && bridge_lock.released_amount == 0 {
+            bridge_lock.released_amount = bridge_lock.amount;
+        }
+        let from_version = bridge_lock.version;
+        bridge_lock.version = BridgeLock::CURRENT_VERSION;
+
+        emit!(AccountMigrated {
+            account: info.key(),
+            event_seq: pool.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            from_version,
+            to_version: bridge_lock.version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        bridge_lock.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    /// Close a drained pool, refunding the rent of the Pool account, vault, and fee vault to
+    /// the admin. Requires no circulating LP, nothing locked, reserved, or insured, and an
+    /// empty fee vault (collect_fees first). Whatever is left in the vault backs only the
+    /// MINIMUM_LIQUIDITY dead shares (plus any unreconciled surplus) and is swept to
+    /// destination. The pool's lock nonce and event_seq are kept in a PoolTombstone, so a
+    /// pool re-created for the same mint never reuses a nonce. Admin-only.
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        require!(
+            pool.lp_supply == 0 || pool.lp_supply == Pool::MINIMUM_LIQUIDITY,
+            BridgeError::LpSupplyOutstanding
+        );
+        require!(
+            ctx.accounts.lp_token_mint.supply == 0,
+            BridgeError::LpSupplyOutstanding
+        );
+        require!(
+            pool.locked_liquidity == 0
+                && pool.reserved_liquidity == 0
+                && pool.queued_withdrawals == 0
+                && pool.insurance_balance == 0
+                && ctx.accounts.fee_vault.amount == 0,
+            BridgeError::PoolNotEmpty
+        );
+
+        let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let residual = ctx.accounts.vault.amount;
+        if residual > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.stablecoin_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                ),
+                residual,
+                ctx.accounts.stablecoin_mint.decimals,
+            )?;
+        }
+        for account in [&ctx.accounts.vault, &ctx.accounts.fee_vault] {
+            let cpi_accounts = CloseAccount {
+                account: account.to_account_info(),
+                destination: ctx.accounts.admin.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            token_interface::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ))?;
+        }
+
+        let pool = &mut ctx.accounts.pool;
+        let event_seq = pool.next_event_seq();
+        let tombstone = &mut ctx.accounts.pool_tombstone;
+        tombstone.stablecoin_mint = pool.stablecoin_mint;
+        tombstone.next_lock_nonce = pool.next_lock_nonce;
+        tombstone.event_seq = pool.event_seq;
+        tombstone.bump = ctx.bumps.pool_tombstone;
+
+        emit!(PoolClosed {
+            pool: pool.key(),
+            event_seq,
+            stablecoin_mint: pool.stablecoin_mint,
+            admin: ctx.accounts.admin.key(),
+            residual,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new protocol admin (step 1 of 2). Admin-only. Proposing the default pubkey
+    /// cancels a pending transfer.
+    pub fn propose_config_admin(ctx: Context<ProposeConfigAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = new_admin;
+
+        emit!(AdminTransferProposed {
+            target: config.key(),
+            event_seq: config.next_event_seq(),
+            current_admin: config.admin,
+            pending_admin: new_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept the protocol admin role (step 2 of 2). Must be signed by the pending admin.
+    pub fn accept_config_admin(ctx: Context<AcceptConfigAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_admin = config.admin;
+        config.admin = config.pending_admin;
+        config.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferAccepted {
+            target: config.key(),
+            event_seq: config.next_event_seq(),
+            previous_admin,
+            new_admin: config.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Rotate one relayer in place: replaces old_relayer with new_relayer. Admin-only.
+    /// Takes effect immediately: the old key can no longer release or complete locks, including
+    /// locks created before the rotation (authorization is checked at release time, not lock
+    /// time), while every other relayer keeps serving in-flight locks.
+    pub fn set_relayer(
+        ctx: Context<ManageRelayers>,
+        old_relayer: Pubkey,
+        new_relayer: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            !config.is_relayer(&new_relayer),
+            BridgeError::RelayerAlreadyAuthorized
+        );
+        let index = config
+            .active_relayers()
+            .iter()
+            .position(|relayer| *relayer == old_relayer)
+            .ok_or(BridgeError::RelayerNotFound)?;
+        config.relayers[index] = new_relayer;
+
+        emit!(RelayerUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            old_relayer,
+            new_relayer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Protocol handover in one transaction: proposes new_admin (who still has to call
+    /// accept_config_admin) and immediately replaces the whole relayer set with new_relayer.
+    /// A release threshold above one is lowered to fit the single relayer. Admin-only.
+    pub fn rotate_authorities(
+        ctx: Context<ManageRelayers>,
+        new_admin: Pubkey,
+        new_relayer: Pubkey,
+    ) -> Result<()> {
+        require!(
+            new_relayer != Pubkey::default(),
+            BridgeError::UnauthorizedRelayer
+        );
+        let config = &mut ctx.accounts.config;
+        let previous_relayer_count = config.relayer_count;
+        config.pending_admin = new_admin;
+        config.relayers = [Pubkey::default(); Config::MAX_RELAYERS];
+        config.relayers[0] = new_relayer;
+        config.relayer_count = 1;
+        config.release_threshold = config.release_threshold.min(1);
+
+        emit!(AuthoritiesRotated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            current_admin: config.admin,
+            pending_admin: new_admin,
+            new_relayer,
+            previous_relayer_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Add a relayer to the authorized set. Admin-only.
+    pub fn add_relayer(ctx: Context<ManageRelayers>, relayer: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            !config.is_relayer(&relayer),
+            BridgeError::RelayerAlreadyAuthorized
+        );
+        let count = config.relayer_count as usize;
+        require!(count < Config::MAX_RELAYERS, BridgeError::RelayerSetFull);
+        config.relayers[count] = relayer;
+        config.relayer_count += 1;
+
+        emit!(RelayerAdded {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            relayer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a relayer from the authorized set. Admin-only. Locks are not bound to the
+    /// relayer that observed them, so remaining relayers can still release or complete them;
+    /// the last relayer cannot be removed so pending locks are never left without one, and the
+    /// set cannot shrink below config.release_threshold.
+    pub fn remove_relayer(ctx: Context<ManageRelayers>, relayer: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let index = config
+            .active_relayers()
+            .iter()
+            .position(|r| *r == relayer)
+            .ok_or(BridgeError::RelayerNotFound)?;
+        require!(
+            config.relayer_count > 1,
+            BridgeError::CannotRemoveLastRelayer
+        );
+        require!(
+            config.relayer_count > config.release_threshold,
+            BridgeError::InvalidReleaseThreshold
+        );
+        let last = config.relayer_count as usize - 1;
+        config.relayers[index] = config.relayers[last];
+        config.relayers[last] = Pubkey::default();
+        config.relayer_count -= 1;
+
+        emit!(RelayerRemoved {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            relayer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set how many relayer approvals a lock needs before release or completion (M of N; 0 =
+    /// none). At most relayer_count, which remove_relayer then cannot go below. Admin-only.
+    pub fn set_release_threshold(ctx: Context<SetReleaseThreshold>, threshold: u8) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            threshold <= config.relayer_count,
+            BridgeError::InvalidReleaseThreshold
+        );
+        let old_threshold = config.release_threshold;
+        config.release_threshold = threshold;
+
+        emit!(ReleaseThresholdUpdated {
+            config: config.key(),
+            event_seq: config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            old_threshold,
+            new_threshold: threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new pool admin (step 1 of 2). Pool-admin-only. Proposing the default pubkey
+    /// cancels a pending transfer.
+    pub fn propose_pool_admin(ctx: Context<ProposePoolAdmin>, new_admin: Pubkey) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.pending_admin = new_admin;
+
+        emit!(AdminTransferProposed {
+            target: pool.key(),
+            event_seq: pool.next_event_seq(),
+            current_admin: pool.admin,
+            pending_admin: new_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept the pool admin role (step 2 of 2). Must be signed by the pending admin.
+    pub fn accept_pool_admin(ctx: Context<AcceptPoolAdmin>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let previous_admin = pool.admin;
+        pool.admin = pool.pending_admin;
+        pool.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferAccepted {
+            target: pool.key(),
+            event_seq: pool.next_event_seq(),
+            previous_admin,
+            new_admin: pool.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Register or update the short name for a destination chain. Admin-only.
+    /// Names are included in BridgeIntent events so indexers need no chain-id lookup table.
+    pub fn set_chain_name(ctx: Context<SetChainName>, chain_id: u64, name: [u8; 16]) -> Result<()> {
+        let chain_info = &mut ctx.accounts.chain_info;
+        chain_info.chain_id = chain_id;
+        chain_info.name = name;
+        chain_info.bump = ctx.bumps.chain_info;
+        Ok(())
+    }
+
+    /// Register how a destination chain encodes recipient addresses; lock_for_bridge rejects
+    /// recipients that do not fit the format with InvalidRecipient. Admin-only.
+    pub fn set_chain_address_format(
+        ctx: Context<SetChainAddressFormat>,
+        chain_id: u64,
+        address_format: AddressFormat,
+    ) -> Result<()> {
+        let chain_info = &mut ctx.accounts.chain_info;
+        chain_info.chain_id = chain_id;
+        chain_info.address_format = address_format;
+        chain_info.bump = ctx.bumps.chain_info;
+        Ok(())
+    }
+
+    /// Allow locks toward a destination chain. Admin-only.
+    pub fn add_supported_chain(ctx: Context<AddSupportedChain>, chain_id: u64) -> Result<()> {
+        let chain_info = &mut ctx.accounts.chain_info;
+        chain_info.chain_id = chain_id;
+        chain_info.bump = ctx.bumps.chain_info;
+        chain_info.supported = true;
+
+        emit!(SupportedChainAdded {
+            chain_id,
+            event_seq: ctx.accounts.config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Stop accepting new locks toward a destination chain. Pending locks are unaffected.
+    /// Admin-only.
+    pub fn remove_supported_chain(ctx: Context<RemoveSupportedChain>, chain_id: u64) -> Result<()> {
+        ctx.accounts.chain_info.supported = false;
+
+        emit!(SupportedChainRemoved {
+            chain_id,
+            event_seq: ctx.accounts.config.next_event_seq(),
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Exempt a user (e.g. a market maker) from the pool's lock cooldown. Admin-only.
+    /// max_lock_per_tx and other limits still apply.
+    pub fn grant_cooldown_exemption(ctx: Context<SetCooldownExemption>) -> Result<()> {
+        set_cooldown_exempt(ctx, true)
+    }
+
+    /// Remove a user's lock cooldown exemption. Admin-only.
+    pub fn revoke_cooldown_exemption(ctx: Context<SetCooldownExemption>) -> Result<()> {
+        set_cooldown_exempt(ctx, false)
+    }
+
+    /// Fee rate (bps) that would apply to the given operation right now. Read-only; returned via
+    /// return data. Uses the same fee selection as the mutating instructions.
+    pub fn effective_fee(ctx: Context<EffectiveFee>, op_kind: OperationKind) -> Result<u16> {
+        Ok(fee_bps_for(&ctx.accounts.pool, op_kind))
+    }
+
+    /// Pre-flight check for deposit_liquidity. Read-only; returns (via return data) 0 if the
+    /// deposit would pass validation, otherwise the error code it would fail with.
+    pub fn check_deposit(ctx: Context<CheckDeposit>, amount: u64) -> Result<u16> {
+        let result = validate_deposit(
+            &ctx.accounts.config,
+            &ctx.accounts.pool,
+            ctx.accounts.lp_token_mint.supply,
+            amount,
+        );
+        Ok(error_code_of(result.map(|_| ())))
+    }
+
+    /// LP tokens deposit_liquidity would mint for amount right now. Read-only; returned via
+    /// return data. Uses the same validation and share math as deposit_liquidity.
+    pub fn quote_deposit(ctx: Context<QuoteLiquidity>, amount: u64) -> Result<u64> {
+        let amounts = validate_deposit(
+            &ctx.accounts.config,
+            &ctx.accounts.pool,
+            ctx.accounts.lp_token_mint.supply,
+            amount,
+        )?;
+        Ok(amounts.lp_tokens)
+    }
+
+    /// Stablecoins withdraw_liquidity would return (net of fee) for lp_amount right now.
+    /// Read-only; returned via return data. Uses the same validation and share math as
+    /// withdraw_liquidity.
+    pub fn quote_withdraw(ctx: Context<QuoteLiquidity>, lp_amount: u64) -> Result<u64> {
+        let amounts = validate_withdraw(
+            &ctx.accounts.config,
+            &ctx.accounts.pool,
+            None,
+            ctx.accounts.lp_token_mint.supply,
+            lp_amount,
+            Clock::get()?.unix_timestamp,
+        )?;
+        Ok(amounts.amount_after_fee)
+    }
+
+    /// Stablecoin per LP unit scaled by Pool::SHARE_PRICE_SCALE (1e9), the rate deposits and
+    /// withdrawals are priced at; SHARE_PRICE_SCALE for a pool with no LP supply. Read-only;
+    /// returned via return data.
+    pub fn get_share_price(ctx: Context<GetPoolState>) -> Result<u128> {
+        Ok(ctx.accounts.pool.share_price_u128())
+    }
+
+    /// Settlement status of a BridgeLock. Read-only; returned via return data.
+    pub fn get_lock_status(ctx: Context<GetLockStatus>) -> Result<LockStatus> {
+        Ok(ctx.accounts.bridge_lock.status())
+    }
+
+    /// Liquidity, share price, utilization, and pause flags of a pool. Read-only; returned via
+    /// return data.
+    pub fn get_pool_state(ctx: Context<GetPoolState>) -> Result<PoolView> {
+        let pool = &ctx.accounts.pool;
+        Ok(PoolView {
+            total_liquidity: pool.total_liquidity,
+            available_liquidity: pool.available_liquidity,
+            reserved_liquidity: pool.reserved_liquidity,
+            locked_liquidity: pool.locked_liquidity,
+            queued_withdrawals: pool.queued_withdrawals,
+            lp_supply: pool.lp_supply,
+            share_price: pool.share_price(),
+            utilization_bps: pool.utilization_bps(),
+            paused: pool.paused,
+            pause_reason: pool.pause_reason,
+            protocol_paused: ctx.accounts.config.paused,
+            emergency_mode: pool.emergency_mode,
+            draining: pool.draining,
+        })
+    }
+
+    /// Pre-flight check for lock_for_bridge. Read-only; returns (via return data) 0 if the
+    /// lock would pass validation, otherwise the error code it would fail with.
+    pub fn check_lock(
+        ctx: Context<CheckLock>,
+        amount: u64,
+        destination_chain_id: u64,
+        recipient_address: [u8; 32],
+        client_tag: [u8; 16],
+        recipient_checksum: Option<u8>,
+    ) -> Result<u16> {
+        let params = LockParams {
+            amount,
+            destination_chain_id,
+            recipient_address,
+            client_tag,
+            recipient_checksum,
+        };
+        let result = validate_lock(
+            &ctx.accounts.config,
+            &ctx.accounts.pool,
+            ctx.accounts.user_lock_state.as_deref(),
+            ctx.accounts.chain_info.as_deref(),
+            &params,
+            Clock::get()?.unix_timestamp,
+        );
+        Ok(error_code_of(result.map(|_| ())))
+    }
+}
+
+/// Shared body of release_locked_liquidity (amount = None: everything outstanding) and
+/// release_partial.
+fn release_lock(ctx: Context<ReleaseLockedLiquidity>, amount: Option<u64>) -> Result<()> {
+    acquire_guard(&mut ctx.accounts.pool)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let bridge_lock = &mut ctx.accounts.bridge_lock;
+
+    require!(
+        bridge_lock.pool == pool.key(),
+        BridgeError::InvalidBridgeLock
+    );
+    let timestamp = Clock::get()?.unix_timestamp;
+    require_lock_age(&ctx.accounts.config, bridge_lock, timestamp)?;
+
+    let reason = if amount.is_some() {
+        ReleaseReason::PartialRevert
+    } else {
+        ReleaseReason::RelayerRevert
+    };
+    let amount = amount.unwrap_or_else(|| bridge_lock.outstanding_amount());
+    use_approvals(
+        &ctx.accounts.config,
+        bridge_lock,
+        ApprovalAction::Release,
+        amount,
+        None,
+    )?;
+    revert_lock(pool, bridge_lock, amount)?;
+    refund_lock(
+        pool,
+        &ctx.accounts.stablecoin_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.sender_stablecoin_ata.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    emit!(BridgeReverted {
+        pool: pool.key(),
+        event_seq: pool.next_event_seq(),
+        bridge_lock: bridge_lock.key(),
+        amount,
+        nonce: bridge_lock.nonce,
+        timestamp,
+        lifetime_released_volume: pool.lifetime_released_volume,
+        remaining_amount: bridge_lock.outstanding_amount(),
+        reason,
+    });
+
+    release_guard(&mut ctx.accounts.pool);
+    assert_pool_invariant(&ctx.accounts.pool)?;
+
+    Ok(())
+}
+
+fn set_cooldown_exempt(ctx: Context<SetCooldownExemption>, exempt: bool) -> Result<()> {
+    let user_lock_state = &mut ctx.accounts.user_lock_state;
+    if user_lock_state.pool == Pubkey::default() {
+        user_lock_state.pool = ctx.accounts.pool.key();
+        user_lock_state.user = ctx.accounts.user.key();
+        user_lock_state.bump = ctx.bumps.user_lock_state;
+    }
+    user_lock_state.cooldown_exempt = exempt;
+    Ok(())
+}
+
+// --- Shared validation ---
+// Used by both the mutating instructions and their read-only pre-flight checks so the two
+// can never disagree.
+
+/// Amounts derived from a validated deposit.
+struct DepositAmounts {
+    fee: u64,
+    amount_after_fee: u64,
+    /// LP minted to the depositor, assuming the vault receives amount_after_fee in full.
+    lp_tokens: u64,
+}
+
+/// lp_supply drives share math; the live LP mint supply (`lp_mint_supply`) is a cross-check
+/// that it has not diverged.
+fn require_lp_mint_in_sync(pool: &Pool, lp_mint_supply: u64) -> Result<()> {
+    require!(
+        lp_mint_supply == pool.expected_mint_supply(),
+        BridgeError::InvalidPoolState
+    );
+    Ok(())
+}
+
+fn validate_deposit(
+    config: &Config,
+    pool: &Pool,
+    lp_mint_supply: u64,
+    amount: u64,
+) -> Result<DepositAmounts> {
+    require!(!config.paused, BridgeError::PoolPaused);
+    require!(!pool.paused, BridgeError::PoolPaused);
+    require!(!pool.draining, BridgeError::PoolIsDraining);
+    require_lp_mint_in_sync(pool, lp_mint_supply)?;
+    require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+
+    let fee = fee_amount(amount, fee_bps_for(pool, OperationKind::Deposit))?;
+    let amount_after_fee = amount.checked_sub(fee).ok_or(BridgeError::MathOverflow)?;
+
+    if pool.max_total_liquidity > 0 {
+        let total_after = pool
+            .total_liquidity
+            .checked_add(amount_after_fee)
+            .ok_or(BridgeError::MathOverflow)?;
+        require!(
+            total_after <= pool.max_total_liquidity,
+            BridgeError::PoolCapExceeded
+        );
+    }
+
+    let (lp_tokens, _) = lp_for_deposit(pool, amount_after_fee)?;
+
+    Ok(DepositAmounts {
+        fee,
+        amount_after_fee,
+        lp_tokens,
+    })
+}
+
+/// LP minted for `credited` stablecoin, as (lp_tokens, dead_lp_shares).
+/// First depositor gets 1:1 minus MINIMUM_LIQUIDITY dead shares.
+fn lp_for_deposit(pool: &Pool, credited: u64) -> Result<(u64, u64)> {
+    let (lp_tokens, dead_lp_shares) = if pool.lp_supply == 0 {
+        (
+            credited.saturating_sub(Pool::MINIMUM_LIQUIDITY),
+            Pool::MINIMUM_LIQUIDITY,
+        )
+    } else {
+        let lp_tokens = pool
+            .lp_supply
+            .checked_mul(credited)
+            .ok_or(BridgeError::MathOverflow)?
+            .checked_div(pool.lp_value())
+            .ok_or(BridgeError::MathOverflow)?;
+        (lp_tokens, 0)
+    };
+
+    require!(lp_tokens > 0, BridgeError::ZeroLpAmount);
+    Ok((lp_tokens, dead_lp_shares))
+}
+
+/// Part of `credited` the `lp_tokens` minted for it do not represent: lp_for_deposit rounds
+/// LP down, so pricing the minted LP back (rounded up) leaves this much over, which would
+/// otherwise accrue to existing LPs. Always 0 for the first deposit (minted 1:1).
+fn deposit_dust(pool: &Pool, credited: u64, lp_tokens: u64) -> Result<u64> {
+    if pool.lp_supply == 0 {
+        return Ok(0);
+    }
+    let supply = u128::from(pool.lp_supply);
+    let minted_value = (u128::from(lp_tokens) * u128::from(pool.lp_value())).div_ceil(supply);
+    let minted_value = u64::try_from(minted_value).map_err(|_| BridgeError::MathOverflow)?;
+    Ok(credited.saturating_sub(minted_value))
+}
+
+/// Amounts derived from a validated withdrawal.
+struct WithdrawAmounts {
+    /// Gross share of the pool's LP value redeemed by lp_amount.
+    stablecoin_out: u64,
+    fee: u64,
+    /// Paid to the withdrawer.
+    amount_after_fee: u64,
+}
+
+/// Validate a withdrawal and compute the stablecoin returned for lp_amount, which must be
+/// payable now from liquidity not owed to queued withdrawals.
+fn validate_withdraw(
+    config: &Config,
+    pool: &Pool,
+    user_lp_state: Option<&UserLpState>,
+    lp_mint_supply: u64,
+    lp_amount: u64,
+    now: i64,
+) -> Result<WithdrawAmounts> {
+    let amounts = price_withdraw(config, pool, user_lp_state, lp_mint_supply, lp_amount, now)?;
+    require!(
+        amounts.stablecoin_out <= pool.unqueued_liquidity(),
+        BridgeError::InsufficientLiquidity
+    );
+    Ok(amounts)
+}
+
+/// validate_withdraw without the payability check, shared with request_withdrawal.
+/// A paused pool still allows withdrawals in emergency mode; the protocol pause always applies.
+/// `user_lp_state` is None when no specific withdrawer is known (no LP lockup applies). A holder
+/// with no deposit record (LP received by transfer) is not locked.
+fn price_withdraw(
+    config: &Config,
+    pool: &Pool,
+    user_lp_state: Option<&UserLpState>,
+    lp_mint_supply: u64,
+    lp_amount: u64,
+    now: i64,
+) -> Result<WithdrawAmounts> {
+    require!(!config.paused, BridgeError::PoolPaused);
+    require!(!pool.paused || pool.emergency_mode, BridgeError::PoolPaused);
+    require!(lp_amount > 0, BridgeError::ZeroLpAmount);
+    require_lp_mint_in_sync(pool, lp_mint_supply)?;
+
+    if let Some(state) = user_lp_state {
+        if pool.min_lp_hold_seconds > 0 && state.last_deposit_at != 0 {
+            let elapsed = now.saturating_sub(state.last_deposit_at);
+            require!(
+                elapsed >= i64::from(pool.min_lp_hold_seconds),
+                BridgeError::LpLockupActive
+            );
+        }
+    }
+
+    // An empty pool has nothing to redeem; this also rules out a zero divisor below.
+    let total_lp_supply = pool.lp_supply;
+    require!(total_lp_supply > 0, BridgeError::NoLpSupply);
+
+    let stablecoin_out = pool
+        .lp_value()
+        .checked_mul(lp_amount)
+        .ok_or(BridgeError::MathOverflow)?
+        .checked_div(total_lp_supply)
+        .ok_or(BridgeError::MathOverflow)?;
+
+    require!(stablecoin_out > 0, BridgeError::ZeroStablecoinAmount);
+
+    let fee = fee_amount(stablecoin_out, fee_bps_for(pool, OperationKind::Withdraw))?;
+    let amount_after_fee = stablecoin_out
+        .checked_sub(fee)
+        .ok_or(BridgeError::MathOverflow)?;
+
+    Ok(WithdrawAmounts {
+        stablecoin_out,
+        fee,
+        amount_after_fee,
+    })
+}
+
+/// Amounts derived from a validated lock.
+struct LockAmounts {
+    fee: u64,
+    /// Escrowed and delivered on destination.
+    amount_after_fee: u64,
+}
+
+/// `user_lock_state` is None when the user has never locked (no cooldown applies).
+fn validate_lock(
+    config: &Config,
+    pool: &Pool,
+    user_lock_state: Option<&UserLockState>,
+    chain_info: Option<&ChainInfo>,
+    params: &LockParams,
+    now: i64,
+) -> Result<LockAmounts> {
+    let amount = params.amount;
+    require!(!config.paused, BridgeError::PoolPaused);
+    require!(!pool.paused, BridgeError::PoolPaused);
+    require!(!pool.draining, BridgeError::PoolIsDraining);
+    let chain_info = match chain_info {
+        Some(info) if info.supported => info,
+        _ => return err!(BridgeError::UnsupportedChain),
+    };
+    // Short (e.g. 20-byte EVM) addresses are right-aligned with zero high bytes; the chain's
+    // address_format decides what else is deliverable.
+    require!(
+        recipient_accepted(
+            chain_info,
+            &params.recipient_address,
+            params.recipient_checksum,
+        ),
+        BridgeError::InvalidRecipient
+    );
+    require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+    require!(
+        amount >= pool.min_lock_amount,
+        BridgeError::LockAmountBelowMinimum
+    );
+    require!(
+        amount <= pool.max_lock_per_tx,
+        BridgeError::LockAmountExceedsLimit
+    );
+    if pool.require_liquidity_backing {
+        require!(
+            pool.unqueued_liquidity() >= amount,
+            BridgeError::InsufficientLiquidity
+        );
+    }
+    if pool.max_lock_per_window > 0 {
+        let (_, window_volume) = lock_window_after(pool, amount, now)?;
+        require!(
+            window_volume <= pool.max_lock_per_window,
+            BridgeError::WindowLimitExceeded
+        );
+    }
+
+    if let Some(state) = user_lock_state {
+        if !state.cooldown_exempt && pool.lock_cooldown_seconds > 0 {
+            let elapsed = now.saturating_sub(state.last_lock_at);
+            require!(
+                elapsed >= i64::from(pool.lock_cooldown_seconds),
+                BridgeError::LockCooldownActive
+            );
+        }
+        require!(
+            !state.has_recent_tag(&params.client_tag, now),
+            BridgeError::DuplicateLock
+        );
+    }
+
+    let fee = fee_amount(amount, fee_bps_for(pool, OperationKind::Bridge))?;
+    let amount_after_fee = amount.checked_sub(fee).ok_or(BridgeError::MathOverflow)?;
+    require!(amount_after_fee > 0, BridgeError::ZeroStablecoinAmount);
+    // The lock re-checks against what the vault actually credits (transfer-fee mints credit
+    // less); this catches the rest up front, including in check_lock.
+    require!(
+        amount_after_fee > pool.relayer_fee,
+        BridgeError::RelayerFeeExceedsAmount
+    );
+    // amount_after_fee bounds what the lock escrows (transfer-fee mints credit less).
+    if pool.max_locked_liquidity > 0 {
+        let locked_after = pool
+            .locked_liquidity
+            .checked_add(amount_after_fee)
+            .ok_or(BridgeError::MathOverflow)?;
+        require!(
+            locked_after <= pool.max_locked_liquidity,
+            BridgeError::LockedCapExceeded
+        );
+    }
+
+    Ok(LockAmounts {
+        fee,
+        amount_after_fee,
+    })
+}
+
+/// Whether recipient is deliverable on the destination chain, per its registered
+/// address_format, and matches the client's checksum when one is given.
+fn recipient_accepted(chain_info: &ChainInfo, recipient: &[u8; 32], checksum: Option<u8>) -> bool {
+    let format = chain_info.address_format;
+    format.accepts(recipient)
+        && !matches!(checksum, Some(checksum) if checksum != format.checksum(recipient))
+}
+
+/// Rolling lock window after adding `amount` at `now`, as (window_start, window_volume).
+/// The window restarts once lock_window_seconds have elapsed since window_start.
+fn lock_window_after(pool: &Pool, amount: u64, now: i64) -> Result<(i64, u64)> {
+    let elapsed = now.saturating_sub(pool.window_start);
+    let (window_start, window_volume) = if elapsed >= i64::from(pool.lock_window_seconds) {
+        (now, 0)
+    } else {
+        (pool.window_start, pool.window_volume)
+    };
+    let window_volume = window_volume
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    Ok((window_start, window_volume))
+}
+
+/// Emit the lock's BridgeIntent, or only its BridgeIntentCompact when the pool opts in.
+fn emit_bridge_intent(pool: &Pool, bridge_lock: &BridgeLock, intent: BridgeIntent) {
+    if pool.compact_events {
+        emit!(BridgeIntentCompact {
+            pool: intent.pool,
+            event_seq: intent.event_seq,
+            nonce: intent.nonce,
+            payload_hash: bridge_lock.intent_hash(),
+        });
+    } else {
+        emit!(intent);
+    }
+}
+
+/// Nonce following `nonce`. Must be strictly greater: BridgeLock PDAs are seeded by nonce,
+/// so a repeated nonce would let a closed lock's address be re-created with a fresh lock that
+/// relayers could confuse with the settled one. Fails instead of wrapping or saturating.
+fn next_nonce(nonce: u64) -> Result<u64> {
+    let next = nonce.checked_add(1).ok_or(BridgeError::MathOverflow)?;
+    Ok(next)
+}
+
+/// Add amount to the rolling outflow window and trip the circuit breaker once the window's
+/// volume exceeds max_outflow_per_window. The outflow that crosses the limit still settles;
+/// the pause blocks everything after it. Returns true if this call paused the pool.
+fn record_outflow(pool: &mut Pool, amount: u64, now: i64) -> Result<bool> {
+    let elapsed = now.saturating_sub(pool.outflow_window_start);
+    if elapsed >= i64::from(pool.outflow_window_seconds) {
+        pool.outflow_window_start = now;
+        pool.outflow_window_volume = 0;
+    }
+    pool.outflow_window_volume = pool
+        .outflow_window_volume
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+
+    let tripped = pool.max_outflow_per_window > 0
+        && !pool.paused
+        && pool.outflow_window_volume > pool.max_outflow_per_window;
+    if tripped {
+        pool.paused = true;
+        pool.paused_at = now;
+        pool.auto_revert_pending = false;
+        pool.pause_reason = PauseReason::CircuitBreaker;
+    }
+    Ok(tripped)
+}
+
+/// Release amount of a pending lock and remove it from the pool's books; the lock is marked
+/// released once nothing is outstanding. The caller refunds amount to the sender.
+fn revert_lock(pool: &mut Pool, bridge_lock: &mut BridgeLock, amount: u64) -> Result<()> {
+    require!(!bridge_lock.released, BridgeError::AlreadyReleased);
+    require!(!bridge_lock.completed, BridgeError::AlreadyCompleted);
+    require!(amount > 0, BridgeError::ZeroStablecoinAmount);
+    require!(
+        amount <= bridge_lock.outstanding_amount(),
+        BridgeError::ReleaseExceedsLocked
+    );
+
+    bridge_lock.released_amount = bridge_lock
+        .released_amount
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    bridge_lock.released = bridge_lock.outstanding_amount() == 0;
+    if bridge_lock.backed {
+        release_backing(pool, amount)?;
+    }
+
+    pool.locked_liquidity = pool
+        .locked_liquidity
+        .checked_sub(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    pool.total_liquidity = pool
+        .total_liquidity
+        .checked_sub(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    pool.lifetime_released_volume = pool.lifetime_released_volume.saturating_add(amount);
+
+    Ok(())
+}
+
+/// Hold amount of available_liquidity back as a lock's backing when the pool requires it.
+/// Returns whether the lock is backed.
+fn reserve_backing(pool: &mut Pool, amount: u64) -> Result<bool> {
+    if !pool.require_liquidity_backing {
+        return Ok(false);
+    }
+    pool.available_liquidity = pool
+        .available_liquidity
+        .checked_sub(amount)
+        .ok_or(BridgeError::InsufficientLiquidity)?;
+    pool.reserved_liquidity = pool
+        .reserved_liquidity
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    Ok(true)
+}
+
+/// Return amount of a backed lock's reservation to available_liquidity once the lock no
+/// longer needs it (reverted or completed).
+fn release_backing(pool: &mut Pool, amount: u64) -> Result<()> {
+    pool.reserved_liquidity = pool
+        .reserved_liquidity
+        .checked_sub(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    pool.available_liquidity = pool
+        .available_liquidity
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    Ok(())
+}
+
+/// Relayer reverts wait config.min_lock_age_seconds after the lock was created, so a lock
+/// cannot be created and reverted back to back.
+fn require_lock_age(config: &Config, bridge_lock: &BridgeLock, now: i64) -> Result<()> {
+    require!(
+        now.saturating_sub(bridge_lock.locked_at) >= i64::from(config.min_lock_age_seconds),
+        BridgeError::LockTooYoung
+    );
+    Ok(())
+}
+
+/// Use the lock's pending approvals for `action` moving `amount`. Requires
+/// config.release_threshold approvals from currently authorized relayers, bound to exactly this
+/// action and amount; `attester` (a verified attestation signer) counts as one more unless it
+/// already approved. Clears the approvals so none carry over to a later settlement (e.g. after a
+/// release_partial).
+fn use_approvals(
+    config: &Config,
+    bridge_lock: &mut BridgeLock,
+    action: ApprovalAction,
+    amount: u64,
+    attester: Option<&Pubkey>,
+) -> Result<()> {
+    let approvals = &bridge_lock.approvals[..bridge_lock.approval_count as usize];
+    let bound = bridge_lock.approved_action == action && bridge_lock.approved_amount == amount;
+    let mut count = if bound {
+        approvals
+            .iter()
+            .filter(|relayer| config.is_relayer(relayer))
+            .count()
+    } else {
+        0
+    };
+    if attester.is_some_and(|attester| !(bound && approvals.contains(attester))) {
+        count += 1;
+    }
+    require!(
+        count >= usize::from(config.release_threshold),
+        BridgeError::ReleaseThresholdNotMet
+    );
+    bridge_lock.clear_approvals();
+    Ok(())
+}
+
+/// Install new fee rates on the pool, keeping the deprecated fee_rate_bps alias in sync.
+fn set_fee_rates(
+    pool: &mut Pool,
+    deposit_fee_bps: u16,
+    withdraw_fee_bps: u16,
+    bridge_fee_bps: u16,
+) {
+    pool.deposit_fee_bps = deposit_fee_bps;
+    pool.withdraw_fee_bps = withdraw_fee_bps;
+    pool.bridge_fee_bps = bridge_fee_bps;
+    pool.fee_rate_bps = deposit_fee_bps;
+}
+
+/// Every pool fee rate must stay at or below config.max_fee_bps.
+fn require_fees_within_max(
+    config: &Config,
+    deposit_fee_bps: u16,
+    withdraw_fee_bps: u16,
+    bridge_fee_bps: u16,
+) -> Result<()> {
+    require!(
+        deposit_fee_bps <= config.max_fee_bps
+            && withdraw_fee_bps <= config.max_fee_bps
+            && bridge_fee_bps <= config.max_fee_bps,
+        BridgeError::FeeExceedsProtocolMax
+    );
+    Ok(())
+}
+
+/// Domain tag prefixed to attestation messages so a relayer signature over this hash cannot be
+/// replayed as a signature for any other protocol message.
+const ATTESTATION_DOMAIN: &[u8] = b"railx-release-v1";
+
+/// Message a relayer signs to attest a release: hash(domain || pool || nonce || amount), LE
+/// integers. amount is the lock's outstanding amount, i.e. what the release moves.
+fn attestation_message(pool: &Pubkey, nonce: u64, amount: u64) -> [u8; 32] {
+    hashv(&[
+        ATTESTATION_DOMAIN,
+        pool.as_ref(),
+        &nonce.to_le_bytes(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Check that the instruction preceding the current one is an Ed25519 program instruction
+/// verifying `signature` over `message` by an authorized relayer, and return that relayer.
+/// Offsets must point into that instruction's own data (index u16::MAX).
+fn verify_relayer_attestation(
+    config: &Config,
+    instructions: &AccountInfo,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<Pubkey> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, BridgeError::InvalidAttestation);
+    let ix = load_instruction_at_checked(usize::from(current - 1), instructions)?;
+    require!(
+        ix.program_id == ed25519_program::ID,
+        BridgeError::InvalidAttestation
+    );
+
+    // Layout: num_signatures (u8), padding (u8), then one 14-byte offsets record.
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        BridgeError::InvalidAttestation
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_offset = usize::from(read_u16(2));
+    let public_key_offset = usize::from(read_u16(6));
+    let message_offset = usize::from(read_u16(10));
+    let message_size = usize::from(read_u16(12));
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        BridgeError::InvalidAttestation
+    );
+
+    let signed = |offset: usize, len: usize| {
+        data.get(offset..offset + len)
+            .ok_or(BridgeError::InvalidAttestation)
+    };
+    require!(
+        signed(signature_offset, 64)? == signature.as_slice(),
+        BridgeError::InvalidAttestation
+    );
+    require!(
+        signed(message_offset, message_size)? == message,
+        BridgeError::InvalidAttestation
+    );
+    let signer = Pubkey::try_from(signed(public_key_offset, 32)?)
+        .map_err(|_| BridgeError::InvalidAttestation)?;
+    require!(config.is_relayer(&signer), BridgeError::UnauthorizedRelayer);
+
+    Ok(signer)
+}
+
+/// Amount a token account gained since `balance_before`. Token-2022 transfer-fee mints
+/// withhold part of each transfer, so inbound flows credit this rather than the amount sent.
+fn received_since(
+    account: &mut InterfaceAccount<TokenAccount>,
+    balance_before: u64,
+) -> Result<u64> {
+    account.reload()?;
+    let received = account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(BridgeError::MathOverflow)?;
+    Ok(received)
+}
+
+/// Transfer an escrowed lock amount from the vault back to the sender's token account.
+fn refund_lock<'info>(
+    pool: &Account<'info, Pool>,
+    stablecoin_mint: &InterfaceAccount<'info, Mint>,
+    vault: AccountInfo<'info>,
+    sender_stablecoin_ata: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"pool", pool.stablecoin_mint.as_ref(), &[pool.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: vault,
+        mint: stablecoin_mint.to_account_info(),
+        to: sender_stablecoin_ata,
+        authority: pool.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(token_program, cpi_accounts, signer),
+        amount,
+        stablecoin_mint.decimals,
+    )
+}
+
+/// Revert one stale lock and refund its sender as part of sweep_stale_locks.
+fn sweep_stale_lock<'info>(
+    pool: &mut Account<'info, Pool>,
+    stablecoin_mint: &InterfaceAccount<'info, Mint>,
+    vault: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    pair: &'info [AccountInfo<'info>],
+    timestamp: i64,
+) -> Result<()> {
+    let [lock_info, refund_info] = pair else {
+        return err!(BridgeError::InvalidBridgeLock);
+    };
+    require!(lock_info.is_writable, BridgeError::InvalidBridgeLock);
+    let mut bridge_lock = Account::<BridgeLock>::try_from(lock_info)?;
+    require!(
+        bridge_lock.pool == pool.key(),
+        BridgeError::InvalidBridgeLock
+    );
+    require!(
+        bridge_lock.locked_at <= pool.auto_revert_cutoff,
+        BridgeError::LockNotStale
+    );
+
+    require!(refund_info.is_writable, BridgeError::InvalidRefundAccount);
+    let refund_account = InterfaceAccount::<TokenAccount>::try_from(refund_info)?;
+    require!(
+        refund_account.owner == bridge_lock.sender,
+        BridgeError::InvalidRefundAccount
+    );
+    require!(
+        refund_account.mint == pool.stablecoin_mint,
+        BridgeError::InvalidRefundAccount
+    );
+
+    let amount = bridge_lock.outstanding_amount();
+    revert_lock(pool, &mut bridge_lock, amount)?;
+    refund_lock(
+        pool,
+        stablecoin_mint,
+        vault.clone(),
+        refund_info.clone(),
+        token_program.clone(),
+        amount,
+    )?;
+    bridge_lock.exit(&crate::ID)?;
+
+    emit!(BridgeReverted {
+        pool: pool.key(),
+        event_seq: pool.next_event_seq(),
+        bridge_lock: bridge_lock.key(),
+        amount,
+        nonce: bridge_lock.nonce,
+        timestamp,
+        lifetime_released_volume: pool.lifetime_released_volume,
+        remaining_amount: 0,
+        reason: ReleaseReason::StaleLockSweep,
+    });
+
+    Ok(())
+}
+
+/// Create one BridgeLock of lock_for_bridge_batch and escrow its net amount. The caller has
+/// already checked the batch total against the per-tx and window limits. Returns the lock's
+/// receipt and the bridge fee still to be transferred.
+fn lock_batch_item<'info>(
+    accounts: &mut LockForBridgeBatch<'info>,
+    params: &LockParams,
+    item: &'info [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<(LockReceipt, u64)> {
+    let [lock_info, chain_info, counter_info] = item else {
+        return err!(BridgeError::InvalidBatchSize);
+    };
+    let chain_info = Account::<ChainInfo>::try_from(chain_info)?;
+    require!(
+        chain_info.chain_id == params.destination_chain_id,
+        BridgeError::UnsupportedChain
+    );
+
+    let LockAmounts {
+        fee,
+        amount_after_fee,
+    } = validate_lock(
+        &accounts.config,
+        &accounts.pool,
+        Some(&accounts.user_lock_state),
+        Some(&chain_info),
+        params,
+        clock.unix_timestamp,
+    )?;
+    // Also catches a tag repeated within the same batch, since each item records its tag.
+    accounts
+        .user_lock_state
+        .record_tag(params.client_tag, clock.unix_timestamp);
+
+    let pool_key = accounts.pool.key();
+    let nonce = accounts.pool.next_lock_nonce;
+    let nonce_bytes = nonce.to_le_bytes();
+    let (expected_lock, bump) = Pubkey::find_program_address(
+        &[b"bridge_lock", pool_key.as_ref(), &nonce_bytes],
+        &crate::ID,
+    );
+    require!(
+        lock_info.key() == expected_lock,
+        BridgeError::InvalidBridgeLock
+    );
+    accounts.pool.next_lock_nonce = next_nonce(nonce)?;
+
+    // Create the BridgeLock PDA, paid for by the sender
+    let space = 8 + BridgeLock::LEN;
+    let seeds = &[b"bridge_lock", pool_key.as_ref(), &nonce_bytes, &[bump]];
+    let signer = &[&seeds[..]];
+    create_pda_account(
+        lock_info,
+        accounts.sender.to_account_info(),
+        accounts.system_program.to_account_info(),
+        space,
+        signer,
+    )?;
+
+    // Transfer net user stablecoin to pool vault
+    let vault_balance_before = accounts.vault.amount;
+    let cpi_accounts = TransferChecked {
+        from: accounts.user_stablecoin_ata.to_account_info(),
+        mint: accounts.stablecoin_mint.to_account_info(),
+        to: accounts.vault.to_account_info(),
+        authority: accounts.sender.to_account_info(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts),
+        amount_after_fee,
+        accounts.stablecoin_mint.decimals,
+    )?;
+    let credited = received_since(&mut accounts.vault, vault_balance_before)?;
+    require!(credited > 0, BridgeError::ZeroStablecoinAmount);
+    let relayer_fee = accounts.pool.relayer_fee;
+    require!(credited > relayer_fee, BridgeError::RelayerFeeExceedsAmount);
+
+    let backed = reserve_backing(&mut accounts.pool, credited)?;
+
+    let chain_index = next_chain_index(
+        accounts,
+        counter_info,
+        pool_key,
+        params.destination_chain_id,
+    )?;
+
+    let bridge_lock = BridgeLock {
+        pool: pool_key,
+        amount: credited,
+        nonce,
+        destination_chain_id: params.destination_chain_id,
+        recipient_address: params.recipient_address,
+        sender: accounts.sender.key(),
+        locked_at: clock.unix_timestamp,
+        locked_slot: clock.slot,
+        relayer_fee,
+        client_tag: params.client_tag,
+        chain_index,
+        backed,
+        version: BridgeLock::CURRENT_VERSION,
+        ..BridgeLock::default()
+    };
+    bridge_lock.try_serialize(&mut &mut lock_info.try_borrow_mut_data()?[..])?;
+
+    let pool = &mut accounts.pool;
+    pool.total_liquidity = pool
+        .total_liquidity
+        .checked_add(credited)
+        .ok_or(BridgeError::MathOverflow)?;
+    pool.locked_liquidity = pool
+        .locked_liquidity
+        .checked_add(credited)
+        .ok_or(BridgeError::MathOverflow)?;
+    pool.lifetime_locked_volume = pool.lifetime_locked_volume.saturating_add(credited);
+
+    let intent = BridgeIntent {
+        pool: pool_key,
+        event_seq: pool.next_event_seq(),
+        sender: accounts.sender.key(),
+        amount: credited,
+        destination_chain_id: params.destination_chain_id,
+        recipient_address: params.recipient_address,
+        nonce,
+        timestamp: clock.unix_timestamp,
+        destination_chain_name: Some(chain_info.name),
+        gross_amount: params.amount,
+        lifetime_locked_volume: pool.lifetime_locked_volume,
+        locked_slot: clock.slot,
+        relayer_fee,
+        chain_index,
+    };
+    emit_bridge_intent(pool, &bridge_lock, intent);
+
+    Ok((
+        LockReceipt {
+            bridge_lock: lock_info.key(),
+            nonce,
+        },
+        fee,
+    ))
+}
+
+/// Create a program-owned PDA the way Anchor's `init` does. An address that already holds
+/// lamports (anyone can pre-fund a predictable PDA) cannot go through create_account, so it is
+/// topped up to rent exemption, then allocated and assigned instead.
+fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    space: usize,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                CreateAccount {
+                    from: payer,
+                    to: account.clone(),
+                },
+                signer,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+    let shortfall = rent.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer,
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            signer,
+        ),
+        &crate::ID,
+    )
+}
+
+/// The *_sol instructions only apply to pools whose stablecoin is the wrapped-SOL mint.
+fn require_native_mint(pool: &Pool) -> Result<()> {
+    require!(
+        pool.stablecoin_mint == spl_token::native_mint::ID
+            || pool.stablecoin_mint == spl_token_2022::native_mint::ID,
+        BridgeError::NotNativeMint
+    );
+    Ok(())
+}
+
+/// Wrap lamports into a wSOL token account: move them in with a system transfer, then
+/// sync_native so the token balance reflects them.
+fn wrap_sol<'info>(
+    payer: AccountInfo<'info>,
+    wsol_account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    require!(lamports > 0, BridgeError::ZeroStablecoinAmount);
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            system_program::Transfer {
+                from: payer,
+                to: wsol_account.clone(),
+            },
+        ),
+        lamports,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        token_program,
+        SyncNative {
+            account: wsol_account,
+        },
+    ))
+}
+
+/// Whether mint is a Token-2022 mint carrying the TransferHook extension. Presence alone
+/// counts: the hook authority can point an unset hook at a program at any time.
+fn has_transfer_hook(mint: &AccountInfo) -> Result<bool> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint.get_extension::<TransferHook>().is_ok())
+}
+
+/// Grow a program-owned account to `space` bytes, zero-filling the new tail and topping its
+/// rent up from payer. No-op if it is already large enough.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    account.realloc(space, true)?;
+    Ok(())
+}
+
+/// Take the next chain_index from a batch item's ChainLockCounter, creating the counter PDA
+/// (paid for by the sender) on the chain's first lock.
+fn next_chain_index<'info>(
+    accounts: &LockForBridgeBatch<'info>,
+    counter_info: &'info AccountInfo<'info>,
+    pool_key: Pubkey,
+    chain_id: u64,
+) -> Result<u64> {
+    let chain_id_bytes = chain_id.to_le_bytes();
+    let (expected_counter, bump) = Pubkey::find_program_address(
+        &[b"chain_lock_counter", pool_key.as_ref(), &chain_id_bytes],
+        &crate::ID,
+    );
+    require!(
+        counter_info.key() == expected_counter,
+        BridgeError::InvalidChainLockCounter
+    );
+
+    let mut counter = if counter_info.data_is_empty() {
+        let space = 8 + ChainLockCounter::LEN;
+        let seeds = &[
+            b"chain_lock_counter",
+            pool_key.as_ref(),
+            &chain_id_bytes,
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+        create_pda_account(
+            counter_info,
+            accounts.sender.to_account_info(),
+            accounts.system_program.to_account_info(),
+            space,
+            signer,
+        )?;
+        ChainLockCounter {
+            pool: pool_key,
+            chain_id,
+            next_index: 0,
+            bump,
+        }
+    } else {
+        require!(
+            counter_info.owner == &crate::ID,
+            BridgeError::InvalidChainLockCounter
+        );
+        ChainLockCounter::try_deserialize(&mut &counter_info.try_borrow_data()?[..])?
+    };
+
+    let chain_index = counter.next_index;
+    counter.next_index = next_nonce(chain_index)?;
+    counter.try_serialize(&mut &mut counter_info.try_borrow_mut_data()?[..])?;
+    Ok(chain_index)
+}
+
+/// Reentrancy guard: mark the pool busy and persist the flag so a nested invocation of this
+/// program sees it. A failed instruction reverts the flag along with everything else.
+fn acquire_guard(pool: &mut Account<Pool>) -> Result<()> {
+    require!(!pool.in_progress, BridgeError::ReentrancyDetected);
+    pool.in_progress = true;
+    pool.exit(&crate::ID)
+}
+
+/// Clear the reentrancy guard at the end of a successful instruction.
+fn release_guard(pool: &mut Pool) {
+    pool.in_progress = false;
+}
+
+/// Every token the pool accounts for is available, reserved to back locks, or locked by a
+/// bridge, so total - locked == available + reserved and LPs can never redeem escrow owed
+/// to senders. Queued withdrawals are a claim on available + reserved.
+fn assert_pool_invariant(pool: &Pool) -> Result<()> {
+    let accounted = pool
+        .available_liquidity
+        .checked_add(pool.reserved_liquidity)
+        .and_then(|lp_owned| lp_owned.checked_add(pool.locked_liquidity))
+        .ok_or(BridgeError::InvalidPoolState)?;
+    require!(
+        pool.total_liquidity == accounted,
+        BridgeError::InvalidPoolState
+    );
+    require!(
+        pool.queued_withdrawals
+            <= pool
+                .available_liquidity
+                .saturating_add(pool.reserved_liquidity),
+        BridgeError::InvalidPoolState
+    );
+    Ok(())
+}
+
+/// Fee rate (bps) charged on an operation. Single place fee selection happens.
+fn fee_bps_for(pool: &Pool, op_kind: OperationKind) -> u16 {
+    match op_kind {
+        OperationKind::Deposit => pool.deposit_fee_bps,
+        OperationKind::Withdraw => pool.withdraw_fee_bps,
+        OperationKind::Bridge => pool.bridge_fee_bps,
+    }
+}
+
+/// Split a collected fee into (protocol share for fee_vault, LP share retained in the pool)
+/// per config.protocol_fee_share_bps. The protocol share rounds down, so any remainder unit
+/// goes to LPs.
+fn split_fee(config: &Config, fee: u64) -> Result<(u64, u64)> {
+    let protocol = u128::from(fee) * u128::from(config.protocol_fee_share_bps) / 10_000;
+    let protocol = u64::try_from(protocol).map_err(|_| BridgeError::MathOverflow)?;
+    let lp = fee.checked_sub(protocol).ok_or(BridgeError::MathOverflow)?;
+    Ok((protocol, lp))
+}
+
+/// Add the LP share of a fee, already in the vault, to available liquidity as LP yield.
+fn credit_lp_fee(pool: &mut Pool, amount: u64) -> Result<()> {
+    pool.total_liquidity = pool
+        .total_liquidity
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    pool.available_liquidity = pool
+        .available_liquidity
+        .checked_add(amount)
+        .ok_or(BridgeError::MathOverflow)?;
+    Ok(())
+}
+
+/// Fee taken from amount at fee_bps, rounded up so the user never pays less than the rate.
+fn fee_amount(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = amount
+        .checked_mul(u64::from(fee_bps))
+        .and_then(|scaled| scaled.checked_add(9_999))
+        .ok_or(BridgeError::MathOverflow)?
+        / 10_000;
+    Ok(fee)
+}
+
+/// Error code a validation result would surface to the client (0 = success).
+fn error_code_of(result: Result<()>) -> u16 {
+    match result {
+        Ok(()) => 0,
+        Err(err) => error_code(&err),
+    }
+}
+
+/// Numeric code of an error as reported in events (u16::MAX for raw program errors).
+fn error_code(err: &Error) -> u16 {
+    match err {
+        Error::AnchorError(err) => err.error_code_number as u16,
+        Error::ProgramError(_) => u16::MAX,
+    }
+}
+
+// --- Account structs and validation ---
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::LEN,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Relayer authority (can call release_locked_liquidity).
+    /// Unchecked: stored for later validation.
+    /// CHECK: relayer pubkey stored in config
+    pub relayer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Pool::LEN,
+        seeds = [b"pool", stablecoin_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PoolTombstone of a previously closed pool for this mint, if any; read in
+    /// initialize_pool only when it is a program-owned account. Address checked by seeds.
+    #[account(
+        seeds = [b"pool_tombstone", stablecoin_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_tombstone: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = vault.mint == stablecoin_mint.key(),
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState,
+        constraint = vault.amount == 0 @ BridgeError::VaultNotEmpty,
+        constraint = vault.delegate.is_none() @ BridgeError::VaultHasAuthority,
+        constraint = vault.close_authority.is_none() @ BridgeError::VaultHasAuthority
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Must be separate from the vault so fees never mix with LP liquidity.
+    #[account(
+        constraint = fee_vault.key() != vault.key() @ BridgeError::InvalidPoolState,
+        constraint = fee_vault.mint == stablecoin_mint.key() @ BridgeError::InvalidPoolState,
+        constraint = fee_vault.owner == pool.key() @ BridgeError::InvalidPoolState,
+        constraint = fee_vault.delegate.is_none() @ BridgeError::VaultHasAuthority,
+        constraint = fee_vault.close_authority.is_none() @ BridgeError::VaultHasAuthority
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lp_token_mint.key() != stablecoin_mint.key(),
+        constraint = lp_token_mint.to_account_info().owner == stablecoin_mint.to_account_info().owner @ BridgeError::InvalidPoolState,
+        constraint = lp_token_mint.decimals == stablecoin_mint.decimals @ BridgeError::DecimalMismatch,
+        constraint = lp_token_mint.supply == 0 @ BridgeError::InvalidPoolState,
+        constraint = lp_token_mint.freeze_authority.is_none() @ BridgeError::InvalidPoolState,
+        constraint = lp_token_mint.mint_authority == COption::Some(pool.key()) @ BridgeError::InvalidPoolState
+    )]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ BridgeError::PoolPaused,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = user_stablecoin_ata.owner == depositor.key()
+    )]
+    pub user_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the minted LP; its owner (the beneficiary) may differ from the depositor.
+    #[account(
+        mut,
+        constraint = user_lp_ata.mint == pool.lp_token_mint
+    )]
+    pub user_lp_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lp_token_mint.key() == pool.lp_token_mint
+    )]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// PDA: ["user_lp", pool.key(), user_lp_ata.owner]. Tracks the LP lockup of the
+    /// beneficiary, who holds (and later redeems) the minted LP.
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = 8 + UserLpState::LEN,
+        seeds = [b"user_lp", pool.key().as_ref(), user_lp_ata.owner.as_ref()],
+        bump
+    )]
+    pub user_lp_state: Account<'info, UserLpState>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositAndBurnLp<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ BridgeError::PoolPaused,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub donor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = user_stablecoin_ata.owner == donor.key()
+    )]
+    pub user_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(constraint = lp_token_mint.key() == pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeYield<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    /// Admin's token account the yield is paid from.
+    #[account(
+        mut,
+        constraint = source_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = source_stablecoin_ata.owner == admin.key()
+    )]
+    pub source_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused || pool.emergency_mode @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_lp_ata.mint == pool.lp_token_mint,
+        constraint = user_lp_ata.owner == withdrawer.key()
+    )]
+    pub user_lp_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = user_stablecoin_ata.owner == withdrawer.key()
+    )]
+    pub user_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, constraint = lp_token_mint.key() == pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// PDA: ["user_lp", pool.key(), withdrawer.key()]. Tracks the LP lockup.
+    #[account(
+        init_if_needed,
+        payer = withdrawer,
+        space = 8 + UserLpState::LEN,
+        seeds = [b"user_lp", pool.key().as_ref(), withdrawer.key().as_ref()],
+        bump
+    )]
+    pub user_lp_state: Account<'info, UserLpState>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused || pool.emergency_mode @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_lp_ata.mint == pool.lp_token_mint,
+        constraint = user_lp_ata.owner == owner.key()
+    )]
+    pub user_lp_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, constraint = lp_token_mint.key() == pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// PDA: ["withdrawal_request", pool.key(), owner.key()]. Fails to init while the owner
+    /// already has a request outstanding.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + WithdrawalRequest::LEN,
+        seeds = [b"withdrawal_request", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    /// PDA: ["user_lp", pool.key(), owner.key()]. Tracks the LP lockup.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserLpState::LEN,
+        seeds = [b"user_lp", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub user_lp_state: Account<'info, UserLpState>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillWithdrawal<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused || pool.emergency_mode @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"withdrawal_request", pool.key().as_ref(), owner.key().as_ref()],
+        bump = withdrawal_request.bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    /// CHECK: the request owner (bound by the withdrawal_request seeds); receives its rent.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = owner_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = owner_stablecoin_ata.owner == owner.key()
+    )]
+    pub owner_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, destination_chain_id: u64, recipient_address: [u8; 32])]
+pub struct LockForBridge<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = user_stablecoin_ata.owner == sender.key()
+    )]
+    pub user_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// PDA: ["bridge_lock", pool.key(), nonce]. Client derives using current pool.next_lock_nonce.
+    #[account(
+        init,
+        payer = sender,
+        space = 8 + BridgeLock::LEN,
+        seeds = [
+            b"bridge_lock",
+            pool.key().as_ref(),
+            &pool.next_lock_nonce.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+
+    /// PDA: ["user_lock", pool.key(), sender.key()]. Tracks the sender's cooldown.
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + UserLockState::LEN,
+        seeds = [b"user_lock", pool.key().as_ref(), sender.key().as_ref()],
+        bump
+    )]
+    pub user_lock_state: Account<'info, UserLockState>,
+
+    /// PDA: ["user_index", pool.key(), sender.key()]. Ring buffer of the sender's recent nonces.
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + UserLockIndex::LEN,
+        seeds = [b"user_index", pool.key().as_ref(), sender.key().as_ref()],
+        bump
+    )]
+    pub user_lock_index: Account<'info, UserLockIndex>,
+
+    /// Registered metadata for destination_chain_id. Locks toward a registered chain that is
+    /// not supported fail with UnsupportedChain.
+    #[account(
+        seeds = [b"chain".as_ref(), &destination_chain_id.to_le_bytes()],
+        bump = chain_info.bump
+    )]
+    pub chain_info: Account<'info, ChainInfo>,
+
+    /// PDA: ["chain_lock_counter", pool.key(), destination_chain_id]. Assigns chain_index.
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + ChainLockCounter::LEN,
+        seeds = [
+            b"chain_lock_counter",
+            pool.key().as_ref(),
+            &destination_chain_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub chain_lock_counter: Account<'info, ChainLockCounter>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LockForBridgeBatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = !pool.paused @ BridgeError::PoolPaused,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = user_stablecoin_ata.owner == sender.key()
+    )]
+    pub user_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// PDA: ["user_lock", pool.key(), sender.key()]. Tracks the sender's cooldown.
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + UserLockState::LEN,
+        seeds = [b"user_lock", pool.key().as_ref(), sender.key().as_ref()],
+        bump
+    )]
+    pub user_lock_state: Account<'info, UserLockState>,
+
+    /// PDA: ["user_index", pool.key(), sender.key()]. Ring buffer of the sender's recent nonces.
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + UserLockIndex::LEN,
+        seeds = [b"user_index", pool.key().as_ref(), sender.key().as_ref()],
+        bump
+    )]
+    pub user_lock_index: Account<'info, UserLockIndex>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseLockedLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.is_relayer(&relayer.key()) @ BridgeError::UnauthorizedRelayer,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bridge_lock.pool == pool.key() @ BridgeError::InvalidBridgeLock
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lock sender's token account receiving the refund.
+    #[account(
+        mut,
+        constraint = sender_stablecoin_ata.mint == pool.stablecoin_mint @ BridgeError::InvalidRefundAccount,
+        constraint = sender_stablecoin_ata.owner == bridge_lock.sender @ BridgeError::InvalidRefundAccount
+    )]
+    pub sender_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ForceRelease<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bridge_lock.pool == pool.key() @ BridgeError::InvalidBridgeLock
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lock sender's token account receiving the refund.
+    #[account(
+        mut,
+        constraint = sender_stablecoin_ata.mint == pool.stablecoin_mint @ BridgeError::InvalidRefundAccount,
+        constraint = sender_stablecoin_ata.owner == bridge_lock.sender @ BridgeError::InvalidRefundAccount
+    )]
+    pub sender_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveRelease<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.is_relayer(&relayer.key()) @ BridgeError::UnauthorizedRelayer,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bridge_lock.pool == pool.key() @ BridgeError::InvalidBridgeLock
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseWithAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = bridge_lock.pool == pool.key() @ BridgeError::InvalidBridgeLock
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Lock sender's token account receiving the refund.
+    #[account(
+        mut,
+        constraint = sender_stablecoin_ata.mint == pool.stablecoin_mint @ BridgeError::InvalidRefundAccount,
+        constraint = sender_stablecoin_ata.owner == bridge_lock.sender @ BridgeError::InvalidRefundAccount
+    )]
+    pub sender_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: instructions sysvar, used to read the preceding Ed25519 verify instruction.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteBridge<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.is_relayer(&relayer.key()) @ BridgeError::UnauthorizedRelayer,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = bridge_lock.pool == pool.key() @ BridgeError::InvalidBridgeLock
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Relayer-controlled token account receiving the settled amount.
+    #[account(
+        mut,
+        constraint = relayer_payout.mint == pool.stablecoin_mint,
+        constraint = relayer_payout.owner == relayer.key()
+    )]
+    pub relayer_payout: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBridgeLock<'info> {
+    #[account(
+        mut,
+        close = sender,
+        constraint = bridge_lock.sender == sender.key() @ BridgeError::InvalidBridgeLock,
+        constraint = (bridge_lock.released || bridge_lock.completed) @ BridgeError::LockNotSettled
+    )]
+    pub bridge_lock: Account<'info, BridgeLock>,
+
+    /// CHECK: rent refund destination; constrained to bridge_lock.sender
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeRate<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PauseResumePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PauseResumeProtocol<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoRevertAfter<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLockWindow<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOutflowLimit<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRelayerFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCompactEvents<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinLockAmount<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinLpHold<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRateLimits<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxLockedLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRequireLiquidityBacking<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollectRoundingDust<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolCap<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepStaleLocks<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = insurance_vault.mint == pool.stablecoin_mint @ BridgeError::InvalidInsuranceVault,
+        constraint = insurance_vault.owner == pool.key() @ BridgeError::InvalidInsuranceVault,
+        constraint = insurance_vault.key() != pool.vault @ BridgeError::InvalidInsuranceVault,
+        constraint = insurance_vault.key() != pool.fee_vault @ BridgeError::InvalidInsuranceVault
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = funder_stablecoin_ata.mint == pool.stablecoin_mint,
+        constraint = funder_stablecoin_ata.owner == funder.key()
+    )]
+    pub funder_stablecoin_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = insurance_vault.key() == pool.insurance_vault @ BridgeError::InvalidInsuranceVault
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = insurance_vault.key() == pool.insurance_vault @ BridgeError::InvalidInsuranceVault
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.mint == pool.stablecoin_mint,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token account receiving the collected fees.
+    #[account(
+        mut,
+        constraint = destination.mint == pool.stablecoin_mint
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's insurance vault; required when insurance_fee_bps is non-zero.
+    #[account(
+        mut,
+        constraint = insurance_vault.key() == pool.insurance_vault @ BridgeError::InvalidInsuranceVault
+    )]
+    pub insurance_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetInsuranceFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateLpMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = new_lp_token_mint.key() != stablecoin_mint.key(),
+        constraint = new_lp_token_mint.key() != pool.lp_token_mint @ BridgeError::InvalidPoolState,
+        constraint = new_lp_token_mint.to_account_info().owner == stablecoin_mint.to_account_info().owner @ BridgeError::InvalidPoolState,
+        constraint = new_lp_token_mint.decimals == stablecoin_mint.decimals @ BridgeError::DecimalMismatch,
+        constraint = new_lp_token_mint.supply == 0 @ BridgeError::InvalidPoolState,
+        constraint = new_lp_token_mint.freeze_authority.is_none() @ BridgeError::InvalidPoolState,
+        constraint = new_lp_token_mint.mint_authority == COption::Some(pool.key()) @ BridgeError::InvalidPoolState
+    )]
+    pub new_lp_token_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct SetLpMetadata<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Pays rent for the metadata account on first call.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(address = pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Token Metadata PDA of the LP mint; validated by seeds and by the metadata program.
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), lp_token_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ResyncLpSupply<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(address = pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct BeginDraining<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: may predate the current Pool layout, so it is grown and deserialized in
+    /// migrate_pool (which checks the admin); address and owner are checked here.
+    #[account(
+        mut,
+        seeds = [b"pool", stablecoin_mint.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    /// Becomes pool.fee_vault for a pool that predates it (validated in migrate_pool);
+    /// otherwise unused.
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Must be pool.lp_token_mint (checked in migrate_pool).
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: may predate the current Config layout, so it is grown and deserialized in
+    /// migrate_config (which checks the admin); address and owner are checked here.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        owner = crate::ID
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateBridgeLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: may predate the current BridgeLock layout, so it is grown and deserialized in
+    /// migrate_bridge_lock (which checks it belongs to pool); owner is checked here.
+    #[account(mut, owner = crate::ID)]
+    pub bridge_lock: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool.vault,
+        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool.fee_vault,
+        constraint = fee_vault.owner == pool.key() @ BridgeError::InvalidPoolState
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = pool.stablecoin_mint)]
+    pub stablecoin_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token account receiving the residual vault balance.
+    #[account(
+        mut,
+        constraint = destination.mint == pool.stablecoin_mint
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// Records the closed pool's counters for a later re-initialization.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + PoolTombstone::LEN,
+        seeds = [b"pool_tombstone", pool.stablecoin_mint.as_ref()],
+        bump
+    )]
+    pub pool_tombstone: Account<'info, PoolTombstone>,
 
-    /// Pause pool: no deposits, withdrawals, or lock_for_bridge. Admin-only (circuit breaker).
-    pub fn pause_pool(_ctx: Context<PauseResumePool>) -> Result<()> {
-        _ctx.accounts.pool.paused = true;
-        Ok(())
-    }
+    #[account(mut)]
+    pub admin: Signer<'info>,
 
-    /// Resume pool after pause. Admin-only.
-    pub fn resume_pool(_ctx: Context<PauseResumePool>) -> Result<()> {
-        _ctx.accounts.pool.paused = false;
-        Ok(())
-    }
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-// --- Account structs and validation ---
-
 #[derive(Accounts)]
-pub struct InitializeConfig<'info> {
+pub struct ProposeConfigAdmin<'info> {
     #[account(
-        init,
-        payer = admin,
-        space = 8 + Config::LEN,
+        mut,
         seeds = [b"config"],
-        bump
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
     pub config: Account<'info, Config>,
 
-    #[account(mut)]
     pub admin: Signer<'info>,
+}
 
-    /// Relayer authority (can call release_locked_liquidity).
-    /// Unchecked: stored for later validation.
-    /// CHECK: relayer pubkey stored in config
-    pub relayer: UncheckedAccount<'info>,
+#[derive(Accounts)]
+pub struct AcceptConfigAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin == new_admin.key() @ BridgeError::UnauthorizedPendingAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
 
-    pub system_program: Program<'info, System>,
+    pub new_admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct InitializePool<'info> {
+pub struct ManageRelayers<'info> {
     #[account(
-        init,
-        payer = admin,
-        space = 8 + Pool::LEN,
-        seeds = [b"pool", stablecoin_mint.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub pool: Account<'info, Pool>,
+    pub config: Account<'info, Config>,
 
-    #[account(mut)]
     pub admin: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetStuckLockTimeout<'info> {
     #[account(
         mut,
-        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
     pub config: Account<'info, Config>,
 
-    pub stablecoin_mint: Account<'info, Mint>,
+    pub admin: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetMinLockAge<'info> {
     #[account(
         mut,
-        constraint = vault.mint == stablecoin_mint.key(),
-        constraint = vault.owner == pool.key() @ BridgeError::InvalidPoolState
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
 
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolFeeShare<'info> {
     #[account(
         mut,
-        constraint = lp_token_mint.key() != stablecoin_mint.key(),
-        constraint = lp_token_mint.mint_authority == Some(pool.key()) @ BridgeError::InvalidPoolState
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub lp_token_mint: Account<'info, Mint>,
+    pub config: Account<'info, Config>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct DepositLiquidity<'info> {
+pub struct SetRequireUnfreezableMint<'info> {
     #[account(
         mut,
-        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
-        bump = pool.bump,
-        constraint = !pool.paused @ BridgeError::PoolPaused,
-        constraint = pool.config == config.key()
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub pool: Account<'info, Pool>,
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetMaxFeeBps<'info> {
     #[account(
-        constraint = config.paused == false @ BridgeError::PoolPaused
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
     pub config: Account<'info, Config>,
 
-    #[account(mut)]
-    pub depositor: Signer<'info>,
+    pub admin: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetAllowTransferHookMints<'info> {
     #[account(
         mut,
-        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
-        constraint = user_stablecoin_ata.owner == depositor.key()
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub user_stablecoin_ata: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
 
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTimelock<'info> {
     #[account(
         mut,
-        constraint = user_lp_ata.mint == pool.lp_token_mint,
-        constraint = user_lp_ata.owner == depositor.key()
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub user_lp_ata: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct SetReleaseThreshold<'info> {
     #[account(
         mut,
-        constraint = vault.key() == pool.vault,
-        constraint = vault.mint == pool.stablecoin_mint
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
 
+#[derive(Accounts)]
+pub struct ProposePoolAdmin<'info> {
     #[account(
         mut,
-        constraint = lp_token_mint.key() == pool.lp_token_mint
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub lp_token_mint: Account<'info, Mint>,
+    pub pool: Account<'info, Pool>,
 
-    pub token_program: Program<'info, Token>,
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawLiquidity<'info> {
+pub struct AcceptPoolAdmin<'info> {
     #[account(
         mut,
         seeds = [b"pool", pool.stablecoin_mint.as_ref()],
         bump = pool.bump,
-        constraint = !pool.paused @ BridgeError::PoolPaused,
-        constraint = pool.config == config.key()
+        constraint = pool.pending_admin == new_admin.key() @ BridgeError::UnauthorizedPendingAdmin,
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
     pub pool: Account<'info, Pool>,
 
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct SetChainName<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
     pub config: Account<'info, Config>,
 
     #[account(mut)]
-    pub withdrawer: Signer<'info>,
+    pub admin: Signer<'info>,
 
     #[account(
-        mut,
-        constraint = user_lp_ata.mint == pool.lp_token_mint,
-        constraint = user_lp_ata.owner == withdrawer.key()
+        init_if_needed,
+        payer = admin,
+        space = 8 + ChainInfo::LEN,
+        seeds = [b"chain".as_ref(), &chain_id.to_le_bytes()],
+        bump
     )]
-    pub user_lp_ata: Account<'info, TokenAccount>,
+    pub chain_info: Account<'info, ChainInfo>,
 
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct SetChainAddressFormat<'info> {
     #[account(
-        mut,
-        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
-        constraint = user_stablecoin_ata.owner == withdrawer.key()
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub user_stablecoin_ata: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
 
     #[account(
-        mut,
-        constraint = vault.key() == pool.vault,
-        constraint = vault.mint == pool.stablecoin_mint
+        init_if_needed,
+        payer = admin,
+        space = 8 + ChainInfo::LEN,
+        seeds = [b"chain".as_ref(), &chain_id.to_le_bytes()],
+        bump
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub chain_info: Account<'info, ChainInfo>,
 
-    #[account(mut, constraint = lp_token_mint.key() == pool.lp_token_mint)]
-    pub lp_token_mint: Account<'info, Mint>,
-
-    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(amount: u64, destination_chain_id: u64, recipient_address: [u8; 32])]
-pub struct LockForBridge<'info> {
+#[instruction(chain_id: u64)]
+pub struct AddSupportedChain<'info> {
     #[account(
         mut,
-        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
-        bump = pool.bump,
-        constraint = !pool.paused @ BridgeError::PoolPaused,
-        constraint = pool.config == config.key()
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub pool: Account<'info, Pool>,
-
     pub config: Account<'info, Config>,
 
     #[account(mut)]
-    pub sender: Signer<'info>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ChainInfo::LEN,
+        seeds = [b"chain".as_ref(), &chain_id.to_le_bytes()],
+        bump
+    )]
+    pub chain_info: Account<'info, ChainInfo>,
+
+    pub system_program: Program<'info, System>,
+}
 
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct RemoveSupportedChain<'info> {
     #[account(
         mut,
-        constraint = user_stablecoin_ata.mint == pool.stablecoin_mint,
-        constraint = user_stablecoin_ata.owner == sender.key()
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
     )]
-    pub user_stablecoin_ata: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
 
     #[account(
         mut,
-        constraint = vault.key() == pool.vault,
-        constraint = vault.mint == pool.stablecoin_mint
+        seeds = [b"chain".as_ref(), &chain_id.to_le_bytes()],
+        bump = chain_info.bump
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub chain_info: Account<'info, ChainInfo>,
+}
 
-    /// PDA: ["bridge_lock", pool.key(), nonce]. Client derives using current pool.next_lock_nonce.
+#[derive(Accounts)]
+pub struct SetCooldownExemption<'info> {
     #[account(
-        init,
-        payer = sender,
-        space = 8 + BridgeLock::LEN,
-        seeds = [
-            b"bridge_lock",
-            pool.key().as_ref(),
-            &pool.next_lock_nonce.to_le_bytes(),
-        ],
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
+        constraint = pool.config == config.key(),
+        constraint = pool.version == Pool::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == Config::CURRENT_VERSION @ BridgeError::AccountVersionMismatch
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: user whose exemption is set; only used as a PDA seed
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + UserLockState::LEN,
+        seeds = [b"user_lock", pool.key().as_ref(), user.key().as_ref()],
         bump
     )]
-    pub bridge_lock: Account<'info, BridgeLock>,
+    pub user_lock_state: Account<'info, UserLockState>,
 
-    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ReleaseLockedLiquidity<'info> {
+pub struct CheckDeposit<'info> {
     #[account(
-        mut,
         seeds = [b"pool", pool.stablecoin_mint.as_ref()],
         bump = pool.bump,
         constraint = pool.config == config.key()
@@ -571,47 +6435,95 @@ pub struct ReleaseLockedLiquidity<'info> {
     pub pool: Account<'info, Pool>,
 
     #[account(
-        constraint = config.relayer == relayer.key() @ BridgeError::UnauthorizedRelayer
+        seeds = [b"config"],
+        bump = config.bump
     )]
     pub config: Account<'info, Config>,
 
-    pub relayer: Signer<'info>,
+    #[account(constraint = lp_token_mint.key() == pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+}
 
+#[derive(Accounts)]
+pub struct QuoteLiquidity<'info> {
     #[account(
-        mut,
-        constraint = bridge_lock.pool == pool.key() @ BridgeError::InvalidBridgeLock
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.config == config.key()
     )]
-    pub bridge_lock: Account<'info, BridgeLock>,
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint = lp_token_mint.key() == pool.lp_token_mint)]
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateFeeRate<'info> {
+#[instruction(amount: u64, destination_chain_id: u64)]
+pub struct CheckLock<'info> {
     #[account(
-        mut,
         seeds = [b"pool", pool.stablecoin_mint.as_ref()],
         bump = pool.bump,
-        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
         constraint = pool.config == config.key()
     )]
     pub pool: Account<'info, Pool>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
     pub config: Account<'info, Config>,
 
-    pub admin: Signer<'info>,
+    /// CHECK: the would-be lock sender; only used as a PDA seed
+    pub sender: UncheckedAccount<'info>,
+
+    /// PDA: ["user_lock", pool.key(), sender.key()]; omit if the sender has never locked on
+    /// this pool.
+    #[account(
+        seeds = [b"user_lock", pool.key().as_ref(), sender.key().as_ref()],
+        bump = user_lock_state.bump
+    )]
+    pub user_lock_state: Option<Account<'info, UserLockState>>,
+
+    /// Registered metadata for destination_chain_id; omit if the chain was never registered.
+    #[account(
+        seeds = [b"chain".as_ref(), &destination_chain_id.to_le_bytes()],
+        bump = chain_info.bump
+    )]
+    pub chain_info: Option<Account<'info, ChainInfo>>,
 }
 
 #[derive(Accounts)]
-pub struct PauseResumePool<'info> {
+pub struct GetLockStatus<'info> {
+    pub bridge_lock: Account<'info, BridgeLock>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolState<'info> {
     #[account(
-        mut,
         seeds = [b"pool", pool.stablecoin_mint.as_ref()],
         bump = pool.bump,
-        constraint = pool.admin == admin.key() @ BridgeError::UnauthorizedAdmin,
         constraint = pool.config == config.key()
     )]
     pub pool: Account<'info, Pool>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
     pub config: Account<'info, Config>,
+}
 
-    pub admin: Signer<'info>,
+#[derive(Accounts)]
+pub struct EffectiveFee<'info> {
+    #[account(
+        seeds = [b"pool", pool.stablecoin_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
 }
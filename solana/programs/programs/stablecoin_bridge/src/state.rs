@@ -5,6 +5,15 @@
 //! represent proportional share of pool.
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+/// Convert whole tokens to base units for a mint with the given decimals
+/// (e.g. 5 whole USDC at 6 decimals is 5_000_000). None on overflow.
+pub fn to_base_units(whole: u64, decimals: u8) -> Option<u64> {
+    10u64
+        .checked_pow(u32::from(decimals))
+        .and_then(|scale| whole.checked_mul(scale))
+}
 
 /// Global protocol config. Single instance per program.
 /// Holds admin and relayer authority for access control.
@@ -13,16 +22,91 @@ use anchor_lang::prelude::*;
 pub struct Config {
     /// Authority that can update fee rates, pause pools, and manage relayers.
     pub admin: Pubkey,
-    /// Authority allowed to call release_locked_liquidity (bridge revert).
-    pub relayer: Pubkey,
+    /// Authorities allowed to call release_locked_liquidity / complete_bridge.
+    /// Only the first `relayer_count` entries are active.
+    pub relayers: [Pubkey; Config::MAX_RELAYERS],
+    /// Number of active entries in `relayers`.
+    pub relayer_count: u8,
     /// Protocol-level pause: when true, no lock_for_bridge or deposits.
     pub paused: bool,
     /// Bump used to derive the config PDA.
     pub bump: u8,
+    /// Proposed next admin; becomes admin once it signs accept_config_admin (default = none).
+    pub pending_admin: Pubkey,
+    /// Relayer approvals (approve_release) a lock needs before it can be released or completed
+    /// (0 = none). Never exceeds relayer_count.
+    pub release_threshold: u8,
+    /// Delay before a proposed pool fee-rate increase can be applied (apply_fee_rate).
+    pub fee_timelock_seconds: u32,
+    /// Lock age after which the admin may force_release it (0 = force release disabled).
+    pub stuck_lock_timeout_seconds: u32,
+    /// Reason given for the latest pause_protocol.
+    pub pause_reason: PauseReason,
+    /// When true, initialize_pool rejects stablecoin mints with a freeze authority.
+    pub require_unfreezable_mint: bool,
+    /// Share (bps) of collected fees routed to pool fee vaults; the rest stays in the pool as
+    /// LP yield.
+    pub protocol_fee_share_bps: u16,
+    /// Sequence number of the next protocol-level event (see events.rs).
+    pub event_seq: u64,
+    /// Seconds after locked_at before relayers may revert a lock (0 = immediately); force_release
+    /// is exempt.
+    pub min_lock_age_seconds: u32,
+    /// Layout version; Config::CURRENT_VERSION once initialized or migrated.
+    pub version: u8,
+    /// Highest deposit, withdraw, or bridge fee (bps) a pool may be set to.
+    pub max_fee_bps: u16,
+    /// When false (the default), initialize_pool and migrate_lp_mint reject Token-2022 mints with
+    /// the TransferHook extension.
+    pub allow_transfer_hook_mints: bool,
 }
 
 impl Config {
-    pub const LEN: usize = 8 + 32 + 32 + 1 + 1;
+    pub const MAX_RELAYERS: usize = 8;
+    /// Bumped whenever fields are added; migrate_config brings older accounts up to it.
+    /// Version 2 added max_fee_bps, version 3 allow_transfer_hook_mints.
+    pub const CURRENT_VERSION: u8 = 3;
+    /// max_fee_bps at initialize_config (1%).
+    pub const DEFAULT_MAX_FEE_BPS: u16 = 100;
+    /// Upper bound set_max_fee_bps accepts (10%).
+    pub const MAX_FEE_BPS_CEILING: u16 = 1_000;
+    /// LEN of the original (unversioned) layout: admin, a single relayer, paused, bump. Its
+    /// accounts hold 8 + LEGACY_LEN bytes.
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 1 + 1;
+    pub const LEN: usize = 8
+        + 32
+        + 32 * Self::MAX_RELAYERS
+        + 1
+        + 1
+        + 1
+        + 32
+        + 1
+        + 4
+        + 4
+        + 1
+        + 1
+        + 2
+        + 8
+        + 4
+        + 1
+        + 2
+        + 1;
+
+    /// Take the next protocol-level event sequence number.
+    pub fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq;
+        self.event_seq = seq.wrapping_add(1);
+        seq
+    }
+
+    /// Currently authorized relayers.
+    pub fn active_relayers(&self) -> &[Pubkey] {
+        &self.relayers[..self.relayer_count as usize]
+    }
+
+    pub fn is_relayer(&self, key: &Pubkey) -> bool {
+        self.active_relayers().contains(key)
+    }
 }
 
 /// One pool per stablecoin. Holds vault ATA and LP mint; tracks liquidity.
@@ -35,13 +119,14 @@ pub struct Pool {
     pub vault: Pubkey,
     /// LP token mint. Minted on deposit, burned on withdraw.
     pub lp_token_mint: Pubkey,
-    /// Total stablecoin liquidity in pool (available + locked). Invariant: total = available + locked.
+    /// Total stablecoin liquidity in pool. Invariant: total = available + reserved + locked.
     pub total_liquidity: u64,
-    /// Liquidity available for LP withdrawals (not locked for bridge).
+    /// LP-owned liquidity free to be redeemed; LP redemptions are paid out of it alone.
     pub available_liquidity: u64,
-    /// Amount currently locked in bridge intents (released on revert or when bridge completes off-chain).
+    /// Sender funds escrowed by bridge intents. Never LP-owned: refunded to the sender on
+    /// revert or paid out via complete_bridge.
     pub locked_liquidity: u64,
-    /// Fee in basis points (0..=10000) taken on deposits/withdraws or bridge. Applied per pool.
+    /// Deprecated alias of deposit_fee_bps, kept in sync for one migration release.
     pub fee_rate_bps: u16,
     /// Pool admin (can update fee, pause this pool).
     pub admin: Pubkey,
@@ -51,16 +136,256 @@ pub struct Pool {
     pub paused: bool,
     /// PDA bump for this pool.
     pub bump: u8,
-    /// Max amount that can be locked in a single lock_for_bridge call (rate limit).
+    /// Max amount that can be locked in a single lock_for_bridge call (rate limit), in
+    /// stablecoin base units; see to_base_units for converting whole tokens.
     pub max_lock_per_tx: u64,
     /// Optional: cooldown in seconds between lock_for_bridge from same user (0 = disabled).
+    /// Tracked per user in UserLockState; cooldown-exempt users skip it.
     pub lock_cooldown_seconds: u32,
     /// Next nonce to assign to a new BridgeLock (incremented on each lock_for_bridge).
+    /// Strictly increasing and never reset: it seeds the BridgeLock PDA, and reusing a nonce
+    /// after close_bridge_lock would re-create a settled lock's address.
     pub next_lock_nonce: u64,
+    /// Pool's token account receiving protocol fees. Fees never count toward pool liquidity.
+    pub fee_vault: Pubkey,
+    /// LP supply tracked by the program: minted LP plus the MINIMUM_LIQUIDITY dead shares
+    /// locked on first deposit. Single source of truth for share math.
+    pub lp_supply: u64,
+    /// Pool is being retired: no deposits or new locks; withdrawals and releases stay open.
+    pub draining: bool,
+    /// Proposed next pool admin; becomes admin once it signs accept_pool_admin (default = none).
+    pub pending_admin: Pubkey,
+    /// Auto-revert threshold in seconds (0 = disabled). If the pool was paused at least this
+    /// long, resume_pool arms a keeper sweep reverting locks older than this.
+    pub auto_revert_after_seconds: u32,
+    /// Timestamp of the last pause_pool.
+    pub paused_at: i64,
+    /// Set by resume_pool after an extended pause; sweep_stale_locks is allowed while true.
+    pub auto_revert_pending: bool,
+    /// Locks with locked_at <= this cutoff are eligible for the auto-revert sweep.
+    pub auto_revert_cutoff: i64,
+    /// Pool's token account holding the insurance fund (default = not configured).
+    pub insurance_vault: Pubkey,
+    /// Insurance fund balance tracked by the program (funded, minus paid out).
+    pub insurance_balance: u64,
+    /// Max total locked per rolling window (0 = no window limit).
+    pub max_lock_per_window: u64,
+    /// Length of the lock rate-limit window in seconds.
+    pub lock_window_seconds: u32,
+    /// Start of the current lock window.
+    pub window_start: i64,
+    /// Amount locked in the current window.
+    pub window_volume: u64,
+    /// Smallest amount accepted by lock_for_bridge (0 = no minimum), in stablecoin base units.
+    /// Deters dust-spam locks.
+    pub min_lock_amount: u64,
+    /// Lifetime protocol fees swept out of fee_vault via collect_fees.
+    pub total_fees_collected: u64,
+    /// Fee in basis points (0..=10000) taken on deposit_liquidity; routed to fee_vault.
+    pub deposit_fee_bps: u16,
+    /// Fee in basis points (0..=10000) taken from stablecoin_out on withdraw_liquidity;
+    /// routed to fee_vault.
+    pub withdraw_fee_bps: u16,
+    /// Fee in basis points (0..=10000) taken from the locked amount on lock_for_bridge;
+    /// routed to fee_vault.
+    pub bridge_fee_bps: u16,
+    /// Emergency exit: withdraw_liquidity stays open while the pool is paused (deposits and
+    /// locks stay blocked).
+    pub emergency_mode: bool,
+    /// Cumulative net amount ever locked via lock_for_bridge.
+    pub lifetime_locked_volume: u64,
+    /// Cumulative amount ever settled out of escrow (reverted or completed).
+    pub lifetime_released_volume: u64,
+    /// Cap on total_liquidity enforced on deposits (0 = unlimited).
+    pub max_total_liquidity: u64,
+    /// Seconds a depositor must wait after their last deposit before withdrawing (0 = no lockup).
+    pub min_lp_hold_seconds: u32,
+    /// Reentrancy guard: set while a mutating instruction is running on this pool.
+    pub in_progress: bool,
+    /// Proposed deposit fee, applied by apply_fee_rate once fee_effective_at passes.
+    pub pending_deposit_fee_bps: u16,
+    /// Proposed withdraw fee.
+    pub pending_withdraw_fee_bps: u16,
+    /// Proposed bridge fee.
+    pub pending_bridge_fee_bps: u16,
+    /// When the pending fee rates may be applied (0 = no pending proposal).
+    pub fee_effective_at: i64,
+    /// Rolling window length for the outflow circuit breaker, in seconds.
+    pub outflow_window_seconds: u32,
+    /// Withdrawal plus completion outflow per window above which the pool auto-pauses (0 = disabled).
+    pub max_outflow_per_window: u64,
+    /// Start of the current outflow window.
+    pub outflow_window_start: i64,
+    /// Outflow recorded in the current window.
+    pub outflow_window_volume: u64,
+    /// Decimals of the stablecoin mint, recorded at initialize_pool.
+    pub stablecoin_decimals: u8,
+    /// Decimals of the LP mint; always equal to stablecoin_decimals so first-deposit 1:1 pricing holds.
+    pub lp_decimals: u8,
+    /// Cumulative yield credited to LPs via distribute_yield.
+    pub lifetime_yield_distributed: u64,
+    /// Timestamp of the last distribute_yield (0 = never).
+    pub last_yield_timestamp: i64,
+    /// Share of each collect_fees sweep routed to the insurance vault, in basis points.
+    pub insurance_fee_bps: u16,
+    /// Emit BridgeIntentCompact instead of the full BridgeIntent on lock (cheaper logs).
+    pub compact_events: bool,
+    /// Reason for the latest pause (set by pause_pool or the outflow circuit breaker).
+    pub pause_reason: PauseReason,
+    /// Flat fee (stablecoin base units) reserved from each lock to reimburse the completing
+    /// relayer's destination gas (0 = none). Snapshotted into BridgeLock.relayer_fee.
+    pub relayer_fee: u64,
+    /// Cap on locked_liquidity, the total escrow relayers are responsible for at once (0 = no cap).
+    pub max_locked_liquidity: u64,
+    /// Stablecoin owed to queued WithdrawalRequests (LP already burned). Excluded from LP value
+    /// and from what withdrawals and backed locks may draw on until fulfill_withdrawal pays
+    /// it.
+    pub queued_withdrawals: u64,
+    /// When set, a lock must be covered by available_liquidity and reserves the amount it
+    /// escrows out of it until the lock is reverted or completed.
+    pub require_liquidity_backing: bool,
+    /// LP liquidity held back to back pending locks' destination payouts. Still LP-owned
+    /// (counted in share pricing) but not redeemable until released to available_liquidity.
+    pub reserved_liquidity: u64,
+    /// Sequence number of the next event about this pool (see events.rs).
+    pub event_seq: u64,
+    /// Route deposit rounding dust (stablecoin credited beyond the value of the LP minted for it)
+    /// to fee_vault instead of leaving it to existing LPs.
+    pub collect_rounding_dust: bool,
+    /// Layout version; Pool::CURRENT_VERSION once initialized or migrated.
+    pub version: u8,
 }
 
 impl Pool {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 2 + 32 + 32 + 1 + 1 + 8 + 4 + 8;
+    pub const LEN: usize = 8
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 2
+        + 32
+        + 32
+        + 1
+        + 1
+        + 8
+        + 4
+        + 8
+        + 32
+        + 8
+        + 1
+        + 32
+        + 4
+        + 8
+        + 1
+        + 8
+        + 32
+        + 8
+        + 8
+        + 4
+        + 8
+        + 8
+        + 8
+        + 8
+        + 2
+        + 2
+        + 2
+        + 1
+        + 8
+        + 8
+        + 8
+        + 4
+        + 1
+        + 2
+        + 2
+        + 2
+        + 8
+        + 4
+        + 8
+        + 8
+        + 8
+        + 1
+        + 1
+        + 8
+        + 8
+        + 2
+        + 1
+        + 1
+        + 8
+        + 8
+        + 1
+        + 8
+        + 8
+        + 1
+        + 1;
+
+    /// LP shares permanently locked on the first deposit (counted in lp_supply, never minted),
+    /// analogous to Uniswap V2's MINIMUM_LIQUIDITY. Makes share-price inflation via donation
+    /// prohibitively expensive.
+    pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+    /// Fixed-point scale of share_price (1e9 = one stablecoin unit per LP unit).
+    pub const SHARE_PRICE_SCALE: u64 = 1_000_000_000;
+
+    /// Bumped whenever fields are added; migrate_pool brings older accounts up to it.
+    pub const CURRENT_VERSION: u8 = 1;
+
+    /// Supply the LP mint should report: lp_supply minus the never-minted dead shares.
+    pub fn expected_mint_supply(&self) -> u64 {
+        self.lp_supply.saturating_sub(Self::MINIMUM_LIQUIDITY)
+    }
+
+    /// Convert a whole-token amount of this pool's stablecoin to base units.
+    pub fn to_base_units(&self, whole: u64) -> Option<u64> {
+        to_base_units(whole, self.stablecoin_decimals)
+    }
+
+    /// Take the next event sequence number for this pool.
+    pub fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq;
+        self.event_seq = seq.wrapping_add(1);
+        seq
+    }
+
+    /// Stablecoin owned by LPs: available_liquidity plus the part reserved to back pending
+    /// locks, less what queued withdrawals are owed. LP shares are priced against this.
+    pub fn lp_value(&self) -> u64 {
+        self.available_liquidity
+            .saturating_add(self.reserved_liquidity)
+            .saturating_sub(self.queued_withdrawals)
+    }
+
+    /// available_liquidity not already owed to queued withdrawals: what a withdrawal or a
+    /// backed lock may draw on.
+    pub fn unqueued_liquidity(&self) -> u64 {
+        self.available_liquidity
+            .saturating_sub(self.queued_withdrawals)
+    }
+
+    /// Stablecoin per LP unit, scaled by SHARE_PRICE_SCALE (rounded down). A pool with no LP
+    /// supply reports 1:1, the rate the first depositor gets. Never overflows.
+    pub fn share_price_u128(&self) -> u128 {
+        if self.lp_supply == 0 {
+            return u128::from(Self::SHARE_PRICE_SCALE);
+        }
+        u128::from(self.lp_value()) * u128::from(Self::SHARE_PRICE_SCALE)
+            / u128::from(self.lp_supply)
+    }
+
+    /// share_price_u128 saturated to u64.
+    pub fn share_price(&self) -> u64 {
+        u64::try_from(self.share_price_u128()).unwrap_or(u64::MAX)
+    }
+
+    /// Share of total_liquidity escrowed by pending bridge locks, in basis points.
+    pub fn utilization_bps(&self) -> u16 {
+        if self.total_liquidity == 0 {
+            return 0;
+        }
+        let bps = u128::from(self.locked_liquidity) * 10_000 / u128::from(self.total_liquidity);
+        u16::try_from(bps).unwrap_or(10_000)
+    }
 }
 
 /// Single bridge lock record. Created on lock_for_bridge; closed or marked released on release_locked_liquidity.
@@ -80,12 +405,472 @@ pub struct BridgeLock {
     pub recipient_address: [u8; 32],
     /// User who initiated the lock (for accounting / future use).
     pub sender: Pubkey,
-    /// True if release_locked_liquidity was called (funds returned to available).
+    /// True once the full amount has been refunded to the sender (release_locked_liquidity,
+    /// or release_partial calls summing to amount).
     pub released: bool,
     /// Timestamp when lock was created (for cooldown / rate limits).
     pub locked_at: i64,
+    /// True if complete_bridge was called (funds paid out after destination delivery).
+    pub completed: bool,
+    /// Amount already refunded to the sender via partial or full releases.
+    pub released_amount: u64,
+    /// Relayers that called approve_release; the first approval_count entries are set.
+    pub approvals: [Pubkey; Config::MAX_RELAYERS],
+    /// Number of set entries in approvals.
+    pub approval_count: u8,
+    /// Settlement the pending approvals authorize (meaningful while approval_count > 0).
+    pub approved_action: ApprovalAction,
+    /// Amount the pending approvals authorize moving.
+    pub approved_amount: u64,
+    /// Slot the lock was created in; relayers enforce a confirmation delta off-chain.
+    pub locked_slot: u64,
+    /// Part of amount reserved for the relayer; destination delivers amount - relayer_fee.
+    pub relayer_fee: u64,
+    /// Client-chosen idempotency tag (all zero = none).
+    pub client_tag: [u8; 16],
+    /// Position of this lock among the pool's locks toward destination_chain_id (0-based).
+    pub chain_index: u64,
+    /// Created by a pool with require_liquidity_backing: its outstanding amount is held in
+    /// reserved_liquidity.
+    pub backed: bool,
+    /// Layout version; BridgeLock::CURRENT_VERSION once created or migrated.
+    pub version: u8,
 }
 
 impl BridgeLock {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 32 + 1 + 8;
+    /// Maximum number of locks created by one lock_for_bridge_batch call (compute bound).
+    pub const MAX_BATCH_LOCKS: usize = 4;
+
+    /// Bumped whenever fields are added; migrate_bridge_lock brings older accounts up to it.
+    pub const CURRENT_VERSION: u8 = 1;
+
+    pub const LEN: usize = 8
+        + 32
+        + 8
+        + 8
+        + 32
+        + 32
+        + 1
+        + 8
+        + 1
+        + 8
+        + 32 * Config::MAX_RELAYERS
+        + 1
+        + 1
+        + 8
+        + 8
+        + 8
+        + 16
+        + 8
+        + 1
+        + 1;
+
+    /// Keccak hash of the lock's delivery payload, emitted in BridgeIntentCompact. Covers
+    /// pool, nonce, sender, amount, destination_chain_id, recipient_address, locked_slot, and
+    /// relayer_fee (integers little-endian), so relayers can check the fetched account against
+    /// the event.
+    pub fn intent_hash(&self) -> [u8; 32] {
+        keccak::hashv(&[
+            self.pool.as_ref(),
+            &self.nonce.to_le_bytes(),
+            self.sender.as_ref(),
+            &self.amount.to_le_bytes(),
+            &self.destination_chain_id.to_le_bytes(),
+            &self.recipient_address,
+            &self.locked_slot.to_le_bytes(),
+            &self.relayer_fee.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Escrowed amount not yet released or settled.
+    pub fn outstanding_amount(&self) -> u64 {
+        self.amount.saturating_sub(self.released_amount)
+    }
+
+    /// Drop all pending approvals (once they are used, or to start a new approval round).
+    pub fn clear_approvals(&mut self) {
+        self.approvals = [Pubkey::default(); Config::MAX_RELAYERS];
+        self.approval_count = 0;
+        self.approved_action = ApprovalAction::default();
+        self.approved_amount = 0;
+    }
+
+    /// Settlement status derived from the lock's flags.
+    pub fn status(&self) -> LockStatus {
+        if self.completed {
+            LockStatus::Completed
+        } else if self.released {
+            LockStatus::Released
+        } else if self.released_amount > 0 {
+            LockStatus::PartiallyReleased
+        } else {
+            LockStatus::Pending
+        }
+    }
+}
+
+/// Per-user lock state for one pool. Created on first lock_for_bridge (or when admin grants
+/// a cooldown exemption). PDA: ["user_lock", pool, user].
+#[account]
+#[derive(Default)]
+pub struct UserLockState {
+    /// Pool this state belongs to.
+    pub pool: Pubkey,
+    /// User this state tracks.
+    pub user: Pubkey,
+    /// Timestamp of the user's last lock_for_bridge (0 = never locked).
+    pub last_lock_at: i64,
+    /// Set by pool admin for trusted market makers: skips lock cooldown (limits still apply).
+    pub cooldown_exempt: bool,
+    /// PDA bump.
+    pub bump: u8,
+    /// Client tags of recent locks, checked to reject duplicate submissions.
+    pub recent_tags: [[u8; 16]; UserLockState::MAX_RECENT_TAGS],
+    /// Lock timestamp of each entry in recent_tags.
+    pub recent_tag_times: [i64; UserLockState::MAX_RECENT_TAGS],
+    /// Slot in recent_tags the next tag is written to (oldest entry).
+    pub next_tag_slot: u8,
+}
+
+impl UserLockState {
+    /// Number of recent client tags remembered per user.
+    pub const MAX_RECENT_TAGS: usize = 8;
+    /// How long a client tag blocks a repeated lock.
+    pub const TAG_WINDOW_SECONDS: i64 = 60 * 60;
+
+    pub const LEN: usize =
+        8 + 32 + 32 + 8 + 1 + 1 + 16 * Self::MAX_RECENT_TAGS + 8 * Self::MAX_RECENT_TAGS + 1;
+
+    /// True if tag was used by a lock within TAG_WINDOW_SECONDS of now. The all-zero tag
+    /// opts out of duplicate detection.
+    pub fn has_recent_tag(&self, tag: &[u8; 16], now: i64) -> bool {
+        *tag != [0u8; 16]
+            && self
+                .recent_tags
+                .iter()
+                .zip(self.recent_tag_times.iter())
+                .any(|(t, at)| t == tag && now.saturating_sub(*at) < Self::TAG_WINDOW_SECONDS)
+    }
+
+    /// Remember tag for duplicate detection, overwriting the oldest entry.
+    pub fn record_tag(&mut self, tag: [u8; 16], now: i64) {
+        if tag == [0u8; 16] {
+            return;
+        }
+        let slot = self.next_tag_slot as usize % Self::MAX_RECENT_TAGS;
+        self.recent_tags[slot] = tag;
+        self.recent_tag_times[slot] = now;
+        self.next_tag_slot = ((slot + 1) % Self::MAX_RECENT_TAGS) as u8;
+    }
+}
+
+/// Per-holder LP state for one pool, used for the LP lockup. Keyed by the LP holder (the deposit
+/// beneficiary), not the depositor. Created on the first deposit_liquidity crediting the holder
+/// (or first withdraw for LP received by transfer). PDA: ["user_lp", pool, user].
+#[account]
+#[derive(Default)]
+pub struct UserLpState {
+    /// Pool this state belongs to.
+    pub pool: Pubkey,
+    /// LP holder this state tracks.
+    pub user: Pubkey,
+    /// Start of the holder's lockup: the deposit time, averaged by LP amount over deposits
+    /// crediting the holder (0 = never credited by a deposit).
+    pub last_deposit_at: i64,
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl UserLpState {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1;
+
+    /// Record a deposit minting `minted` LP to a holder already holding `held` LP. The lockup
+    /// start moves towards `now` by the minted share, so a dust deposit from a third party
+    /// barely moves it. LP held before the first recorded deposit counts as acquired `now`.
+    pub fn record_deposit(&mut self, held: u64, minted: u64, now: i64) {
+        if self.last_deposit_at == 0 {
+            self.last_deposit_at = now;
+            return;
+        }
+        let total = u128::from(held) + u128::from(minted);
+        if total == 0 {
+            self.last_deposit_at = now;
+            return;
+        }
+        let since = now.saturating_sub(self.last_deposit_at).max(0) as u128;
+        let advance = since * u128::from(minted) / total;
+        self.last_deposit_at = self.last_deposit_at.saturating_add(advance as i64);
+    }
+}
+
+/// A withdrawal queued by request_withdrawal because available_liquidity could not pay it
+/// yet. The LP is burned at request time; fulfill_withdrawal pays it out and closes the
+/// account. One outstanding request per owner and pool. PDA: ["withdrawal_request", pool, owner].
+#[account]
+#[derive(Default)]
+pub struct WithdrawalRequest {
+    /// Pool the LP was redeemed from.
+    pub pool: Pubkey,
+    /// LP holder who queued the request; receives the payout and the account's rent.
+    pub owner: Pubkey,
+    /// LP burned for this request.
+    pub lp_tokens_burned: u64,
+    /// Stablecoin paid to the owner (net of the withdraw fee).
+    pub amount: u64,
+    /// Protocol share of the withdraw fee, paid to the fee vault on fulfillment.
+    pub protocol_fee: u64,
+    /// Time the request was queued.
+    pub requested_at: i64,
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+
+    /// Stablecoin leaving the vault on fulfillment (and held in Pool::queued_withdrawals).
+    pub fn owed(&self) -> u64 {
+        self.amount.saturating_add(self.protocol_fee)
+    }
+}
+
+/// Ring buffer of a sender's most recent lock nonces in one pool, so clients can find their
+/// locks from one account. Updated on lock_for_bridge. PDA: ["user_index", pool, sender].
+#[account]
+#[derive(Default)]
+pub struct UserLockIndex {
+    /// Pool this index belongs to.
+    pub pool: Pubkey,
+    /// Sender whose locks are indexed.
+    pub user: Pubkey,
+    /// Recent lock nonces; only the first count slots are meaningful.
+    pub nonces: [u64; UserLockIndex::CAPACITY],
+    /// Number of filled slots (at most CAPACITY).
+    pub count: u8,
+    /// Slot the next nonce is written to; the oldest entry once the buffer is full.
+    pub head: u8,
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl UserLockIndex {
+    /// Number of recent nonces kept; older entries roll off.
+    pub const CAPACITY: usize = 16;
+
+    pub const LEN: usize = 8 + 32 + 32 + 8 * Self::CAPACITY + 1 + 1 + 1;
+
+    /// Record a new lock nonce, overwriting the oldest once full.
+    pub fn push(&mut self, nonce: u64) {
+        self.nonces[self.head as usize] = nonce;
+        self.head = ((self.head as usize + 1) % Self::CAPACITY) as u8;
+        if (self.count as usize) < Self::CAPACITY {
+            self.count += 1;
+        }
+    }
+
+    /// Indexed nonces, oldest first.
+    pub fn recent(&self) -> Vec<u64> {
+        let start = if (self.count as usize) < Self::CAPACITY {
+            0
+        } else {
+            self.head as usize
+        };
+        (0..self.count as usize)
+            .map(|i| self.nonces[(start + i) % Self::CAPACITY])
+            .collect()
+    }
+}
+
+/// Admin-maintained metadata for a destination chain. PDA: ["chain", chain_id (LE bytes)].
+/// Also serves as the destination allowlist: locks require a ChainInfo with supported = true.
+#[account]
+#[derive(Default)]
+pub struct ChainInfo {
+    /// Destination chain id (matches BridgeLock.destination_chain_id).
+    pub chain_id: u64,
+    /// Short human-readable name (UTF-8, zero-padded), emitted in BridgeIntent.
+    pub name: [u8; 16],
+    /// PDA bump.
+    pub bump: u8,
+    /// Whether locks toward this chain are accepted (managed via add/remove_supported_chain).
+    pub supported: bool,
+    /// Recipient encoding on this chain; Evm20 requires right-aligned 20-byte addresses.
+    pub address_format: AddressFormat,
+}
+
+impl ChainInfo {
+    pub const LEN: usize = 8 + 8 + 16 + 1 + 1 + 1;
+}
+
+/// Per-chain lock counter for one pool, so relayers can page through the locks bound for
+/// their chain by chain_index. PDA: ["chain_lock_counter", pool, destination_chain_id].
+#[account]
+#[derive(Default)]
+pub struct ChainLockCounter {
+    /// Pool this counter belongs to.
+    pub pool: Pubkey,
+    /// Destination chain id counted.
+    pub chain_id: u64,
+    /// chain_index the next lock toward chain_id receives.
+    pub next_index: u64,
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl ChainLockCounter {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+/// Left behind by close_pool so a pool re-created for the same mint (same Pool PDA) resumes
+/// its counters instead of restarting them: lock nonces are never reused, and event_seq keeps
+/// increasing. PDA: ["pool_tombstone", stablecoin_mint].
+#[account]
+#[derive(Default)]
+pub struct PoolTombstone {
+    /// Stablecoin mint of the closed pool.
+    pub stablecoin_mint: Pubkey,
+    /// Closed pool's next_lock_nonce; initialize_pool starts from it.
+    pub next_lock_nonce: u64,
+    /// Closed pool's event_seq; initialize_pool starts from it.
+    pub event_seq: u64,
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl PoolTombstone {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+/// Snapshot of a pool's liquidity and status (returned by get_pool_state).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolView {
+    pub total_liquidity: u64,
+    pub available_liquidity: u64,
+    pub reserved_liquidity: u64,
+    pub locked_liquidity: u64,
+    pub queued_withdrawals: u64,
+    pub lp_supply: u64,
+    /// Stablecoin per LP unit, scaled by Pool::SHARE_PRICE_SCALE.
+    pub share_price: u64,
+    /// locked_liquidity / total_liquidity in basis points.
+    pub utilization_bps: u16,
+    pub paused: bool,
+    pub pause_reason: PauseReason,
+    pub protocol_paused: bool,
+    pub emergency_mode: bool,
+    pub draining: bool,
+}
+
+/// Settlement status of a BridgeLock (returned by get_lock_status).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockStatus {
+    Pending,
+    Released,
+    Completed,
+    PartiallyReleased,
+}
+
+/// Settlement a lock's relayer approvals authorize (approve_release).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApprovalAction {
+    /// Refund to the sender: release_locked_liquidity, release_partial, release_with_attestation.
+    #[default]
+    Release,
+    /// Pay out to the relayer: complete_bridge.
+    Complete,
+}
+
+/// Why a pool or the protocol was paused; recorded on the account and in pause events.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PauseReason {
+    #[default]
+    Unspecified,
+    Maintenance,
+    Exploit,
+    OracleFailure,
+    /// Tripped automatically by the pool's outflow circuit breaker.
+    CircuitBreaker,
+    Other,
+}
+
+/// Which path returned escrowed funds to the sender (emitted in BridgeReverted / ForceReleased).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReleaseReason {
+    /// Relayer reverted the whole outstanding amount.
+    RelayerRevert,
+    /// Admin refunded a stuck lock after the stuck-lock timeout.
+    ForceReleaseTimeout,
+    /// Relayer reverted part of the lock via release_partial.
+    PartialRevert,
+    /// sweep_stale_locks refunded a lock left pending across a long pause.
+    StaleLockSweep,
+}
+
+/// How a destination chain encodes recipient_address in its 32 bytes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// No format registered; any non-zero address is accepted.
+    #[default]
+    Unspecified,
+    /// 20-byte EVM address, right-aligned (high 12 bytes zero).
+    Evm20,
+    /// Full 32-byte Solana public key.
+    Solana32,
+    /// Cosmos account bytes; not checked beyond being non-zero.
+    Cosmos,
+}
+
+impl AddressFormat {
+    /// Whether recipient is well-formed for this format. The all-zero address is never valid.
+    pub fn accepts(&self, recipient: &[u8; 32]) -> bool {
+        if *recipient == [0u8; 32] {
+            return false;
+        }
+        match self {
+            AddressFormat::Evm20 => recipient[..12].iter().all(|b| *b == 0),
+            AddressFormat::Unspecified | AddressFormat::Solana32 | AddressFormat::Cosmos => true,
+        }
+    }
+
+    /// One-byte fat-finger guard a client can pass alongside recipient: the first byte of
+    /// keccak256 over the address bytes (the low 20 for Evm20, all 32 otherwise).
+    pub fn checksum(&self, recipient: &[u8; 32]) -> u8 {
+        let bytes = match self {
+            AddressFormat::Evm20 => &recipient[12..],
+            _ => &recipient[..],
+        };
+        keccak::hash(bytes).to_bytes()[0]
+    }
+}
+
+/// Pool operation a fee can apply to (used by effective_fee).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+    Deposit,
+    Withdraw,
+    Bridge,
+}
+
+/// One lock in a lock_for_bridge_batch call.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockParams {
+    /// Gross amount debited from the sender, including the bridge fee.
+    pub amount: u64,
+    pub destination_chain_id: u64,
+    /// Recipient on destination (opaque bytes; interpretation is off-chain).
+    pub recipient_address: [u8; 32],
+    /// Idempotency tag, as in lock_for_bridge (all zero = none).
+    pub client_tag: [u8; 16],
+    /// Optional recipient checksum, as in lock_for_bridge.
+    pub recipient_checksum: Option<u8>,
+}
+
+/// Return data of lock_for_bridge: identity of the created lock.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockReceipt {
+    /// Address of the created BridgeLock PDA.
+    pub bridge_lock: Pubkey,
+    /// Nonce assigned to the lock (also emitted in BridgeIntent).
+    pub nonce: u64,
 }
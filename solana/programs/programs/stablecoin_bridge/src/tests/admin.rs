@@ -0,0 +1,579 @@
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::metadata::mpl_token_metadata::accounts::Metadata;
+use anchor_spl::token::spl_token;
+
+use super::fixture::{Bridge, PoolParams};
+use super::harness::{anchor_err, bridge_err, code};
+use crate::errors::BridgeError;
+use crate::events::{
+    AdminTransferAccepted, AdminTransferProposed, FeeRateUpdated, LpMintMigrated, PoolClosed,
+    PoolDraining, PoolPaused, PoolResumed, ProtocolPaused, ProtocolResumed,
+    RequireUnfreezableMintUpdated,
+};
+use crate::state::PauseReason;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+
+#[test]
+fn draining_blocks_inflows_only() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+
+    bridge.begin_draining().unwrap();
+    assert_eq!(bridge.rt.events::<PoolDraining>()[0].admin, bridge.admin);
+    assert!(bridge.pool_state().draining);
+
+    assert_eq!(
+        bridge.deposit(&user, 1_000),
+        Err(bridge_err(BridgeError::PoolIsDraining))
+    );
+    assert_eq!(
+        bridge.lock(&user, 1_000),
+        Err(bridge_err(BridgeError::PoolIsDraining))
+    );
+    assert_eq!(
+        bridge.check_deposit(1_000),
+        code(BridgeError::PoolIsDraining)
+    );
+    assert_eq!(bridge.check_lock(1_000), code(BridgeError::PoolIsDraining));
+
+    bridge.release(0).unwrap();
+    bridge.complete(1).unwrap();
+    bridge.withdraw(&user, 9_000).unwrap();
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 0);
+}
+
+#[test]
+fn draining_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.begin_draining(),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn config_admin_transfer_takes_two_steps() {
+    let mut bridge = Bridge::new();
+    let old_admin = bridge.admin;
+    let new_admin = bridge.rt.wallet();
+
+    bridge.propose_config_admin(new_admin).unwrap();
+    let proposed = &bridge.rt.events::<AdminTransferProposed>()[0];
+    assert_eq!(
+        (proposed.target, proposed.pending_admin),
+        (bridge.config, new_admin)
+    );
+    assert_eq!(bridge.config_state().admin, old_admin);
+
+    let stranger = bridge.rt.wallet();
+    assert_eq!(
+        bridge.accept_config_admin(stranger),
+        Err(bridge_err(BridgeError::UnauthorizedPendingAdmin))
+    );
+    bridge.accept_config_admin(new_admin).unwrap();
+    let accepted = &bridge.rt.events::<AdminTransferAccepted>()[0];
+    assert_eq!(
+        (accepted.previous_admin, accepted.new_admin),
+        (old_admin, new_admin)
+    );
+    let config = bridge.config_state();
+    assert_eq!(
+        (config.admin, config.pending_admin),
+        (new_admin, Pubkey::default())
+    );
+
+    assert_eq!(
+        bridge.propose_config_admin(stranger),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn pool_admin_transfer_takes_two_steps() {
+    let mut bridge = Bridge::new();
+    let new_admin = bridge.rt.wallet();
+
+    bridge.propose_pool_admin(new_admin).unwrap();
+    assert_eq!(
+        bridge.accept_pool_admin(bridge.admin),
+        Err(bridge_err(BridgeError::UnauthorizedPendingAdmin))
+    );
+    bridge.accept_pool_admin(new_admin).unwrap();
+    assert_eq!(bridge.pool_state().admin, new_admin);
+
+    // The old admin no longer controls the pool.
+    assert_eq!(
+        bridge.begin_draining(),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+    bridge.admin = new_admin;
+    bridge.begin_draining().unwrap();
+}
+
+#[test]
+fn pause_resume_and_fee_updates_emit_audit_events() {
+    let mut bridge = Bridge::new();
+    let admin = bridge.admin;
+
+    bridge.pause_pool().unwrap();
+    let paused = &bridge.rt.events::<PoolPaused>()[0];
+    assert_eq!((paused.pool, paused.admin), (bridge.pool, admin));
+
+    bridge.resume_pool().unwrap();
+    let resumed = &bridge.rt.events::<PoolResumed>()[0];
+    assert_eq!((resumed.pool, resumed.admin), (bridge.pool, admin));
+
+    bridge.update_fee_rate(25, 10, 0).unwrap();
+    bridge.update_fee_rate(20, 0, 0).unwrap();
+    let updated = &bridge.rt.events::<FeeRateUpdated>()[0];
+    assert_eq!(
+        (updated.old_deposit_fee_bps, updated.new_deposit_fee_bps),
+        (25, 20)
+    );
+    assert_eq!(
+        (updated.old_withdraw_fee_bps, updated.new_withdraw_fee_bps),
+        (10, 0)
+    );
+}
+
+#[test]
+fn pause_reason_round_trips_through_event_and_account() {
+    let mut bridge = Bridge::new();
+    assert_eq!(bridge.pool_state().pause_reason, PauseReason::Unspecified);
+    assert_eq!(bridge.config_state().pause_reason, PauseReason::Unspecified);
+
+    bridge.pause_pool_for(PauseReason::Exploit).unwrap();
+    assert_eq!(
+        bridge.rt.events::<PoolPaused>()[0].reason,
+        PauseReason::Exploit
+    );
+    assert_eq!(bridge.pool_state().pause_reason, PauseReason::Exploit);
+    assert_eq!(
+        bridge.pool_view().unwrap().pause_reason,
+        PauseReason::Exploit
+    );
+
+    bridge
+        .pause_protocol_for(PauseReason::OracleFailure)
+        .unwrap();
+    assert_eq!(
+        bridge.rt.events::<ProtocolPaused>()[0].reason,
+        PauseReason::OracleFailure
+    );
+    assert_eq!(
+        bridge.config_state().pause_reason,
+        PauseReason::OracleFailure
+    );
+    // The pool keeps its own reason.
+    assert_eq!(bridge.pool_state().pause_reason, PauseReason::Exploit);
+}
+
+#[test]
+fn protocol_pause_halts_every_flow() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(5_000);
+    bridge.deposit(&user, 2_000).unwrap();
+
+    bridge.pause_protocol().unwrap();
+    let paused = &bridge.rt.events::<ProtocolPaused>()[0];
+    assert_eq!((paused.config, paused.admin), (bridge.config, bridge.admin));
+    assert!(!bridge.pool_state().paused);
+
+    let paused = Err(bridge_err(BridgeError::PoolPaused));
+    assert_eq!(bridge.deposit(&user, 1_000), paused);
+    assert_eq!(bridge.withdraw(&user, 100), paused);
+    assert_eq!(bridge.lock(&user, 100), paused);
+
+    bridge.resume_protocol().unwrap();
+    assert_eq!(bridge.rt.events::<ProtocolResumed>().len(), 1);
+    bridge.deposit(&user, 1_000).unwrap();
+    bridge.withdraw(&user, 100).unwrap();
+    bridge.lock(&user, 100).unwrap();
+}
+
+#[test]
+fn protocol_pause_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.pause_protocol(),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn lp_mint_decimals_must_match_the_stablecoin() {
+    let mut bridge = Bridge::bare();
+    bridge.lp_mint = bridge.rt.create_mint(spl_token::ID, bridge.pool, 9);
+    bridge.initialize_config().unwrap();
+    assert_eq!(
+        bridge.initialize_pool(PoolParams::default()),
+        Err(bridge_err(BridgeError::DecimalMismatch))
+    );
+}
+
+#[test]
+fn pool_records_mint_decimals() {
+    let bridge = Bridge::new();
+    let pool = bridge.pool_state();
+    assert_eq!((pool.stablecoin_decimals, pool.lp_decimals), (6, 6));
+}
+
+#[test]
+fn lp_mint_must_be_fresh() {
+    let invalid = Err(bridge_err(BridgeError::InvalidPoolState));
+
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    bridge.rt.set_mint_supply(bridge.lp_mint, 1);
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), invalid);
+
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    let mut lp_mint = bridge.rt.mint(&bridge.lp_mint);
+    lp_mint.freeze_authority = COption::Some(bridge.admin);
+    lp_mint.pack_into_slice(&mut bridge.rt.accounts.get_mut(&bridge.lp_mint).unwrap().data);
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), invalid);
+}
+
+#[test]
+fn vault_must_be_empty_and_unencumbered() {
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    bridge.rt.mint_to(bridge.vault, 1);
+    assert_eq!(
+        bridge.initialize_pool(PoolParams::default()),
+        Err(bridge_err(BridgeError::VaultNotEmpty))
+    );
+
+    type Edit = fn(&mut spl_token::state::Account, Pubkey);
+    let edits: [Edit; 2] = [
+        |vault, key| vault.delegate = COption::Some(key),
+        |vault, key| vault.close_authority = COption::Some(key),
+    ];
+    for edit in edits {
+        let mut bridge = Bridge::bare();
+        bridge.initialize_config().unwrap();
+        let mut vault = bridge.rt.token_account(&bridge.vault);
+        edit(&mut vault, bridge.admin);
+        vault.pack_into_slice(&mut bridge.rt.accounts.get_mut(&bridge.vault).unwrap().data);
+        assert_eq!(
+            bridge.initialize_pool(PoolParams::default()),
+            Err(bridge_err(BridgeError::VaultHasAuthority))
+        );
+    }
+}
+
+#[test]
+fn fee_vault_must_be_a_separate_pool_owned_stablecoin_account() {
+    let invalid = Err(bridge_err(BridgeError::InvalidPoolState));
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    let (vault, fee_vault) = (bridge.vault, bridge.fee_vault);
+
+    bridge.fee_vault = vault;
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), invalid);
+
+    let other_mint = bridge.rt.create_mint(spl_token::ID, bridge.admin, 6);
+    bridge.fee_vault = bridge.rt.create_token_account(other_mint, bridge.pool, 0);
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), invalid);
+
+    bridge.fee_vault = bridge.rt.create_token_account(bridge.mint, bridge.admin, 0);
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), invalid);
+
+    type Edit = fn(&mut spl_token::state::Account, Pubkey);
+    let edits: [Edit; 2] = [
+        |fee_vault, key| fee_vault.delegate = COption::Some(key),
+        |fee_vault, key| fee_vault.close_authority = COption::Some(key),
+    ];
+    for edit in edits {
+        bridge.fee_vault = bridge.rt.create_token_account(bridge.mint, bridge.pool, 0);
+        let mut account = bridge.rt.token_account(&bridge.fee_vault);
+        edit(&mut account, bridge.admin);
+        account.pack_into_slice(&mut bridge.rt.accounts.get_mut(&bridge.fee_vault).unwrap().data);
+        assert_eq!(
+            bridge.initialize_pool(PoolParams::default()),
+            Err(bridge_err(BridgeError::VaultHasAuthority))
+        );
+    }
+
+    bridge.fee_vault = fee_vault;
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+    assert_eq!(bridge.pool_state().fee_vault, fee_vault);
+}
+
+#[test]
+fn vault_contexts_require_the_pool_to_own_the_vault() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 5_000).unwrap();
+    let sender = bridge.user(1_000);
+    let vault = bridge.vault;
+    let pool = bridge.pool;
+
+    let set_owner = |bridge: &mut Bridge, owner: Pubkey| {
+        let mut account = bridge.rt.token_account(&vault);
+        account.owner = owner;
+        account.pack_into_slice(&mut bridge.rt.accounts.get_mut(&vault).unwrap().data);
+    };
+    let stranger = bridge.rt.wallet();
+    set_owner(&mut bridge, stranger);
+
+    let invalid = Err(bridge_err(BridgeError::InvalidPoolState));
+    assert_eq!(bridge.deposit(&lp, 1_000), invalid);
+    assert_eq!(bridge.withdraw(&lp, 1_000), invalid);
+    assert_eq!(bridge.lock(&sender, 100), invalid);
+
+    set_owner(&mut bridge, pool);
+    bridge.deposit(&lp, 1_000).unwrap();
+    bridge.withdraw(&lp, 1_000).unwrap();
+    bridge.lock(&sender, 100).unwrap();
+}
+
+#[test]
+fn close_pool_needs_redeemed_lp_and_an_empty_fee_vault() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    });
+    let user = bridge.user(10_000);
+    let treasury = bridge.rt.create_token_account(bridge.mint, bridge.admin, 0);
+    bridge.deposit(&user, 10_000).unwrap();
+    assert_eq!(
+        bridge.close_pool(treasury),
+        Err(bridge_err(BridgeError::LpSupplyOutstanding))
+    );
+
+    let lp = bridge.rt.balance(&user.lp_ata);
+    bridge.withdraw(&user, lp).unwrap();
+    assert_eq!(
+        bridge.close_pool(treasury),
+        Err(bridge_err(BridgeError::PoolNotEmpty))
+    );
+
+    let fees = bridge.rt.balance(&bridge.fee_vault);
+    bridge.collect_fees(treasury, fees).unwrap();
+    let residual = bridge.rt.balance(&bridge.vault);
+    assert!(residual > 0);
+    bridge.close_pool(treasury).unwrap();
+    assert_eq!(bridge.rt.events::<PoolClosed>()[0].residual, residual);
+    assert_eq!(bridge.rt.balance(&treasury), fees + residual);
+}
+
+#[test]
+fn close_pool_refunds_rent_to_the_admin() {
+    let mut bridge = Bridge::new();
+    let destination = bridge.rt.create_token_account(bridge.mint, bridge.admin, 0);
+    let rent = [bridge.pool, bridge.vault, bridge.fee_vault]
+        .iter()
+        .map(|key| bridge.rt.get(key).lamports)
+        .sum::<u64>();
+    let tombstone_rent = {
+        let admin_before = bridge.rt.get(&bridge.admin).lamports;
+        bridge.close_pool(destination).unwrap();
+        admin_before + rent - bridge.rt.get(&bridge.admin).lamports
+    };
+    assert_eq!(
+        tombstone_rent,
+        bridge.rt.get(&bridge.pool_tombstone_pda()).lamports
+    );
+    let event = &bridge.rt.events::<PoolClosed>()[0];
+    assert_eq!((event.pool, event.residual), (bridge.pool, 0));
+    assert!(!bridge.rt.exists(&bridge.pool));
+    assert!(!bridge.rt.exists(&bridge.vault));
+    assert!(!bridge.rt.exists(&bridge.fee_vault));
+}
+
+#[test]
+fn close_pool_is_admin_only() {
+    let mut bridge = Bridge::new();
+    let outsider = bridge.rt.wallet();
+    let destination = bridge.rt.create_token_account(bridge.mint, outsider, 0);
+    bridge.admin = outsider;
+    assert_eq!(
+        bridge.close_pool(destination),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn recreated_pool_resumes_the_closed_pools_counters() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    bridge.complete(0).unwrap();
+    let lp = bridge.rt.balance(&user.lp_ata);
+    bridge.withdraw(&user, lp).unwrap();
+    bridge.close_pool(user.ata).unwrap();
+    let closed_seq = *bridge.rt.event_seqs().last().unwrap();
+
+    bridge.vault = bridge.rt.create_token_account(bridge.mint, bridge.pool, 0);
+    bridge.fee_vault = bridge.rt.create_token_account(bridge.mint, bridge.pool, 0);
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+    let pool = bridge.pool_state();
+    assert_eq!(pool.next_lock_nonce, 1);
+    assert_eq!(pool.event_seq, closed_seq + 1);
+
+    bridge.deposit(&user, 5_000).unwrap();
+    assert_eq!(bridge.rt.event_seqs()[0], closed_seq + 1);
+    bridge.lock(&user, 1_000).unwrap();
+    assert_eq!(bridge.lock_state(1).amount, 1_000);
+}
+
+#[test]
+fn lp_mint_migrates_only_while_no_lp_exists() {
+    let mut bridge = Bridge::new();
+    let old_mint = bridge.lp_mint;
+    let invalid = Err(bridge_err(BridgeError::InvalidPoolState));
+    let foreign = bridge.rt.create_mint(spl_token::ID, bridge.admin, 6);
+    assert_eq!(bridge.migrate_lp_mint(foreign), invalid);
+    assert_eq!(bridge.migrate_lp_mint(old_mint), invalid);
+
+    let new_mint = bridge.rt.create_mint(spl_token::ID, bridge.pool, 6);
+    bridge.migrate_lp_mint(new_mint).unwrap();
+    let event = &bridge.rt.events::<LpMintMigrated>()[0];
+    assert_eq!((event.old_mint, event.new_mint), (old_mint, new_mint));
+    assert_eq!(bridge.pool_state().lp_token_mint, new_mint);
+
+    // Deposits mint the new LP from then on.
+    bridge.lp_mint = new_mint;
+    let user = bridge.user(5_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 4_000);
+
+    let replacement = bridge.rt.create_mint(spl_token::ID, bridge.pool, 6);
+    assert_eq!(
+        bridge.migrate_lp_mint(replacement),
+        Err(bridge_err(BridgeError::LpSupplyOutstanding))
+    );
+}
+
+#[test]
+fn migrate_lp_mint_is_admin_only() {
+    let mut bridge = Bridge::new();
+    let new_mint = bridge.rt.create_mint(spl_token::ID, bridge.pool, 6);
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.migrate_lp_mint(new_mint),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn set_lp_metadata_creates_then_updates_the_metadata_account() {
+    let mut bridge = Bridge::new();
+    let metadata = bridge.lp_metadata_pda();
+    assert!(!bridge.rt.exists(&metadata));
+
+    bridge
+        .set_lp_metadata("Bridge USDC LP", "bUSDC-LP", "https://example.com/lp.json")
+        .unwrap();
+    let account = bridge.rt.get(&metadata);
+    assert_eq!(account.owner, mpl_token_metadata::ID);
+    let state = Metadata::from_bytes(&account.data).unwrap();
+    assert_eq!(
+        (state.mint, state.update_authority),
+        (bridge.lp_mint, bridge.pool)
+    );
+    assert_eq!(state.name, "Bridge USDC LP");
+    assert_eq!(state.symbol, "bUSDC-LP");
+    assert_eq!(state.uri, "https://example.com/lp.json");
+    assert!(state.is_mutable);
+    assert!(!bridge.pool_state().in_progress);
+
+    // A second call updates the existing account in place.
+    bridge
+        .set_lp_metadata(
+            "Bridge USDC LP v2",
+            "bUSDC-LP",
+            "https://example.com/v2.json",
+        )
+        .unwrap();
+    let state = Metadata::from_bytes(&bridge.rt.get(&metadata).data).unwrap();
+    assert_eq!(state.name, "Bridge USDC LP v2");
+    assert_eq!(state.uri, "https://example.com/v2.json");
+    assert_eq!(state.update_authority, bridge.pool);
+}
+
+#[test]
+fn set_lp_metadata_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_lp_metadata("LP", "LP", ""),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn freezable_stablecoin_mint_is_rejected_only_when_required() {
+    let freezable = |bridge: &mut Bridge| {
+        let mut mint = bridge.rt.mint(&bridge.mint);
+        mint.freeze_authority = COption::Some(bridge.admin);
+        mint.pack_into_slice(&mut bridge.rt.accounts.get_mut(&bridge.mint).unwrap().data);
+    };
+
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    freezable(&mut bridge);
+    bridge.set_require_unfreezable_mint(true).unwrap();
+    let event = &bridge.rt.events::<RequireUnfreezableMintUpdated>()[0];
+    assert_eq!((event.old_required, event.new_required), (false, true));
+    assert!(bridge.config_state().require_unfreezable_mint);
+    assert_eq!(
+        bridge.initialize_pool(PoolParams::default()),
+        Err(bridge_err(BridgeError::FrozenAuthorityPresent))
+    );
+
+    bridge.set_require_unfreezable_mint(false).unwrap();
+    let event = &bridge.rt.events::<RequireUnfreezableMintUpdated>()[0];
+    assert_eq!((event.old_required, event.new_required), (true, false));
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+    assert_eq!(bridge.pool_state().stablecoin_decimals, 6);
+
+    // With the flag set, a mint without a freeze authority is still accepted.
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    bridge.set_require_unfreezable_mint(true).unwrap();
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+}
+
+#[test]
+fn require_unfreezable_mint_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_require_unfreezable_mint(true),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn config_must_be_the_canonical_pda() {
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    let canonical = bridge.config;
+    // A program-owned copy of the real config at another address.
+    let copy = Pubkey::new_unique();
+    let account = bridge.rt.get(&canonical);
+    bridge.rt.put(copy, account);
+    bridge.config = copy;
+
+    let seeds = Err(anchor_err(ErrorCode::ConstraintSeeds));
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), seeds);
+    assert_eq!(bridge.pause_protocol(), seeds);
+    assert_eq!(bridge.set_stuck_lock_timeout(60), seeds);
+    assert_eq!(bridge.add_supported_chain(42), seeds);
+
+    bridge.config = canonical;
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+    bridge.pause_protocol().unwrap();
+}
@@ -0,0 +1,865 @@
+use super::fixture::{ed25519_verify, keypair, Bridge};
+use super::harness::{anchor_err, bridge_err, code, warp};
+use crate::errors::BridgeError;
+use crate::events::{
+    AuthoritiesRotated, BridgeCompleted, BridgeIntent, BridgeReverted, ForceReleased,
+    MinLockAgeUpdated, RelayerAdded, RelayerRemoved, RelayerUpdated, ReleaseApproved,
+    ReleaseThresholdUpdated, StuckLockTimeoutUpdated,
+};
+use crate::state::{ApprovalAction, Config, LockStatus, ReleaseReason};
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::{ProgramError, Pubkey};
+
+#[test]
+fn complete_bridge_settles_and_pays_out() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+
+    bridge.complete(0).unwrap();
+
+    assert!(bridge.lock_state(0).completed);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.locked_liquidity, 0);
+    assert_eq!(pool.total_liquidity, 0);
+    assert_eq!(bridge.rt.balance(&bridge.relayer_payout), 400);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 0);
+    let event = &bridge.rt.events::<BridgeCompleted>()[0];
+    assert_eq!((event.nonce, event.amount), (0, 400));
+}
+
+#[test]
+fn complete_bridge_rejects_settled_locks() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.lock(&user, 100).unwrap();
+
+    bridge.complete(0).unwrap();
+    assert_eq!(
+        bridge.complete(0),
+        Err(bridge_err(BridgeError::AlreadyCompleted))
+    );
+    assert_eq!(
+        bridge.release(0),
+        Err(bridge_err(BridgeError::AlreadyCompleted))
+    );
+
+    bridge.release(1).unwrap();
+    assert_eq!(
+        bridge.complete(1),
+        Err(bridge_err(BridgeError::AlreadyReleased))
+    );
+}
+
+#[test]
+fn complete_bridge_is_relayer_only() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+
+    bridge.relayer = user.key;
+    bridge.relayer_payout = user.ata;
+    assert_eq!(
+        bridge.complete(0),
+        Err(bridge_err(BridgeError::UnauthorizedRelayer))
+    );
+}
+
+#[test]
+fn settled_lock_closes_and_refunds_sender() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    let lock = bridge.lock_pda(0);
+    let rent = bridge.rt.get(&lock).lamports;
+    let before = bridge.rt.get(&user.key).lamports;
+
+    assert_eq!(
+        bridge.close_lock(0, &user.key),
+        Err(bridge_err(BridgeError::LockNotSettled))
+    );
+    bridge.release(0).unwrap();
+    let stranger = bridge.rt.wallet();
+    assert_eq!(
+        bridge.close_lock(0, &stranger),
+        Err(bridge_err(BridgeError::InvalidBridgeLock))
+    );
+
+    bridge.close_lock(0, &user.key).unwrap();
+    assert!(!bridge.rt.exists(&lock));
+    assert_eq!(bridge.rt.get(&user.key).lamports, before + rent);
+    assert_eq!(
+        bridge.close_lock(0, &user.key),
+        Err(anchor_err(ErrorCode::AccountNotInitialized))
+    );
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+}
+
+#[test]
+fn rotated_relayer_takes_over_in_flight_locks() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+
+    let old_relayer = bridge.relayer;
+    let new_relayer = bridge.rt.wallet();
+    bridge.set_relayer(old_relayer, new_relayer).unwrap();
+    let event = &bridge.rt.events::<RelayerUpdated>()[0];
+    assert_eq!(
+        (event.old_relayer, event.new_relayer),
+        (old_relayer, new_relayer)
+    );
+    assert_eq!(bridge.config_state().active_relayers(), [new_relayer]);
+
+    assert_eq!(
+        bridge.release(0),
+        Err(bridge_err(BridgeError::UnauthorizedRelayer))
+    );
+    bridge.relayer = new_relayer;
+    bridge.release(0).unwrap();
+}
+
+#[test]
+fn set_relayer_is_admin_only() {
+    let mut bridge = Bridge::new();
+    let stranger = bridge.rt.wallet();
+    bridge.admin = stranger;
+    assert_eq!(
+        bridge.set_relayer(bridge.relayer, stranger),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn any_authorized_relayer_can_settle() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.lock(&user, 100).unwrap();
+
+    let first = bridge.relayer;
+    let second = bridge.rt.wallet();
+    bridge.add_relayer(second).unwrap();
+    assert_eq!(bridge.rt.events::<RelayerAdded>()[0].relayer, second);
+    assert_eq!(
+        bridge.add_relayer(second),
+        Err(bridge_err(BridgeError::RelayerAlreadyAuthorized))
+    );
+
+    bridge.relayer = second;
+    bridge.release(0).unwrap();
+
+    // Removing a relayer mid-flight leaves the remaining one able to settle.
+    bridge.remove_relayer(first).unwrap();
+    assert_eq!(bridge.rt.events::<RelayerRemoved>()[0].relayer, first);
+    assert_eq!(bridge.config_state().active_relayers(), [second]);
+    bridge.release(1).unwrap();
+
+    bridge.relayer = first;
+    let user_two = bridge.user(1_000);
+    bridge.lock(&user_two, 100).unwrap();
+    assert_eq!(
+        bridge.release(2),
+        Err(bridge_err(BridgeError::UnauthorizedRelayer))
+    );
+}
+
+#[test]
+fn relayer_set_bounds_are_enforced() {
+    let mut bridge = Bridge::new();
+    let stranger = bridge.rt.wallet();
+    assert_eq!(
+        bridge.remove_relayer(stranger),
+        Err(bridge_err(BridgeError::RelayerNotFound))
+    );
+    assert_eq!(
+        bridge.remove_relayer(bridge.relayer),
+        Err(bridge_err(BridgeError::CannotRemoveLastRelayer))
+    );
+
+    for _ in 1..Config::MAX_RELAYERS {
+        let relayer = bridge.rt.wallet();
+        bridge.add_relayer(relayer).unwrap();
+    }
+    assert_eq!(
+        bridge.add_relayer(stranger),
+        Err(bridge_err(BridgeError::RelayerSetFull))
+    );
+
+    bridge.admin = stranger;
+    assert_eq!(
+        bridge.add_relayer(stranger),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn rotate_authorities_hands_over_the_protocol() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    let second = bridge.rt.wallet();
+    bridge.add_relayer(second).unwrap();
+    bridge.set_release_threshold(2).unwrap();
+
+    let (old_admin, old_relayer) = (bridge.admin, bridge.relayer);
+    let new_admin = bridge.rt.wallet();
+    let new_relayer = bridge.rt.wallet();
+    bridge.rotate_authorities(new_admin, new_relayer).unwrap();
+    let event = &bridge.rt.events::<AuthoritiesRotated>()[0];
+    assert_eq!(
+        (event.current_admin, event.pending_admin, event.new_relayer),
+        (old_admin, new_admin, new_relayer)
+    );
+    assert_eq!(event.previous_relayer_count, 2);
+    let config = bridge.config_state();
+    assert_eq!(config.active_relayers(), [new_relayer]);
+    assert_eq!(config.release_threshold, 1);
+
+    // The relayer switch is immediate; the old relayers can no longer approve or settle.
+    let unauthorized = Err(bridge_err(BridgeError::UnauthorizedRelayer));
+    assert_eq!(
+        bridge.approve_release(second, 0, ApprovalAction::Release, 400),
+        unauthorized
+    );
+    assert_eq!(bridge.release(0), unauthorized);
+    bridge.relayer = new_relayer;
+    bridge
+        .approve_release(new_relayer, 0, ApprovalAction::Release, 400)
+        .unwrap();
+    bridge.release(0).unwrap();
+
+    // The admin switch still needs the new admin's acceptance.
+    assert_eq!(bridge.config_state().admin, old_admin);
+    bridge.accept_config_admin(new_admin).unwrap();
+    assert_eq!(
+        bridge.rotate_authorities(old_admin, old_relayer),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+    bridge.admin = new_admin;
+    bridge.add_relayer(old_relayer).unwrap();
+}
+
+#[test]
+fn rotate_authorities_rejects_an_empty_relayer() {
+    let mut bridge = Bridge::new();
+    let stranger = bridge.rt.wallet();
+    assert_eq!(
+        bridge.rotate_authorities(stranger, Pubkey::default()),
+        Err(bridge_err(BridgeError::UnauthorizedRelayer))
+    );
+    bridge.admin = stranger;
+    assert_eq!(
+        bridge.rotate_authorities(stranger, stranger),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn bridge_escrow_does_not_change_lp_redemption_value() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    let lp_tokens = bridge.rt.balance(&lp.lp_ata);
+    let user = bridge.user(1_000);
+
+    bridge.lock(&user, 600).unwrap();
+    let pool = bridge.pool_state();
+    assert_eq!(pool.available_liquidity, 10_000);
+    assert_eq!(pool.locked_liquidity, 600);
+    assert_eq!(pool.total_liquidity, 10_600);
+
+    // A revert refunds the sender instead of crediting LPs.
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+    let pool = bridge.pool_state();
+    assert_eq!(
+        (pool.available_liquidity, pool.total_liquidity),
+        (10_000, 10_000)
+    );
+
+    bridge.lock(&user, 400).unwrap();
+    bridge.withdraw(&lp, lp_tokens).unwrap();
+    assert_eq!(bridge.rt.balance(&lp.ata), 9_000);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 1_400);
+    assert_eq!(bridge.pool_state().locked_liquidity, 400);
+}
+
+#[test]
+fn lifetime_volume_accumulates_across_locks_and_settlements() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.lock(&user, 1_000).unwrap();
+    bridge.lock(&user, 2_000).unwrap();
+    bridge.lock(&user, 3_000).unwrap();
+    assert_eq!(
+        bridge.rt.events::<BridgeIntent>()[0].lifetime_locked_volume,
+        6_000
+    );
+
+    bridge.release(0).unwrap();
+    assert_eq!(
+        bridge.rt.events::<BridgeReverted>()[0].lifetime_released_volume,
+        1_000
+    );
+    bridge.complete(2).unwrap();
+    assert_eq!(
+        bridge.rt.events::<BridgeCompleted>()[0].lifetime_released_volume,
+        4_000
+    );
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.lifetime_locked_volume, 6_000);
+    assert_eq!(pool.lifetime_released_volume, 4_000);
+    assert_eq!(pool.locked_liquidity, 2_000);
+}
+
+#[test]
+fn partial_releases_sum_to_the_full_lock() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+
+    bridge.release_partial(0, 150).unwrap();
+    let lock = bridge.lock_state(0);
+    assert_eq!((lock.released_amount, lock.released), (150, false));
+    assert_eq!(bridge.pool_state().locked_liquidity, 250);
+    assert_eq!(bridge.rt.balance(&user.ata), 750);
+    let event = &bridge.rt.events::<BridgeReverted>()[0];
+    assert_eq!((event.amount, event.remaining_amount), (150, 250));
+
+    bridge.release_partial(0, 250).unwrap();
+    let lock = bridge.lock_state(0);
+    assert_eq!((lock.released_amount, lock.released), (400, true));
+    assert_eq!(bridge.pool_state().locked_liquidity, 0);
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+    assert_eq!(
+        bridge.release_partial(0, 1),
+        Err(bridge_err(BridgeError::AlreadyReleased))
+    );
+}
+
+#[test]
+fn partial_release_cannot_exceed_the_outstanding_amount() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.release_partial(0, 300).unwrap();
+
+    assert_eq!(
+        bridge.release_partial(0, 101),
+        Err(bridge_err(BridgeError::ReleaseExceedsLocked))
+    );
+    assert_eq!(
+        bridge.release_partial(0, 0),
+        Err(bridge_err(BridgeError::ZeroStablecoinAmount))
+    );
+
+    // The rest settles normally: only what is still escrowed is paid out.
+    bridge.complete(0).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.relayer_payout), 100);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 0);
+}
+
+#[test]
+fn lock_status_follows_settlement() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.lock(&user, 400).unwrap();
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Pending));
+
+    bridge.release_partial(0, 100).unwrap();
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::PartiallyReleased));
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Released));
+
+    bridge.complete(1).unwrap();
+    assert_eq!(bridge.lock_status(1), Ok(LockStatus::Completed));
+}
+
+/// A bridge with a 400 lock (nonce 0) and an extra relayer holding a signing key.
+fn attested_bridge() -> (Bridge, ed25519_dalek::Keypair) {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    let signer = keypair(1);
+    bridge
+        .add_relayer(Pubkey::new_from_array(signer.public.to_bytes()))
+        .unwrap();
+    (bridge, signer)
+}
+
+#[test]
+fn attested_release_can_be_submitted_by_anyone() {
+    let (mut bridge, signer) = attested_bridge();
+    let (ed25519, signature) = bridge.attest(&signer, 0, 400);
+
+    bridge
+        .release_with_attestation(0, ed25519, signature)
+        .unwrap();
+    let event = &bridge.rt.events::<BridgeReverted>()[0];
+    assert_eq!((event.amount, event.remaining_amount), (400, 0));
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Released));
+    assert_eq!(bridge.rt.balance(&bridge.sender_ata(0)), 1_000);
+}
+
+#[test]
+fn attestation_from_outside_the_relayer_set_is_rejected() {
+    let (mut bridge, _) = attested_bridge();
+    let (ed25519, signature) = bridge.attest(&keypair(2), 0, 400);
+
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519, signature),
+        Err(bridge_err(BridgeError::UnauthorizedRelayer))
+    );
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Pending));
+}
+
+#[test]
+fn attestation_must_cover_this_release() {
+    let (mut bridge, signer) = attested_bridge();
+    let invalid = Err(bridge_err(BridgeError::InvalidAttestation));
+
+    // Signed over a different amount or lock.
+    let (ed25519, signature) = bridge.attest(&signer, 0, 401);
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519, signature),
+        invalid
+    );
+    let (ed25519, signature) = bridge.attest(&signer, 1, 400);
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519, signature),
+        invalid
+    );
+
+    // The undomained hash of the same fields is not an attestation.
+    let pool = bridge.pool;
+    let bare = anchor_lang::solana_program::hash::hashv(&[
+        pool.as_ref(),
+        &0u64.to_le_bytes(),
+        &400u64.to_le_bytes(),
+    ]);
+    let (ed25519, signature) = ed25519_verify(&signer, bare.as_ref());
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519, signature),
+        invalid
+    );
+
+    // The signature passed to the program must be the verified one.
+    let (ed25519, mut signature) = bridge.attest(&signer, 0, 400);
+    signature[0] ^= 1;
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519, signature),
+        invalid
+    );
+
+    // A message tampered after signing fails Ed25519 verification before the program runs.
+    let (mut ed25519, signature) = bridge.attest(&signer, 0, 400);
+    *ed25519.data.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519, signature),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Pending));
+}
+
+#[test]
+fn attestation_signs_the_outstanding_amount() {
+    let (mut bridge, signer) = attested_bridge();
+    let stale = bridge.attest(&signer, 0, 400);
+    bridge.release_partial(0, 100).unwrap();
+
+    assert_eq!(
+        bridge.release_with_attestation(0, stale.0, stale.1),
+        Err(bridge_err(BridgeError::InvalidAttestation))
+    );
+    let (ed25519, signature) = bridge.attest(&signer, 0, 300);
+    bridge
+        .release_with_attestation(0, ed25519, signature)
+        .unwrap();
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Released));
+    assert_eq!(bridge.rt.balance(&bridge.sender_ata(0)), 1_000);
+}
+
+/// A bridge with a 400 lock (nonce 0), a second relayer, and a 2-of-2 release threshold.
+fn two_of_two() -> (Bridge, Pubkey) {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    let second = bridge.rt.wallet();
+    bridge.add_relayer(second).unwrap();
+    bridge.set_release_threshold(2).unwrap();
+    (bridge, second)
+}
+
+#[test]
+fn settlement_waits_for_the_release_threshold() {
+    let (mut bridge, second) = two_of_two();
+    let not_met = Err(bridge_err(BridgeError::ReleaseThresholdNotMet));
+    assert_eq!(bridge.release(0), not_met);
+
+    let first = bridge.relayer;
+    bridge
+        .approve_release(first, 0, ApprovalAction::Release, 400)
+        .unwrap();
+    let event = &bridge.rt.events::<ReleaseApproved>()[0];
+    assert_eq!(
+        (
+            event.relayer,
+            event.action,
+            event.amount,
+            event.approval_count
+        ),
+        (first, ApprovalAction::Release, 400, 1)
+    );
+    assert_eq!(bridge.release(0), not_met);
+    assert_eq!(
+        bridge.approve_release(first, 0, ApprovalAction::Release, 400),
+        Err(bridge_err(BridgeError::DuplicateApproval))
+    );
+
+    bridge
+        .approve_release(second, 0, ApprovalAction::Release, 400)
+        .unwrap();
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Released));
+    assert_eq!(bridge.lock_state(0).approval_count, 0);
+}
+
+#[test]
+fn completion_needs_approvals_for_completion() {
+    let (mut bridge, second) = two_of_two();
+    let not_met = Err(bridge_err(BridgeError::ReleaseThresholdNotMet));
+    assert_eq!(bridge.complete(0), not_met);
+
+    // Approvals to refund the sender do not authorize paying the relayer.
+    let first = bridge.relayer;
+    for relayer in [first, second] {
+        bridge
+            .approve_release(relayer, 0, ApprovalAction::Release, 400)
+            .unwrap();
+    }
+    assert_eq!(bridge.complete(0), not_met);
+    assert_eq!(bridge.release_partial(0, 100), not_met);
+    let third = bridge.rt.wallet();
+    bridge.add_relayer(third).unwrap();
+    assert_eq!(
+        bridge.approve_release(third, 0, ApprovalAction::Complete, 400),
+        Err(bridge_err(BridgeError::ApprovalMismatch))
+    );
+    bridge.release(0).unwrap();
+
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 300).unwrap();
+    for relayer in [first, second] {
+        bridge
+            .approve_release(relayer, 1, ApprovalAction::Complete, 300)
+            .unwrap();
+    }
+    bridge.complete(1).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.relayer_payout), 300);
+}
+
+#[test]
+fn approvals_do_not_survive_a_partial_release() {
+    let (mut bridge, second) = two_of_two();
+    let first = bridge.relayer;
+    for relayer in [first, second] {
+        bridge
+            .approve_release(relayer, 0, ApprovalAction::Release, 100)
+            .unwrap();
+    }
+    bridge.release_partial(0, 100).unwrap();
+    assert_eq!(bridge.lock_state(0).approval_count, 0);
+
+    assert_eq!(
+        bridge.release_partial(0, 100),
+        Err(bridge_err(BridgeError::ReleaseThresholdNotMet))
+    );
+    assert_eq!(
+        bridge.approve_release(first, 0, ApprovalAction::Release, 301),
+        Err(bridge_err(BridgeError::ReleaseExceedsLocked))
+    );
+}
+
+#[test]
+fn only_current_relayers_approve() {
+    let (mut bridge, second) = two_of_two();
+    let stranger = bridge.rt.wallet();
+    assert_eq!(
+        bridge.approve_release(stranger, 0, ApprovalAction::Release, 400),
+        Err(bridge_err(BridgeError::UnauthorizedRelayer))
+    );
+
+    // An approval from a relayer rotated out no longer counts.
+    let first = bridge.relayer;
+    for relayer in [first, second] {
+        bridge
+            .approve_release(relayer, 0, ApprovalAction::Release, 400)
+            .unwrap();
+    }
+    let replacement = bridge.rt.wallet();
+    bridge.set_relayer(second, replacement).unwrap();
+    assert_eq!(
+        bridge.release(0),
+        Err(bridge_err(BridgeError::ReleaseThresholdNotMet))
+    );
+    bridge
+        .approve_release(replacement, 0, ApprovalAction::Release, 400)
+        .unwrap();
+    bridge.release(0).unwrap();
+}
+
+#[test]
+fn release_threshold_stays_within_the_relayer_set() {
+    let mut bridge = Bridge::new();
+    let second = bridge.rt.wallet();
+    bridge.add_relayer(second).unwrap();
+    let invalid = Err(bridge_err(BridgeError::InvalidReleaseThreshold));
+    assert_eq!(bridge.set_release_threshold(3), invalid);
+
+    bridge.set_release_threshold(2).unwrap();
+    let event = &bridge.rt.events::<ReleaseThresholdUpdated>()[0];
+    assert_eq!(
+        (event.admin, event.old_threshold, event.new_threshold),
+        (bridge.admin, 0, 2)
+    );
+    assert_eq!(bridge.remove_relayer(second), invalid);
+
+    bridge.set_release_threshold(1).unwrap();
+    bridge.remove_relayer(second).unwrap();
+
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_release_threshold(0),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn attestation_counts_as_the_signers_approval() {
+    let (mut bridge, signer) = attested_bridge();
+    bridge.set_release_threshold(2).unwrap();
+    let not_met = Err(bridge_err(BridgeError::ReleaseThresholdNotMet));
+    let (ed25519, signature) = bridge.attest(&signer, 0, 400);
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519.clone(), signature),
+        not_met
+    );
+
+    // The signer approving as well is still one approval.
+    let attester = Pubkey::new_from_array(signer.public.to_bytes());
+    bridge
+        .approve_release(attester, 0, ApprovalAction::Release, 400)
+        .unwrap();
+    assert_eq!(
+        bridge.release_with_attestation(0, ed25519.clone(), signature),
+        not_met
+    );
+
+    let relayer = bridge.relayer;
+    bridge
+        .approve_release(relayer, 0, ApprovalAction::Release, 400)
+        .unwrap();
+    bridge
+        .release_with_attestation(0, ed25519, signature)
+        .unwrap();
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Released));
+}
+
+#[test]
+fn force_release_waits_for_the_stuck_lock_timeout() {
+    let (mut bridge, _) = two_of_two();
+    let not_expired = Err(bridge_err(BridgeError::LockNotExpired));
+    // Disabled until the admin sets a timeout.
+    assert_eq!(bridge.force_release(0), not_expired);
+
+    bridge.set_stuck_lock_timeout(3_600).unwrap();
+    let event = &bridge.rt.events::<StuckLockTimeoutUpdated>()[0];
+    assert_eq!(
+        (event.admin, event.old_seconds, event.new_seconds),
+        (bridge.admin, 0, 3_600)
+    );
+    warp(3_599);
+    assert_eq!(bridge.force_release(0), not_expired);
+
+    // No relayer approvals needed once the lock is stuck.
+    warp(1);
+    bridge.force_release(0).unwrap();
+    let event = &bridge.rt.events::<ForceReleased>()[0];
+    assert_eq!(
+        (event.admin, event.amount, event.nonce),
+        (bridge.admin, 400, 0)
+    );
+    assert_eq!(bridge.rt.balance(&bridge.sender_ata(0)), 1_000);
+    let pool = bridge.pool_state();
+    assert_eq!((pool.locked_liquidity, pool.total_liquidity), (0, 0));
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Released));
+    assert_eq!(
+        bridge.force_release(0),
+        Err(bridge_err(BridgeError::AlreadyReleased))
+    );
+}
+
+#[test]
+fn force_release_refunds_only_the_outstanding_amount() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.release_partial(0, 150).unwrap();
+    bridge.set_stuck_lock_timeout(60).unwrap();
+    warp(60);
+
+    bridge.force_release(0).unwrap();
+    assert_eq!(bridge.rt.events::<ForceReleased>()[0].amount, 250);
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+}
+
+#[test]
+fn each_release_path_reports_its_reason() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 100).unwrap();
+    bridge.lock(&user, 100).unwrap();
+    bridge.lock(&user, 100).unwrap();
+
+    bridge.release(0).unwrap();
+    let event = &bridge.rt.events::<BridgeReverted>()[0];
+    assert_eq!(
+        (event.nonce, event.reason),
+        (0, ReleaseReason::RelayerRevert)
+    );
+
+    // Settling the rest through release_partial is still a partial revert.
+    for amount in [40, 60] {
+        bridge.release_partial(1, amount).unwrap();
+        let event = &bridge.rt.events::<BridgeReverted>()[0];
+        assert_eq!(
+            (event.nonce, event.reason),
+            (1, ReleaseReason::PartialRevert)
+        );
+    }
+
+    bridge.set_stuck_lock_timeout(60).unwrap();
+    warp(60);
+    bridge.force_release(2).unwrap();
+    let event = &bridge.rt.events::<ForceReleased>()[0];
+    assert_eq!(
+        (event.nonce, event.reason),
+        (2, ReleaseReason::ForceReleaseTimeout)
+    );
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+}
+
+#[test]
+fn force_release_and_its_timeout_are_admin_only() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.set_stuck_lock_timeout(60).unwrap();
+    warp(60);
+
+    bridge.admin = bridge.relayer;
+    let unauthorized = Err(bridge_err(BridgeError::UnauthorizedAdmin));
+    assert_eq!(bridge.force_release(0), unauthorized);
+    assert_eq!(bridge.set_stuck_lock_timeout(0), unauthorized);
+}
+
+#[test]
+fn relayer_reverts_wait_for_the_min_lock_age() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.set_min_lock_age(600).unwrap();
+    let event = &bridge.rt.events::<MinLockAgeUpdated>()[0];
+    assert_eq!(
+        (event.admin, event.old_seconds, event.new_seconds),
+        (bridge.admin, 0, 600)
+    );
+    bridge.lock(&user, 400).unwrap();
+
+    let too_young = Err(bridge_err(BridgeError::LockTooYoung));
+    assert_eq!(bridge.release(0), too_young);
+    assert_eq!(bridge.release_partial(0, 100), too_young);
+    warp(599);
+    assert_eq!(bridge.release(0), too_young);
+
+    warp(1);
+    bridge.release_partial(0, 100).unwrap();
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+}
+
+#[test]
+fn force_release_ignores_the_min_lock_age() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.set_min_lock_age(7_200).unwrap();
+    bridge.set_stuck_lock_timeout(3_600).unwrap();
+    bridge.lock(&user, 400).unwrap();
+
+    warp(3_600);
+    assert_eq!(
+        bridge.release(0),
+        Err(bridge_err(BridgeError::LockTooYoung))
+    );
+    bridge.force_release(0).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+}
+
+#[test]
+fn set_min_lock_age_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_min_lock_age(60),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn relayer_is_paid_the_reserved_fee_on_top_of_the_delivered_net() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(2_000);
+    bridge.set_relayer_fee(25).unwrap();
+
+    bridge.lock(&user, 1_000).unwrap();
+    let intent = &bridge.rt.events::<BridgeIntent>()[0];
+    assert_eq!((intent.amount, intent.relayer_fee), (1_000, 25));
+    assert_eq!(bridge.lock_state(0).relayer_fee, 25);
+
+    // Later fee changes leave existing locks on the fee they were created with.
+    bridge.set_relayer_fee(40).unwrap();
+    bridge.complete(0).unwrap();
+    let event = &bridge.rt.events::<BridgeCompleted>()[0];
+    assert_eq!((event.amount, event.relayer_fee), (1_000, 25));
+    // The recipient was delivered the net; the payout covers it plus the reserved fee.
+    assert_eq!(event.amount - event.relayer_fee, 975);
+    assert_eq!(bridge.rt.balance(&bridge.relayer_payout), 1_000);
+    assert_eq!(bridge.pool_state().locked_liquidity, 0);
+}
+
+#[test]
+fn lock_must_exceed_the_relayer_fee() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.set_relayer_fee(100).unwrap();
+
+    assert_eq!(
+        bridge.lock(&user, 100),
+        Err(bridge_err(BridgeError::RelayerFeeExceedsAmount))
+    );
+    assert_eq!(
+        bridge.check_lock(100),
+        code(BridgeError::RelayerFeeExceedsAmount)
+    );
+    bridge.lock(&user, 101).unwrap();
+    assert_eq!(bridge.lock_state(0).relayer_fee, 100);
+
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.set_relayer_fee(0),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
@@ -0,0 +1,569 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token;
+
+use super::fixture::{Bridge, PoolParams, User};
+use super::harness::{anchor_err, bridge_err, code, now, warp};
+use crate::errors::BridgeError;
+use crate::events::{
+    CollectRoundingDustUpdated, DepositFeeCollected, DustCollected, LiquidityDeposited,
+    LiquidityLockedForever, LpSupplyResynced, PoolCapUpdated, VaultReconciled, YieldDistributed,
+};
+use crate::state::{Pool, UserLpState};
+
+#[test]
+fn deposit_fee_goes_to_fee_vault_not_liquidity() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    });
+    let user = bridge.user(10_000);
+
+    bridge.deposit(&user, 10_000).unwrap();
+
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 100);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 9_900);
+    assert_eq!(bridge.rt.balance(&user.ata), 0);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.total_liquidity, 9_900);
+    assert_eq!(pool.available_liquidity, 9_900);
+    // First deposit locks MINIMUM_LIQUIDITY dead shares.
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 8_900);
+
+    let fees = bridge.rt.events::<DepositFeeCollected>();
+    assert_eq!(fees.len(), 1);
+    assert_eq!(fees[0].payer, user.key);
+    assert_eq!(fees[0].fee_amount, 100);
+    assert_eq!(
+        bridge.rt.events::<LiquidityDeposited>()[0].stablecoin_amount,
+        10_000
+    );
+}
+
+#[test]
+fn zero_fee_deposit_emits_no_fee_event() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(5_000);
+
+    bridge.deposit(&user, 5_000).unwrap();
+
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 0);
+    assert_eq!(bridge.pool_state().available_liquidity, 5_000);
+    assert!(bridge.rt.events::<DepositFeeCollected>().is_empty());
+}
+
+#[test]
+fn fee_multiplication_overflow_is_a_clean_error() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    });
+    let amount = u64::MAX / 50;
+    let user = bridge.user(amount);
+
+    assert_eq!(
+        bridge.deposit(&user, amount),
+        Err(bridge_err(BridgeError::MathOverflow))
+    );
+    assert_eq!(
+        bridge.check_deposit(amount),
+        code(BridgeError::MathOverflow)
+    );
+    assert_eq!(bridge.rt.balance(&user.ata), amount);
+}
+
+#[test]
+fn donation_mints_no_lp_and_raises_share_value() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(2_000);
+    bridge.deposit(&lp, 2_000).unwrap();
+    let donor = bridge.user(500);
+
+    bridge.deposit_and_burn(&donor, 500).unwrap();
+
+    assert_eq!(bridge.rt.balance(&donor.lp_ata), 0);
+    assert_eq!(bridge.rt.mint_supply(&bridge.lp_mint), 1_000);
+    assert_eq!(bridge.pool_state().available_liquidity, 2_500);
+    let event = &bridge.rt.events::<LiquidityLockedForever>()[0];
+    assert_eq!(
+        (event.stablecoin_amount, event.lp_tokens_burned),
+        (500, 500)
+    );
+
+    // 1_000 of 2_000 shares (half are dead) now redeem for half of 2_500.
+    bridge.withdraw(&lp, 1_000).unwrap();
+    assert_eq!(bridge.rt.balance(&lp.ata), 1_250);
+}
+
+#[test]
+fn donation_requires_existing_lp_supply() {
+    let mut bridge = Bridge::new();
+    let donor = bridge.user(500);
+    assert_eq!(
+        bridge.deposit_and_burn(&donor, 500),
+        Err(bridge_err(BridgeError::NoLpSupply))
+    );
+}
+
+#[test]
+fn first_deposit_locks_dead_shares() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(5_000);
+
+    assert_eq!(
+        bridge.deposit(&user, Pool::MINIMUM_LIQUIDITY),
+        Err(bridge_err(BridgeError::ZeroLpAmount))
+    );
+    bridge.deposit(&user, 5_000).unwrap();
+
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 4_000);
+    assert_eq!(bridge.pool_state().lp_supply, 5_000);
+}
+
+#[test]
+fn vault_donation_cannot_inflate_share_price() {
+    let mut bridge = Bridge::new();
+    let attacker = bridge.user(1_001);
+    bridge.deposit(&attacker, 1_001).unwrap();
+    assert_eq!(bridge.rt.balance(&attacker.lp_ata), 1);
+    // Tokens sent straight to the vault are not pool liquidity.
+    bridge.rt.mint_to(bridge.vault, 1_000_000);
+
+    let victim = bridge.user(10_000);
+    bridge.deposit(&victim, 10_000).unwrap();
+
+    assert_eq!(bridge.rt.balance(&victim.lp_ata), 10_000);
+    bridge.withdraw(&victim, 10_000).unwrap();
+    assert_eq!(bridge.rt.balance(&victim.ata), 10_000);
+}
+
+#[test]
+fn diverged_lp_mint_supply_is_rejected() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    // Someone else mints LP outside the program.
+    bridge.rt.mint_to(user.lp_ata, 1);
+
+    assert_eq!(
+        bridge.deposit(&user, 5_000),
+        Err(bridge_err(BridgeError::InvalidPoolState))
+    );
+    assert_eq!(
+        bridge.check_deposit(5_000),
+        code(BridgeError::InvalidPoolState)
+    );
+    assert_eq!(
+        bridge.withdraw(&user, 100),
+        Err(bridge_err(BridgeError::InvalidPoolState))
+    );
+}
+
+#[test]
+fn paused_pool_can_resync_a_diverged_lp_supply() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    let lp_supply = bridge.pool_state().lp_supply;
+    bridge.rt.mint_to(user.lp_ata, 1);
+
+    assert_eq!(
+        bridge.resync_lp_supply(),
+        Err(bridge_err(BridgeError::PoolNotPaused))
+    );
+    bridge.pause_pool().unwrap();
+    let admin = bridge.admin;
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.resync_lp_supply(),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+    bridge.admin = admin;
+
+    bridge.resync_lp_supply().unwrap();
+    let event = &bridge.rt.events::<LpSupplyResynced>()[0];
+    assert_eq!(
+        (event.old_lp_supply, event.new_lp_supply),
+        (lp_supply, lp_supply + 1)
+    );
+    let pool = bridge.pool_state();
+    assert_eq!(
+        pool.expected_mint_supply(),
+        bridge.rt.mint(&bridge.lp_mint).supply
+    );
+
+    bridge.resume_pool().unwrap();
+    bridge.deposit(&user, 1_000).unwrap();
+    bridge.withdraw(&user, 100).unwrap();
+}
+
+#[test]
+fn resync_keeps_an_empty_pool_empty() {
+    let mut bridge = Bridge::new();
+    bridge.pause_pool().unwrap();
+    bridge.resync_lp_supply().unwrap();
+    assert_eq!(bridge.pool_state().lp_supply, 0);
+}
+
+#[test]
+fn deposit_respects_min_lp_out() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+
+    assert_eq!(
+        bridge.deposit_min(&user, 5_000, 5_001),
+        Err(bridge_err(BridgeError::SlippageExceeded))
+    );
+    assert_eq!(bridge.rt.balance(&user.ata), 10_000);
+
+    bridge.deposit_min(&user, 5_000, 4_990).unwrap();
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 9_000 + 5_000);
+}
+
+#[test]
+fn pool_cap_bounds_total_liquidity() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(20_000);
+    bridge.set_pool_cap(10_000).unwrap();
+    let event = &bridge.rt.events::<PoolCapUpdated>()[0];
+    assert_eq!((event.old_cap, event.new_cap), (0, 10_000));
+
+    bridge.deposit(&user, 6_000).unwrap();
+    assert_eq!(
+        bridge.deposit(&user, 4_001),
+        Err(bridge_err(BridgeError::PoolCapExceeded))
+    );
+    assert_eq!(
+        bridge.check_deposit(4_001),
+        code(BridgeError::PoolCapExceeded)
+    );
+    bridge.deposit(&user, 4_000).unwrap();
+    assert_eq!(bridge.pool_state().total_liquidity, 10_000);
+
+    bridge.set_pool_cap(0).unwrap();
+    bridge.deposit(&user, 10_000).unwrap();
+    assert_eq!(bridge.pool_state().total_liquidity, 20_000);
+}
+
+#[test]
+fn pool_cap_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_pool_cap(1),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+/// A pool at a 1.5 share price, where LP minted for 1_000 rounds down to 666 (worth 999).
+fn dusty_pool() -> (Bridge, User) {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    let treasury = bridge
+        .rt
+        .create_token_account(bridge.mint, bridge.admin, 5_000);
+    bridge.distribute_yield(treasury, 5_000).unwrap();
+    let depositor = bridge.user(1_000);
+    (bridge, depositor)
+}
+
+#[test]
+fn deposit_rounding_dust_goes_to_the_fee_vault() {
+    let (mut bridge, depositor) = dusty_pool();
+    bridge.set_collect_rounding_dust(true).unwrap();
+    let event = &bridge.rt.events::<CollectRoundingDustUpdated>()[0];
+    assert_eq!((event.pool, event.admin), (bridge.pool, bridge.admin));
+    assert_eq!((event.old_enabled, event.new_enabled), (false, true));
+
+    bridge.deposit(&depositor, 1_000).unwrap();
+    assert_eq!(bridge.rt.balance(&depositor.lp_ata), 666);
+    let dust = &bridge.rt.events::<DustCollected>()[0];
+    assert_eq!((dust.depositor, dust.amount), (depositor.key, 1));
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 1);
+
+    // The pool books exactly what stays in the vault.
+    let pool = bridge.pool_state();
+    assert_eq!(pool.available_liquidity, 15_999);
+    assert_eq!(bridge.rt.balance(&bridge.vault), pool.total_liquidity);
+}
+
+#[test]
+fn rounding_dust_stays_with_lps_by_default() {
+    let (mut bridge, depositor) = dusty_pool();
+    bridge.deposit(&depositor, 1_000).unwrap();
+    assert!(bridge.rt.events::<DustCollected>().is_empty());
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 0);
+    assert_eq!(bridge.pool_state().available_liquidity, 16_000);
+
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_collect_rounding_dust(true),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn distributed_yield_raises_share_price_without_minting() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    let treasury = bridge
+        .rt
+        .create_token_account(bridge.mint, bridge.admin, 5_000);
+    assert_eq!(
+        bridge.distribute_yield(treasury, 1_000),
+        Err(bridge_err(BridgeError::NoLpSupply))
+    );
+
+    bridge.deposit(&user, 10_000).unwrap();
+    let before = bridge.pool_state();
+    let lp_mint_supply = bridge.rt.mint_supply(&bridge.lp_mint);
+
+    bridge.distribute_yield(treasury, 5_000).unwrap();
+    let event = &bridge.rt.events::<YieldDistributed>()[0];
+    assert_eq!((event.source, event.amount), (bridge.admin, 5_000));
+    assert_eq!(event.share_price, 1_500_000_000);
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.lp_supply, before.lp_supply);
+    assert_eq!(bridge.rt.mint_supply(&bridge.lp_mint), lp_mint_supply);
+    assert_eq!(pool.available_liquidity, 15_000);
+    assert_eq!(pool.total_liquidity, 15_000);
+    assert!(pool.share_price() > before.share_price());
+    assert_eq!(bridge.rt.balance(&bridge.vault), 15_000);
+    assert_eq!(
+        bridge.distribute_yield(treasury, 0),
+        Err(bridge_err(BridgeError::ZeroStablecoinAmount))
+    );
+}
+
+#[test]
+fn distribute_yield_is_admin_only() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.distribute_yield(user.ata, 1_000),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn yield_accumulators_track_each_distribution() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    let treasury = bridge
+        .rt
+        .create_token_account(bridge.mint, bridge.admin, 5_000);
+
+    let first_at = now();
+    bridge.distribute_yield(treasury, 2_000).unwrap();
+    let event = &bridge.rt.events::<YieldDistributed>()[0];
+    assert_eq!(
+        (event.lifetime_yield_distributed, event.last_yield_timestamp),
+        (2_000, 0)
+    );
+
+    warp(86_400);
+    bridge.distribute_yield(treasury, 3_000).unwrap();
+    let event = &bridge.rt.events::<YieldDistributed>()[0];
+    assert_eq!(
+        (event.lifetime_yield_distributed, event.last_yield_timestamp),
+        (5_000, first_at)
+    );
+    assert_eq!(event.timestamp, first_at + 86_400);
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.lifetime_yield_distributed, 5_000);
+    assert_eq!(pool.last_yield_timestamp, first_at + 86_400);
+}
+
+#[test]
+fn deposit_can_mint_lp_to_a_beneficiary() {
+    let mut bridge = Bridge::new();
+    let aggregator = bridge.user(10_000);
+    let owner = bridge.user(0);
+
+    bridge.deposit_for(&aggregator, &owner, 5_000, 0).unwrap();
+    let event = &bridge.rt.events::<LiquidityDeposited>()[0];
+    assert_eq!(
+        (event.depositor, event.beneficiary),
+        (aggregator.key, owner.key)
+    );
+    assert_eq!(bridge.rt.balance(&aggregator.ata), 5_000);
+    assert_eq!(bridge.rt.balance(&aggregator.lp_ata), 0);
+    assert_eq!(bridge.rt.balance(&owner.lp_ata), 4_000);
+
+    // The lockup follows the LP to its holder.
+    let state: UserLpState = bridge.rt.state(&bridge.user_lp_pda(&owner.key));
+    assert_eq!((state.user, state.last_deposit_at), (owner.key, now()));
+    assert!(!bridge.rt.exists(&bridge.user_lp_pda(&aggregator.key)));
+    bridge.withdraw(&owner, 4_000).unwrap();
+    assert_eq!(bridge.rt.balance(&owner.ata), 4_000);
+}
+
+#[test]
+fn third_party_dust_barely_moves_the_holders_lockup() {
+    let mut bridge = Bridge::new();
+    let holder = bridge.user(100_000);
+    let griefer = bridge.user(100);
+    bridge.set_min_lp_hold(3_600).unwrap();
+    bridge.deposit(&holder, 100_000).unwrap();
+    let deposited_at = now();
+
+    warp(3_500);
+    bridge.deposit_for(&griefer, &holder, 100, 0).unwrap();
+    let state: UserLpState = bridge.rt.state(&bridge.user_lp_pda(&holder.key));
+    // 100 LP minted onto 99_000 held moves the start by 3_500 * 100 / 99_100 seconds.
+    assert_eq!(state.last_deposit_at, deposited_at + 3);
+
+    warp(100);
+    assert_eq!(
+        bridge.withdraw(&holder, 1_000),
+        Err(bridge_err(BridgeError::LpLockupActive))
+    );
+    warp(3);
+    bridge.withdraw(&holder, 1_000).unwrap();
+}
+
+#[test]
+fn dust_deposit_is_rejected_before_funds_move() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    let treasury = bridge
+        .rt
+        .create_token_account(bridge.mint, bridge.admin, 1_000_000);
+    bridge.distribute_yield(treasury, 1_000_000).unwrap();
+
+    // The user cannot cover the transfer, so only a check ahead of it yields ZeroLpAmount.
+    let dust = bridge.user(10);
+    assert_eq!(
+        bridge.deposit(&dust, 50),
+        Err(bridge_err(BridgeError::ZeroLpAmount))
+    );
+    assert_eq!(bridge.rt.balance(&dust.ata), 10);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 1_010_000);
+}
+
+#[test]
+fn slippage_failing_deposit_is_rejected_before_funds_move() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+
+    let user = bridge.user(10);
+    assert_eq!(
+        bridge.deposit_min(&user, 100, 101),
+        Err(bridge_err(BridgeError::SlippageExceeded))
+    );
+    assert_eq!(bridge.rt.balance(&user.ata), 10);
+}
+
+#[test]
+fn failed_deposits_move_no_funds() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    });
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    let user = bridge.user(1_000);
+    let user_ata = user.ata;
+    let snapshot = move |bridge: &Bridge| {
+        (
+            bridge.rt.balance(&user_ata),
+            bridge.rt.balance(&bridge.vault),
+            bridge.rt.balance(&bridge.fee_vault),
+            bridge.rt.mint_supply(&bridge.lp_mint),
+            bridge.pool_state().available_liquidity,
+        )
+    };
+    let before = snapshot(&bridge);
+
+    assert_eq!(
+        bridge.deposit_min(&user, 1_000, 991),
+        Err(bridge_err(BridgeError::SlippageExceeded))
+    );
+    assert_eq!(snapshot(&bridge), before);
+
+    // LP minted into an account of the wrong mint.
+    let wrong_lp = User {
+        key: user.key,
+        ata: user.ata,
+        lp_ata: user.ata,
+    };
+    assert_eq!(
+        bridge.deposit(&wrong_lp, 1_000),
+        Err(anchor_err(ErrorCode::ConstraintRaw))
+    );
+    assert_eq!(snapshot(&bridge), before);
+
+    // A frozen LP account is caught before the stablecoin transfer, not at the mint.
+    let mut lp_account = bridge.rt.token_account(&user.lp_ata);
+    lp_account.state = spl_token::state::AccountState::Frozen;
+    lp_account.pack_into_slice(&mut bridge.rt.accounts.get_mut(&user.lp_ata).unwrap().data);
+    assert_eq!(
+        bridge.deposit(&user, 1_000),
+        Err(bridge_err(BridgeError::TokenAccountFrozen))
+    );
+    assert_eq!(snapshot(&bridge), before);
+
+    bridge.set_config_paused(true);
+    assert_eq!(
+        bridge.deposit(&user, 1_000),
+        Err(bridge_err(BridgeError::PoolPaused))
+    );
+    assert_eq!(snapshot(&bridge), before);
+}
+
+#[test]
+fn reconcile_vault_credits_donations_to_lps() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    assert_eq!(
+        bridge.reconcile_vault(true),
+        Err(bridge_err(BridgeError::ZeroStablecoinAmount))
+    );
+
+    bridge.rt.mint_to(bridge.vault, 500);
+    bridge.reconcile_vault(true).unwrap();
+    let event = &bridge.rt.events::<VaultReconciled>()[0];
+    assert_eq!((event.surplus, event.credited_to_lps), (500, true));
+    let pool = bridge.pool_state();
+    assert_eq!(pool.total_liquidity, 10_500);
+    assert_eq!(pool.available_liquidity, 10_500);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 10_500);
+
+    bridge.withdraw(&lp, 9_000).unwrap();
+    assert_eq!(bridge.rt.balance(&lp.ata), 9_450);
+}
+
+#[test]
+fn reconcile_vault_can_sweep_donations_to_fee_vault() {
+    let mut bridge = Bridge::new();
+    // Crediting LPs needs LP supply; sweeping does not.
+    bridge.rt.mint_to(bridge.vault, 300);
+    assert_eq!(
+        bridge.reconcile_vault(true),
+        Err(bridge_err(BridgeError::NoLpSupply))
+    );
+    bridge.reconcile_vault(false).unwrap();
+    let event = &bridge.rt.events::<VaultReconciled>()[0];
+    assert_eq!((event.surplus, event.credited_to_lps), (300, false));
+    assert_eq!(bridge.rt.balance(&bridge.vault), 0);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 300);
+    assert_eq!(bridge.pool_state().total_liquidity, 0);
+
+    bridge.rt.mint_to(bridge.vault, 1);
+    let user = bridge.user(0);
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.reconcile_vault(false),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
@@ -0,0 +1,59 @@
+use super::fixture::Bridge;
+
+#[test]
+fn pool_events_are_numbered_in_emission_order() {
+    let mut bridge = Bridge::new();
+    let first = bridge.pool_state().event_seq;
+    let lp = bridge.user(10_000);
+    let sender = bridge.user(1_000);
+    let mut seen = Vec::new();
+
+    bridge.deposit(&lp, 5_000).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.propose_fee_rate(0, 100, 100).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.apply_fee_rate().unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.lock(&sender, 400).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    // A fee-bearing withdraw emits two events; each gets its own number.
+    bridge.withdraw(&lp, 1_000).unwrap();
+    assert_eq!(bridge.rt.event_seqs().len(), 2);
+    seen.extend(bridge.rt.event_seqs());
+    bridge.pause_pool().unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.resume_pool().unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.release(0).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+
+    let next = first + seen.len() as u64;
+    assert_eq!(seen, (first..next).collect::<Vec<_>>());
+    assert_eq!(bridge.pool_state().event_seq, next);
+}
+
+#[test]
+fn protocol_events_use_the_config_counter() {
+    let mut bridge = Bridge::new();
+    let pool_seq = bridge.pool_state().event_seq;
+    let first = bridge.config_state().event_seq;
+    let relayer = bridge.rt.wallet();
+    let new_admin = bridge.rt.wallet();
+    let mut seen = Vec::new();
+
+    bridge.add_relayer(relayer).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.set_release_threshold(2).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.propose_config_admin(new_admin).unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.pause_protocol().unwrap();
+    seen.extend(bridge.rt.event_seqs());
+    bridge.resume_protocol().unwrap();
+    seen.extend(bridge.rt.event_seqs());
+
+    let next = first + seen.len() as u64;
+    assert_eq!(seen, (first..next).collect::<Vec<_>>());
+    assert_eq!(bridge.config_state().event_seq, next);
+    assert_eq!(bridge.pool_state().event_seq, pool_seq);
+}
@@ -0,0 +1,432 @@
+use super::fixture::{Bridge, PoolParams};
+use super::harness::{bridge_err, code, now, warp};
+use crate::errors::BridgeError;
+use crate::events::{
+    BridgeFeeCollected, BridgeIntent, DepositFeeCollected, FeeRateApplied, FeeRateProposed,
+    FeeRateUpdated, FeeTimelockUpdated, FeesCollected, LiquidityWithdrawn, MaxFeeBpsUpdated,
+    ProtocolFeeShareUpdated, WithdrawFeeCollected,
+};
+use crate::state::OperationKind;
+use anchor_lang::prelude::ProgramError;
+
+fn fee_pool() -> Bridge {
+    Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    })
+}
+
+#[test]
+fn collect_fees_sweeps_partial_then_full_balance() {
+    let mut bridge = fee_pool();
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.deposit(&user, 5_000).unwrap();
+    bridge.deposit(&user, 5_000).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 200);
+    let treasury = bridge.rt.create_token_account(bridge.mint, bridge.admin, 0);
+
+    bridge.collect_fees(treasury, 120).unwrap();
+    let event = &bridge.rt.events::<FeesCollected>()[0];
+    assert_eq!((event.destination, event.amount), (treasury, 120));
+    assert_eq!(event.total_fees_collected, 120);
+
+    assert_eq!(
+        bridge.collect_fees(treasury, 81),
+        Err(bridge_err(BridgeError::InsufficientFeeBalance))
+    );
+    bridge.collect_fees(treasury, 80).unwrap();
+
+    assert_eq!(bridge.rt.balance(&treasury), 200);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 0);
+    assert_eq!(bridge.pool_state().total_fees_collected, 200);
+    // LP liquidity is untouched by fee collection.
+    assert_eq!(bridge.pool_state().available_liquidity, 19_800);
+}
+
+#[test]
+fn collect_fees_is_admin_only() {
+    let mut bridge = fee_pool();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.collect_fees(user.ata, 100),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn withdraw_fee_goes_to_fee_vault() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.set_max_fee_bps(200).unwrap();
+    bridge.update_fee_rate(0, 200, 0).unwrap();
+
+    // 4_500 LP redeems 4_500 gross; 2% (90) stays behind as fee.
+    assert_eq!(
+        bridge.withdraw_min(&user, 4_500, 4_411),
+        Err(bridge_err(BridgeError::SlippageExceeded))
+    );
+    bridge.withdraw_min(&user, 4_500, 4_410).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 4_410);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 90);
+    let event = &bridge.rt.events::<WithdrawFeeCollected>()[0];
+    assert_eq!((event.withdrawer, event.fee_amount), (user.key, 90));
+    let withdrawn = &bridge.rt.events::<LiquidityWithdrawn>()[0];
+    assert_eq!(withdrawn.stablecoin_amount, 4_410);
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.available_liquidity, 5_500);
+    assert_eq!(pool.fee_rate_bps, pool.deposit_fee_bps);
+}
+
+#[test]
+fn zero_withdraw_fee_takes_nothing() {
+    let mut bridge = fee_pool();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    let fees = bridge.rt.balance(&bridge.fee_vault);
+
+    bridge.withdraw(&user, 1_000).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), fees);
+    assert!(bridge.rt.events::<WithdrawFeeCollected>().is_empty());
+    assert_eq!(bridge.rt.balance(&user.ata), 1_000);
+}
+
+#[test]
+fn fee_rates_are_capped_at_the_protocol_max() {
+    let mut bridge = Bridge::new();
+    let over_max = Err(bridge_err(BridgeError::FeeExceedsProtocolMax));
+    assert_eq!(bridge.config_state().max_fee_bps, 100);
+    assert_eq!(bridge.update_fee_rate(101, 0, 0), over_max);
+    assert_eq!(bridge.update_fee_rate(0, 101, 0), over_max);
+    assert_eq!(bridge.update_fee_rate(0, 0, 101), over_max);
+    bridge.update_fee_rate(100, 100, 100).unwrap();
+
+    bridge.set_max_fee_bps(250).unwrap();
+    let event = &bridge.rt.events::<MaxFeeBpsUpdated>()[0];
+    assert_eq!(
+        (event.admin, event.old_max_fee_bps, event.new_max_fee_bps),
+        (bridge.admin, 100, 250)
+    );
+    bridge.update_fee_rate(0, 250, 0).unwrap();
+    assert_eq!(bridge.update_fee_rate(0, 251, 0), over_max);
+}
+
+#[test]
+fn max_fee_bps_is_bounded_and_admin_only() {
+    let mut bridge = Bridge::new();
+    assert_eq!(
+        bridge.set_max_fee_bps(1_001),
+        Err(bridge_err(BridgeError::InvalidFeeRate))
+    );
+    bridge.set_max_fee_bps(1_000).unwrap();
+
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_max_fee_bps(100),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn pool_creation_and_pending_increases_respect_the_max() {
+    let mut bridge = Bridge::bare();
+    bridge.initialize_config().unwrap();
+    assert_eq!(
+        bridge.initialize_pool(PoolParams {
+            fee_rate_bps: 101,
+            ..PoolParams::default()
+        }),
+        Err(bridge_err(BridgeError::FeeExceedsProtocolMax))
+    );
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+
+    // A maximum lowered during the timelock blocks the pending increase.
+    bridge.set_fee_timelock(3_600).unwrap();
+    bridge.propose_fee_rate(80, 0, 0).unwrap();
+    bridge.set_max_fee_bps(50).unwrap();
+    warp(3_600);
+    assert_eq!(
+        bridge.apply_fee_rate(),
+        Err(bridge_err(BridgeError::FeeExceedsProtocolMax))
+    );
+    assert_eq!(bridge.pool_state().deposit_fee_bps, 0);
+}
+
+#[test]
+fn bridge_fee_is_taken_from_the_locked_amount() {
+    let mut bridge = Bridge::new();
+    bridge.update_fee_rate(0, 0, 50).unwrap();
+    let user = bridge.user(10_000);
+
+    bridge.lock(&user, 10_000).unwrap();
+    let intent = &bridge.rt.events::<BridgeIntent>()[0];
+    assert_eq!((intent.gross_amount, intent.amount), (10_000, 9_950));
+    assert_eq!(bridge.lock_state(0).amount, 9_950);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 50);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 9_950);
+    let pool = bridge.pool_state();
+    assert_eq!(
+        (pool.locked_liquidity, pool.total_liquidity),
+        (9_950, 9_950)
+    );
+    assert_eq!(bridge.effective_fee(OperationKind::Bridge), 50);
+
+    // The relayer pays out exactly the net amount.
+    bridge.complete(0).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.relayer_payout), 9_950);
+}
+
+#[test]
+fn lock_net_of_fee_must_be_positive() {
+    let mut bridge = Bridge::new();
+    bridge.set_max_fee_bps(1_000).unwrap();
+    bridge.update_fee_rate(0, 0, 1_000).unwrap();
+    let user = bridge.user(100);
+    // The fee rounds up, so it takes all of a 1-unit lock.
+    assert_eq!(
+        bridge.lock(&user, 1),
+        Err(bridge_err(BridgeError::ZeroStablecoinAmount))
+    );
+    assert_eq!(
+        bridge.check_lock(1),
+        code(BridgeError::ZeroStablecoinAmount)
+    );
+}
+
+#[test]
+fn fee_increase_waits_for_the_timelock() {
+    let mut bridge = Bridge::new();
+    bridge.set_fee_timelock(3_600).unwrap();
+    let event = &bridge.rt.events::<FeeTimelockUpdated>()[0];
+    assert_eq!((event.old_seconds, event.new_seconds), (0, 3_600));
+
+    bridge.propose_fee_rate(50, 0, 0).unwrap();
+    let proposed = &bridge.rt.events::<FeeRateProposed>()[0];
+    assert_eq!(
+        (proposed.deposit_fee_bps, proposed.effective_at),
+        (50, now() + 3_600)
+    );
+    assert_eq!(bridge.pool_state().deposit_fee_bps, 0);
+
+    let early = Err(bridge_err(BridgeError::FeeTimelockActive));
+    assert_eq!(bridge.apply_fee_rate(), early);
+    warp(3_599);
+    assert_eq!(bridge.apply_fee_rate(), early);
+
+    warp(1);
+    bridge.apply_fee_rate().unwrap();
+    let applied = bridge.rt.events::<FeeRateApplied>();
+    assert_eq!(applied.len(), 1);
+    assert!(bridge.rt.events::<FeeRateUpdated>().is_empty());
+    assert_eq!(
+        (
+            applied[0].old_deposit_fee_bps,
+            applied[0].new_deposit_fee_bps
+        ),
+        (0, 50)
+    );
+    assert_eq!(bridge.pool_state().deposit_fee_bps, 50);
+    assert_eq!(
+        bridge.apply_fee_rate(),
+        Err(bridge_err(BridgeError::NoPendingFeeRate))
+    );
+}
+
+#[test]
+fn fee_decrease_applies_immediately_and_cancels_a_pending_increase() {
+    let mut bridge = fee_pool();
+    bridge.set_max_fee_bps(200).unwrap();
+    bridge.set_fee_timelock(3_600).unwrap();
+    bridge.propose_fee_rate(200, 0, 0).unwrap();
+
+    bridge.propose_fee_rate(50, 0, 0).unwrap();
+    assert_eq!(bridge.pool_state().deposit_fee_bps, 50);
+    let updated = &bridge.rt.events::<FeeRateUpdated>()[0];
+    assert_eq!(
+        (updated.old_deposit_fee_bps, updated.new_deposit_fee_bps),
+        (100, 50)
+    );
+
+    warp(3_600);
+    assert_eq!(
+        bridge.apply_fee_rate(),
+        Err(bridge_err(BridgeError::NoPendingFeeRate))
+    );
+}
+
+#[test]
+fn fee_timelock_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.propose_fee_rate(50, 0, 0).unwrap();
+    bridge.admin = bridge.relayer;
+    let unauthorized = Err(bridge_err(BridgeError::UnauthorizedAdmin));
+    assert_eq!(bridge.set_fee_timelock(0), unauthorized);
+    assert_eq!(bridge.apply_fee_rate(), unauthorized);
+}
+
+#[test]
+fn fees_round_up() {
+    assert_eq!(crate::fee_amount(0, 30).unwrap(), 0);
+    assert_eq!(crate::fee_amount(1, 1).unwrap(), 1);
+    assert_eq!(crate::fee_amount(10_000, 30).unwrap(), 30);
+    assert_eq!(crate::fee_amount(10_001, 30).unwrap(), 31);
+    assert_eq!(crate::fee_amount(5_000, 0).unwrap(), 0);
+}
+
+#[test]
+fn deposit_then_withdraw_never_returns_more_than_deposited() {
+    let mut bridge = Bridge::new();
+    bridge.set_max_fee_bps(300).unwrap();
+    let seed_lp = bridge.user(1_000_000);
+    bridge.deposit(&seed_lp, 100_000).unwrap();
+
+    // Deterministic xorshift so failures replay.
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut next = move |bound: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % bound
+    };
+    for _ in 0..60 {
+        // Skew the share price with a donation and vary the fee rates.
+        bridge.deposit_and_burn(&seed_lp, 1 + next(3_333)).unwrap();
+        let (deposit_bps, withdraw_bps) = (next(300) as u16, next(300) as u16);
+        bridge
+            .update_fee_rate(deposit_bps, withdraw_bps, 0)
+            .unwrap();
+
+        let amount = 1 + next(50_000);
+        let user = bridge.user(amount);
+        if bridge.deposit(&user, amount).is_err() {
+            // Too small to mint a single LP unit.
+            continue;
+        }
+        let lp = bridge.rt.balance(&user.lp_ata);
+        bridge.withdraw(&user, lp).unwrap();
+        assert!(bridge.rt.balance(&user.ata) <= amount);
+    }
+}
+
+#[test]
+fn split_fee_rounds_the_protocol_share_down() {
+    let mut config = Bridge::new().config_state();
+    for (share_bps, fee, expected) in [
+        (0, 90, (0, 90)),
+        (2_500, 90, (22, 68)),
+        (3_333, 3, (0, 3)),
+        (5_000, 91, (45, 46)),
+        (10_000, 90, (90, 0)),
+        (10_000, u64::MAX, (u64::MAX, 0)),
+    ] {
+        config.protocol_fee_share_bps = share_bps;
+        assert_eq!(
+            crate::split_fee(&config, fee).map_err(ProgramError::from),
+            Ok(expected)
+        );
+    }
+}
+
+#[test]
+fn protocol_fee_share_defaults_to_the_whole_fee() {
+    let mut bridge = fee_pool();
+    assert_eq!(bridge.config_state().protocol_fee_share_bps, 10_000);
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    let event = &bridge.rt.events::<DepositFeeCollected>()[0];
+    assert_eq!(
+        (event.fee_amount, event.protocol_amount, event.lp_amount),
+        (100, 100, 0)
+    );
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 100);
+    assert_eq!(bridge.pool_state().available_liquidity, 9_900);
+}
+
+#[test]
+fn lp_share_of_a_deposit_fee_stays_in_the_pool() {
+    let mut bridge = fee_pool();
+    bridge.set_protocol_fee_share(2_500).unwrap();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+
+    let event = &bridge.rt.events::<DepositFeeCollected>()[0];
+    assert_eq!(
+        (event.fee_amount, event.protocol_amount, event.lp_amount),
+        (100, 25, 75)
+    );
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 25);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 9_975);
+    let pool = bridge.pool_state();
+    assert_eq!(
+        (pool.total_liquidity, pool.available_liquidity),
+        (9_975, 9_975)
+    );
+}
+
+#[test]
+fn lp_share_of_a_withdraw_fee_never_leaves_the_vault() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.set_max_fee_bps(200).unwrap();
+    bridge.update_fee_rate(0, 200, 0).unwrap();
+    bridge.set_protocol_fee_share(0).unwrap();
+
+    bridge.withdraw(&user, 4_500).unwrap();
+    let event = &bridge.rt.events::<WithdrawFeeCollected>()[0];
+    assert_eq!(
+        (event.fee_amount, event.protocol_amount, event.lp_amount),
+        (90, 0, 90)
+    );
+    assert_eq!(bridge.rt.balance(&user.ata), 4_410);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 0);
+    assert_eq!(bridge.pool_state().available_liquidity, 5_590);
+}
+
+#[test]
+fn bridge_fee_is_split_and_reported() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.update_fee_rate(0, 0, 100).unwrap();
+    bridge.set_protocol_fee_share(5_000).unwrap();
+
+    bridge.lock(&user, 1_000).unwrap();
+    let event = &bridge.rt.events::<BridgeFeeCollected>()[0];
+    assert_eq!(event.sender, user.key);
+    assert_eq!(
+        (event.fee_amount, event.protocol_amount, event.lp_amount),
+        (10, 5, 5)
+    );
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 5);
+    assert_eq!(bridge.pool_state().available_liquidity, 10_005);
+
+    // Fee-free locks emit no fee event.
+    bridge.update_fee_rate(0, 0, 0).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    assert!(bridge.rt.events::<BridgeFeeCollected>().is_empty());
+}
+
+#[test]
+fn protocol_fee_share_is_bounded_and_admin_only() {
+    let mut bridge = Bridge::new();
+    assert_eq!(
+        bridge.set_protocol_fee_share(10_001),
+        Err(bridge_err(BridgeError::InvalidFeeRate))
+    );
+    bridge.set_protocol_fee_share(4_000).unwrap();
+    let event = &bridge.rt.events::<ProtocolFeeShareUpdated>()[0];
+    assert_eq!((event.config, event.admin), (bridge.config, bridge.admin));
+    assert_eq!((event.old_share_bps, event.new_share_bps), (10_000, 4_000));
+
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_protocol_fee_share(0),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
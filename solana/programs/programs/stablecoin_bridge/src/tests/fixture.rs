@@ -0,0 +1,1572 @@
+//! A config plus one pool, wired up the way a client would, with helpers
+//! for each instruction.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{ed25519_program, system_program, sysvar};
+use anchor_lang::InstructionData;
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::metadata::mpl_token_metadata::accounts::Metadata;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+
+use super::harness::{Runtime, TxResult};
+use crate::state::{
+    AddressFormat, ApprovalAction, BridgeLock, ChainInfo, Config, LockParams, LockStatus,
+    OperationKind, PauseReason, Pool, PoolView,
+};
+use crate::{accounts, instruction};
+
+pub struct PoolParams {
+    pub fee_rate_bps: u16,
+    pub max_lock_per_tx: u64,
+    pub lock_cooldown_seconds: u32,
+    pub min_lock_amount: u64,
+}
+
+impl Default for PoolParams {
+    fn default() -> Self {
+        Self {
+            fee_rate_bps: 0,
+            max_lock_per_tx: 1_000_000_000_000,
+            lock_cooldown_seconds: 0,
+            min_lock_amount: 0,
+        }
+    }
+}
+
+/// Token program (and extensions) the pool's stablecoin lives under.
+#[derive(Clone, Copy)]
+pub enum Stablecoin {
+    Token,
+    Token2022,
+    /// Token-2022 with a transfer fee in bps.
+    TransferFee(u16),
+    /// Token-2022 with the TransferHook extension (no hook program set).
+    TransferHook,
+    /// The wrapped-SOL mint.
+    NativeSol,
+}
+
+pub struct User {
+    pub key: Pubkey,
+    pub ata: Pubkey,
+    pub lp_ata: Pubkey,
+}
+
+pub struct Bridge {
+    pub rt: Runtime,
+    pub admin: Pubkey,
+    pub relayer: Pubkey,
+    pub config: Pubkey,
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub vault: Pubkey,
+    pub fee_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub relayer_payout: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub const CHAIN: u64 = 1;
+pub const RECIPIENT: [u8; 32] = [7; 32];
+
+/// A deterministic Ed25519 keypair (a relayer's signing key).
+pub fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+/// Ed25519 program instruction verifying `signer`'s signature over `message`, laid out like
+/// `solana_sdk::ed25519_instruction::new_ed25519_instruction`.
+pub fn ed25519_verify(signer: &Keypair, message: &[u8]) -> (Instruction, [u8; 64]) {
+    let signature = signer.sign(message).to_bytes();
+    let (public_key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.public.as_bytes());
+    data.extend_from_slice(&signature);
+    data.extend_from_slice(message);
+    let instruction = Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    };
+    (instruction, signature)
+}
+
+/// Zero-padded short chain name.
+pub fn chain_name(name: &str) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    bytes[..name.len()].copy_from_slice(name.as_bytes());
+    bytes
+}
+
+impl Bridge {
+    pub fn new() -> Self {
+        Self::with(PoolParams::default())
+    }
+
+    pub fn with(params: PoolParams) -> Self {
+        Self::with_stablecoin(Stablecoin::Token, params)
+    }
+
+    pub fn with_stablecoin(stablecoin: Stablecoin, params: PoolParams) -> Self {
+        let mut bridge = Self::bare_with(stablecoin);
+        bridge.initialize_config().unwrap();
+        bridge.initialize_pool(params).unwrap();
+        bridge.add_supported_chain(CHAIN).unwrap();
+        bridge
+    }
+
+    /// Accounts for a config and pool, without running either initializer.
+    pub fn bare() -> Self {
+        Self::bare_with(Stablecoin::Token)
+    }
+
+    pub fn bare_with(stablecoin: Stablecoin) -> Self {
+        let mut rt = Runtime::new();
+        let admin = rt.wallet();
+        let relayer = rt.wallet();
+        let (token_program, mint) = match stablecoin {
+            Stablecoin::Token => (spl_token::ID, rt.create_mint(spl_token::ID, admin, 6)),
+            Stablecoin::Token2022 => (
+                spl_token_2022::ID,
+                rt.create_mint(spl_token_2022::ID, admin, 6),
+            ),
+            Stablecoin::TransferFee(fee_bps) => (
+                spl_token_2022::ID,
+                rt.create_transfer_fee_mint(admin, 6, fee_bps),
+            ),
+            Stablecoin::TransferHook => {
+                (spl_token_2022::ID, rt.create_transfer_hook_mint(admin, 6))
+            }
+            Stablecoin::NativeSol => (spl_token::ID, rt.create_native_mint()),
+        };
+        let config = Pubkey::find_program_address(&[b"config"], &crate::ID).0;
+        let pool = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &crate::ID).0;
+        let vault = rt.create_token_account(mint, pool, 0);
+        let fee_vault = rt.create_token_account(mint, pool, 0);
+        let lp_mint = rt.create_mint(token_program, pool, rt.mint(&mint).decimals);
+        let relayer_payout = rt.create_token_account(mint, relayer, 0);
+        Self {
+            rt,
+            admin,
+            relayer,
+            config,
+            mint,
+            pool,
+            vault,
+            fee_vault,
+            lp_mint,
+            relayer_payout,
+            token_program,
+        }
+    }
+
+    pub fn initialize_config(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::InitializeConfig {
+                config: self.config,
+                admin: self.admin,
+                relayer: self.relayer,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeConfig {},
+        )
+    }
+
+    pub fn initialize_pool(&mut self, params: PoolParams) -> TxResult {
+        self.rt.send(
+            accounts::InitializePool {
+                pool: self.pool,
+                admin: self.admin,
+                config: self.config,
+                stablecoin_mint: self.mint,
+                pool_tombstone: self.pool_tombstone_pda(),
+                vault: self.vault,
+                fee_vault: self.fee_vault,
+                lp_token_mint: self.lp_mint,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            instruction::InitializePool {
+                fee_rate_bps: params.fee_rate_bps,
+                max_lock_per_tx: params.max_lock_per_tx,
+                lock_cooldown_seconds: params.lock_cooldown_seconds,
+                min_lock_amount: params.min_lock_amount,
+            },
+        )
+    }
+
+    /// A wallet holding `stablecoin` tokens and an empty LP account.
+    pub fn user(&mut self, stablecoin: u64) -> User {
+        let key = self.rt.wallet();
+        let ata = self.rt.create_token_account(self.mint, key, stablecoin);
+        let lp_ata = self.rt.create_token_account(self.lp_mint, key, 0);
+        User { key, ata, lp_ata }
+    }
+
+    pub fn pool_state(&self) -> Pool {
+        self.rt.state(&self.pool)
+    }
+
+    pub fn config_state(&self) -> Config {
+        self.rt.state(&self.config)
+    }
+
+    pub fn lock_pda(&self, nonce: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"bridge_lock", self.pool.as_ref(), &nonce.to_le_bytes()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn user_lock_pda(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"user_lock", self.pool.as_ref(), user.as_ref()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn user_lock_index_pda(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"user_index", self.pool.as_ref(), user.as_ref()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn user_lp_pda(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"user_lp", self.pool.as_ref(), user.as_ref()], &crate::ID).0
+    }
+
+    pub fn set_cooldown_exemption(&mut self, user: &Pubkey, exempt: bool) -> TxResult {
+        let accounts = accounts::SetCooldownExemption {
+            pool: self.pool,
+            config: self.config,
+            admin: self.admin,
+            user: *user,
+            user_lock_state: self.user_lock_pda(user),
+            system_program: system_program::ID,
+        };
+        if exempt {
+            self.rt
+                .send(accounts, instruction::GrantCooldownExemption {})
+        } else {
+            self.rt
+                .send(accounts, instruction::RevokeCooldownExemption {})
+        }
+    }
+
+    pub fn chain_pda(&self, chain_id: u64) -> Pubkey {
+        Pubkey::find_program_address(&[b"chain", &chain_id.to_le_bytes()], &crate::ID).0
+    }
+
+    pub fn chain_lock_counter_pda(&self, chain_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"chain_lock_counter",
+                self.pool.as_ref(),
+                &chain_id.to_le_bytes(),
+            ],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn chain_state(&self, chain_id: u64) -> ChainInfo {
+        self.rt.state(&self.chain_pda(chain_id))
+    }
+
+    pub fn set_chain_name(&mut self, chain_id: u64, name: &str) -> TxResult {
+        self.rt.send(
+            accounts::SetChainName {
+                config: self.config,
+                admin: self.admin,
+                chain_info: self.chain_pda(chain_id),
+                system_program: system_program::ID,
+            },
+            instruction::SetChainName {
+                chain_id,
+                name: chain_name(name),
+            },
+        )
+    }
+
+    pub fn set_chain_address_format(
+        &mut self,
+        chain_id: u64,
+        address_format: AddressFormat,
+    ) -> TxResult {
+        self.rt.send(
+            accounts::SetChainAddressFormat {
+                config: self.config,
+                admin: self.admin,
+                chain_info: self.chain_pda(chain_id),
+                system_program: system_program::ID,
+            },
+            instruction::SetChainAddressFormat {
+                chain_id,
+                address_format,
+            },
+        )
+    }
+
+    pub fn add_supported_chain(&mut self, chain_id: u64) -> TxResult {
+        self.rt.send(
+            accounts::AddSupportedChain {
+                config: self.config,
+                admin: self.admin,
+                chain_info: self.chain_pda(chain_id),
+                system_program: system_program::ID,
+            },
+            instruction::AddSupportedChain { chain_id },
+        )
+    }
+
+    pub fn remove_supported_chain(&mut self, chain_id: u64) -> TxResult {
+        self.rt.send(
+            accounts::RemoveSupportedChain {
+                config: self.config,
+                admin: self.admin,
+                chain_info: self.chain_pda(chain_id),
+            },
+            instruction::RemoveSupportedChain { chain_id },
+        )
+    }
+
+    pub fn lock_state(&self, nonce: u64) -> BridgeLock {
+        self.rt.state(&self.lock_pda(nonce))
+    }
+
+    pub fn deposit(&mut self, user: &User, amount: u64) -> TxResult {
+        self.deposit_min(user, amount, 0)
+    }
+
+    pub fn deposit_min(&mut self, user: &User, amount: u64, min_lp_out: u64) -> TxResult {
+        self.deposit_for(user, user, amount, min_lp_out)
+    }
+
+    /// Deposit from `user`, minting the LP to `beneficiary`.
+    pub fn deposit_for(
+        &mut self,
+        user: &User,
+        beneficiary: &User,
+        amount: u64,
+        min_lp_out: u64,
+    ) -> TxResult {
+        self.rt.send(
+            self.deposit_accounts(user, beneficiary),
+            instruction::DepositLiquidity { amount, min_lp_out },
+        )
+    }
+
+    /// Deposit native lamports into a wrapped-SOL pool through the user's wSOL account.
+    pub fn deposit_sol(&mut self, user: &User, lamports: u64) -> TxResult {
+        self.rt.send(
+            self.deposit_accounts(user, user),
+            instruction::DepositLiquiditySol {
+                lamports,
+                min_lp_out: 0,
+            },
+        )
+    }
+
+    fn deposit_accounts(&self, user: &User, beneficiary: &User) -> accounts::DepositLiquidity {
+        accounts::DepositLiquidity {
+            pool: self.pool,
+            config: self.config,
+            depositor: user.key,
+            user_stablecoin_ata: user.ata,
+            user_lp_ata: beneficiary.lp_ata,
+            vault: self.vault,
+            fee_vault: self.fee_vault,
+            lp_token_mint: self.lp_mint,
+            user_lp_state: self.user_lp_pda(&beneficiary.key),
+            stablecoin_mint: self.mint,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn deposit_and_burn(&mut self, user: &User, amount: u64) -> TxResult {
+        self.rt.send(
+            accounts::DepositAndBurnLp {
+                pool: self.pool,
+                config: self.config,
+                donor: user.key,
+                user_stablecoin_ata: user.ata,
+                vault: self.vault,
+                fee_vault: self.fee_vault,
+                lp_token_mint: self.lp_mint,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::DepositAndBurnLp { amount },
+        )
+    }
+
+    /// Pay `amount` of yield from `source` (a stablecoin account owned by the admin).
+    pub fn distribute_yield(&mut self, source: Pubkey, amount: u64) -> TxResult {
+        self.rt.send(
+            accounts::DistributeYield {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                source_stablecoin_ata: source,
+                vault: self.vault,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::DistributeYield { amount },
+        )
+    }
+
+    pub fn withdraw(&mut self, user: &User, lp_amount: u64) -> TxResult {
+        self.withdraw_min(user, lp_amount, 0)
+    }
+
+    pub fn withdraw_min(
+        &mut self,
+        user: &User,
+        lp_amount: u64,
+        min_stablecoin_out: u64,
+    ) -> TxResult {
+        self.rt.send(
+            self.withdraw_accounts(user),
+            instruction::WithdrawLiquidity {
+                lp_amount,
+                min_stablecoin_out,
+            },
+        )
+    }
+
+    /// Withdraw from a wrapped-SOL pool as native lamports, closing the user's wSOL account.
+    pub fn withdraw_sol(&mut self, user: &User, lp_amount: u64) -> TxResult {
+        self.rt.send(
+            self.withdraw_accounts(user),
+            instruction::WithdrawLiquiditySol {
+                lp_amount,
+                min_stablecoin_out: 0,
+            },
+        )
+    }
+
+    pub fn withdrawal_request_pda(&self, owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"withdrawal_request", self.pool.as_ref(), owner.as_ref()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn request_withdrawal(&mut self, user: &User, lp_amount: u64) -> TxResult {
+        self.rt.send(
+            accounts::RequestWithdrawal {
+                pool: self.pool,
+                config: self.config,
+                owner: user.key,
+                user_lp_ata: user.lp_ata,
+                lp_token_mint: self.lp_mint,
+                withdrawal_request: self.withdrawal_request_pda(&user.key),
+                user_lp_state: self.user_lp_pda(&user.key),
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            instruction::RequestWithdrawal {
+                lp_amount,
+                min_stablecoin_out: 0,
+            },
+        )
+    }
+
+    pub fn fulfill_withdrawal(&mut self, user: &User) -> TxResult {
+        self.rt.send(
+            accounts::FulfillWithdrawal {
+                pool: self.pool,
+                config: self.config,
+                withdrawal_request: self.withdrawal_request_pda(&user.key),
+                owner: user.key,
+                owner_stablecoin_ata: user.ata,
+                vault: self.vault,
+                fee_vault: self.fee_vault,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::FulfillWithdrawal {},
+        )
+    }
+
+    fn withdraw_accounts(&self, user: &User) -> accounts::WithdrawLiquidity {
+        accounts::WithdrawLiquidity {
+            pool: self.pool,
+            config: self.config,
+            withdrawer: user.key,
+            user_lp_ata: user.lp_ata,
+            user_stablecoin_ata: user.ata,
+            vault: self.vault,
+            fee_vault: self.fee_vault,
+            lp_token_mint: self.lp_mint,
+            user_lp_state: self.user_lp_pda(&user.key),
+            stablecoin_mint: self.mint,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn lock(&mut self, user: &User, amount: u64) -> TxResult {
+        self.lock_to(user, amount, CHAIN, RECIPIENT)
+    }
+
+    pub fn lock_to(
+        &mut self,
+        user: &User,
+        amount: u64,
+        destination_chain_id: u64,
+        recipient_address: [u8; 32],
+    ) -> TxResult {
+        self.lock_with(
+            user,
+            LockParams {
+                amount,
+                destination_chain_id,
+                recipient_address,
+                client_tag: [0; 16],
+                recipient_checksum: None,
+            },
+        )
+    }
+
+    pub fn lock_with(&mut self, user: &User, params: LockParams) -> TxResult {
+        self.rt.send(
+            self.lock_accounts(user, params.destination_chain_id),
+            instruction::LockForBridge {
+                amount: params.amount,
+                destination_chain_id: params.destination_chain_id,
+                recipient_address: params.recipient_address,
+                client_tag: params.client_tag,
+                recipient_checksum: params.recipient_checksum,
+            },
+        )
+    }
+
+    /// Lock native lamports from a wrapped-SOL pool's user toward CHAIN.
+    pub fn lock_sol(&mut self, user: &User, lamports: u64) -> TxResult {
+        self.rt.send(
+            self.lock_accounts(user, CHAIN),
+            instruction::LockForBridgeSol {
+                lamports,
+                destination_chain_id: CHAIN,
+                recipient_address: RECIPIENT,
+                client_tag: [0; 16],
+                recipient_checksum: None,
+            },
+        )
+    }
+
+    fn lock_accounts(&self, user: &User, destination_chain_id: u64) -> accounts::LockForBridge {
+        let nonce = self.pool_state().next_lock_nonce;
+        accounts::LockForBridge {
+            pool: self.pool,
+            config: self.config,
+            sender: user.key,
+            user_stablecoin_ata: user.ata,
+            vault: self.vault,
+            fee_vault: self.fee_vault,
+            bridge_lock: self.lock_pda(nonce),
+            user_lock_state: self.user_lock_pda(&user.key),
+            user_lock_index: self.user_lock_index_pda(&user.key),
+            chain_info: self.chain_pda(destination_chain_id),
+            chain_lock_counter: self.chain_lock_counter_pda(destination_chain_id),
+            stablecoin_mint: self.mint,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Lock each item in one lock_for_bridge_batch call, passing (lock PDA, ChainInfo) pairs.
+    pub fn lock_batch(&mut self, user: &User, locks: &[LockParams]) -> TxResult {
+        let first_nonce = self.pool_state().next_lock_nonce;
+        let mut metas = accounts::LockForBridgeBatch {
+            pool: self.pool,
+            config: self.config,
+            sender: user.key,
+            user_stablecoin_ata: user.ata,
+            vault: self.vault,
+            fee_vault: self.fee_vault,
+            user_lock_state: self.user_lock_pda(&user.key),
+            user_lock_index: self.user_lock_index_pda(&user.key),
+            stablecoin_mint: self.mint,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        for (nonce, params) in (first_nonce..).zip(locks) {
+            metas.push(AccountMeta::new(self.lock_pda(nonce), false));
+            metas.push(AccountMeta::new_readonly(
+                self.chain_pda(params.destination_chain_id),
+                false,
+            ));
+            metas.push(AccountMeta::new(
+                self.chain_lock_counter_pda(params.destination_chain_id),
+                false,
+            ));
+        }
+        self.rt.process(Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data: instruction::LockForBridgeBatch {
+                locks: locks.to_vec(),
+            }
+            .data(),
+        })
+    }
+
+    pub fn complete(&mut self, nonce: u64) -> TxResult {
+        self.rt.send(
+            accounts::CompleteBridge {
+                pool: self.pool,
+                config: self.config,
+                relayer: self.relayer,
+                bridge_lock: self.lock_pda(nonce),
+                vault: self.vault,
+                relayer_payout: self.relayer_payout,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::CompleteBridge {},
+        )
+    }
+
+    pub fn close_lock(&mut self, nonce: u64, sender: &Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::CloseBridgeLock {
+                bridge_lock: self.lock_pda(nonce),
+                sender: *sender,
+            },
+            instruction::CloseBridgeLock {},
+        )
+    }
+
+    /// Propose fee rates and, if that queues an increase, apply it (no timelock by default).
+    pub fn update_fee_rate(
+        &mut self,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        bridge_fee_bps: u16,
+    ) -> TxResult {
+        self.propose_fee_rate(deposit_fee_bps, withdraw_fee_bps, bridge_fee_bps)?;
+        if self.pool_state().fee_effective_at != 0 {
+            self.apply_fee_rate()?;
+        }
+        Ok(())
+    }
+
+    pub fn propose_fee_rate(
+        &mut self,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        bridge_fee_bps: u16,
+    ) -> TxResult {
+        self.rt.send(
+            self.fee_rate_accounts(),
+            instruction::ProposeFeeRate {
+                deposit_fee_bps,
+                withdraw_fee_bps,
+                bridge_fee_bps,
+            },
+        )
+    }
+
+    pub fn apply_fee_rate(&mut self) -> TxResult {
+        self.rt
+            .send(self.fee_rate_accounts(), instruction::ApplyFeeRate {})
+    }
+
+    fn fee_rate_accounts(&self) -> accounts::UpdateFeeRate {
+        accounts::UpdateFeeRate {
+            pool: self.pool,
+            config: self.config,
+            admin: self.admin,
+        }
+    }
+
+    pub fn set_max_fee_bps(&mut self, max_fee_bps: u16) -> TxResult {
+        self.rt.send(
+            accounts::SetMaxFeeBps {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetMaxFeeBps { max_fee_bps },
+        )
+    }
+
+    pub fn set_allow_transfer_hook_mints(&mut self, allowed: bool) -> TxResult {
+        self.rt.send(
+            accounts::SetAllowTransferHookMints {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetAllowTransferHookMints { allowed },
+        )
+    }
+
+    pub fn set_fee_timelock(&mut self, seconds: u32) -> TxResult {
+        self.rt.send(
+            accounts::SetFeeTimelock {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetFeeTimelock { seconds },
+        )
+    }
+
+    pub fn effective_fee(&mut self, op_kind: OperationKind) -> u16 {
+        self.rt
+            .send(
+                accounts::EffectiveFee { pool: self.pool },
+                instruction::EffectiveFee { op_kind },
+            )
+            .unwrap();
+        self.rt.returned()
+    }
+
+    pub fn resync_lp_supply(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::ResyncLpSupply {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                lp_token_mint: self.lp_mint,
+            },
+            instruction::ResyncLpSupply {},
+        )
+    }
+
+    pub fn migrate_lp_mint(&mut self, new_lp_token_mint: Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::MigrateLpMint {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                stablecoin_mint: self.mint,
+                new_lp_token_mint,
+            },
+            instruction::MigrateLpMint {},
+        )
+    }
+
+    pub fn migrate_pool(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::MigratePool {
+                pool: self.pool,
+                stablecoin_mint: self.mint,
+                fee_vault: self.fee_vault,
+                lp_token_mint: self.lp_mint,
+                admin: self.admin,
+                system_program: system_program::ID,
+            },
+            instruction::MigratePool {},
+        )
+    }
+
+    pub fn migrate_config(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::MigrateConfig {
+                config: self.config,
+                admin: self.admin,
+                system_program: system_program::ID,
+            },
+            instruction::MigrateConfig {},
+        )
+    }
+
+    pub fn migrate_bridge_lock(&mut self, nonce: u64) -> TxResult {
+        self.rt.send(
+            accounts::MigrateBridgeLock {
+                pool: self.pool,
+                bridge_lock: self.lock_pda(nonce),
+                admin: self.admin,
+                system_program: system_program::ID,
+            },
+            instruction::MigrateBridgeLock {},
+        )
+    }
+
+    pub fn pool_tombstone_pda(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"pool_tombstone", self.mint.as_ref()], &crate::ID).0
+    }
+
+    pub fn close_pool(&mut self, destination: Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::ClosePool {
+                pool: self.pool,
+                config: self.config,
+                vault: self.vault,
+                fee_vault: self.fee_vault,
+                stablecoin_mint: self.mint,
+                lp_token_mint: self.lp_mint,
+                destination,
+                pool_tombstone: self.pool_tombstone_pda(),
+                admin: self.admin,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            instruction::ClosePool {},
+        )
+    }
+
+    pub fn begin_draining(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::BeginDraining {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::BeginDraining {},
+        )
+    }
+
+    pub fn propose_config_admin(&mut self, new_admin: Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::ProposeConfigAdmin {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::ProposeConfigAdmin { new_admin },
+        )
+    }
+
+    pub fn accept_config_admin(&mut self, new_admin: Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::AcceptConfigAdmin {
+                config: self.config,
+                new_admin,
+            },
+            instruction::AcceptConfigAdmin {},
+        )
+    }
+
+    pub fn set_relayer(&mut self, old_relayer: Pubkey, new_relayer: Pubkey) -> TxResult {
+        self.rt.send(
+            self.manage_relayers(),
+            instruction::SetRelayer {
+                old_relayer,
+                new_relayer,
+            },
+        )
+    }
+
+    pub fn add_relayer(&mut self, relayer: Pubkey) -> TxResult {
+        self.rt
+            .send(self.manage_relayers(), instruction::AddRelayer { relayer })
+    }
+
+    pub fn remove_relayer(&mut self, relayer: Pubkey) -> TxResult {
+        self.rt.send(
+            self.manage_relayers(),
+            instruction::RemoveRelayer { relayer },
+        )
+    }
+
+    pub fn rotate_authorities(&mut self, new_admin: Pubkey, new_relayer: Pubkey) -> TxResult {
+        self.rt.send(
+            self.manage_relayers(),
+            instruction::RotateAuthorities {
+                new_admin,
+                new_relayer,
+            },
+        )
+    }
+
+    pub fn set_release_threshold(&mut self, threshold: u8) -> TxResult {
+        self.rt.send(
+            accounts::SetReleaseThreshold {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetReleaseThreshold { threshold },
+        )
+    }
+
+    fn manage_relayers(&self) -> accounts::ManageRelayers {
+        accounts::ManageRelayers {
+            config: self.config,
+            admin: self.admin,
+        }
+    }
+
+    pub fn propose_pool_admin(&mut self, new_admin: Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::ProposePoolAdmin {
+                pool: self.pool,
+                admin: self.admin,
+            },
+            instruction::ProposePoolAdmin { new_admin },
+        )
+    }
+
+    pub fn accept_pool_admin(&mut self, new_admin: Pubkey) -> TxResult {
+        self.rt.send(
+            accounts::AcceptPoolAdmin {
+                pool: self.pool,
+                new_admin,
+            },
+            instruction::AcceptPoolAdmin {},
+        )
+    }
+
+    pub fn pause_pool(&mut self) -> TxResult {
+        self.pause_pool_for(PauseReason::Maintenance)
+    }
+
+    pub fn pause_pool_for(&mut self, reason: PauseReason) -> TxResult {
+        self.rt.send(
+            accounts::PauseResumePool {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::PausePool { reason },
+        )
+    }
+
+    pub fn resume_pool(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::PauseResumePool {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::ResumePool {},
+        )
+    }
+
+    pub fn set_auto_revert_after(&mut self, seconds: u32) -> TxResult {
+        self.rt.send(
+            accounts::SetAutoRevertAfter {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetAutoRevertAfter { seconds },
+        )
+    }
+
+    pub fn set_lock_window(
+        &mut self,
+        max_lock_per_window: u64,
+        lock_window_seconds: u32,
+    ) -> TxResult {
+        self.rt.send(
+            accounts::SetLockWindow {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetLockWindow {
+                max_lock_per_window,
+                lock_window_seconds,
+            },
+        )
+    }
+
+    pub fn set_outflow_limit(
+        &mut self,
+        max_outflow_per_window: u64,
+        outflow_window_seconds: u32,
+    ) -> TxResult {
+        self.rt.send(
+            accounts::SetOutflowLimit {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetOutflowLimit {
+                max_outflow_per_window,
+                outflow_window_seconds,
+            },
+        )
+    }
+
+    pub fn set_relayer_fee(&mut self, relayer_fee: u64) -> TxResult {
+        self.rt.send(
+            accounts::SetRelayerFee {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetRelayerFee { relayer_fee },
+        )
+    }
+
+    pub fn set_compact_events(&mut self, compact_events: bool) -> TxResult {
+        self.rt.send(
+            accounts::SetCompactEvents {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetCompactEvents { compact_events },
+        )
+    }
+
+    pub fn set_collect_rounding_dust(&mut self, collect_rounding_dust: bool) -> TxResult {
+        self.rt.send(
+            accounts::SetCollectRoundingDust {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetCollectRoundingDust {
+                collect_rounding_dust,
+            },
+        )
+    }
+
+    pub fn set_min_lock_amount(&mut self, min_lock_amount: u64) -> TxResult {
+        self.rt.send(
+            accounts::SetMinLockAmount {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetMinLockAmount { min_lock_amount },
+        )
+    }
+
+    pub fn set_min_lp_hold(&mut self, min_lp_hold_seconds: u32) -> TxResult {
+        self.rt.send(
+            accounts::SetMinLpHold {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetMinLpHold {
+                min_lp_hold_seconds,
+            },
+        )
+    }
+
+    pub fn update_rate_limits(
+        &mut self,
+        max_lock_per_tx: u64,
+        lock_cooldown_seconds: u32,
+    ) -> TxResult {
+        self.rt.send(
+            accounts::UpdateRateLimits {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::UpdateRateLimits {
+                max_lock_per_tx,
+                lock_cooldown_seconds,
+            },
+        )
+    }
+
+    pub fn set_max_locked_liquidity(&mut self, max_locked_liquidity: u64) -> TxResult {
+        self.rt.send(
+            accounts::SetMaxLockedLiquidity {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetMaxLockedLiquidity {
+                max_locked_liquidity,
+            },
+        )
+    }
+
+    pub fn set_require_liquidity_backing(&mut self, require_liquidity_backing: bool) -> TxResult {
+        self.rt.send(
+            accounts::SetRequireLiquidityBacking {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetRequireLiquidityBacking {
+                require_liquidity_backing,
+            },
+        )
+    }
+
+    pub fn set_pool_cap(&mut self, cap: u64) -> TxResult {
+        self.rt.send(
+            accounts::SetPoolCap {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetPoolCap { cap },
+        )
+    }
+
+    /// Sweep the given locks, passed with their refund accounts as remaining accounts.
+    pub fn sweep_stale_locks(&mut self, nonces: &[u64]) -> TxResult {
+        let mut metas = accounts::SweepStaleLocks {
+            pool: self.pool,
+            vault: self.vault,
+            stablecoin_mint: self.mint,
+            token_program: self.token_program,
+        }
+        .to_account_metas(None);
+        for nonce in nonces {
+            metas.push(AccountMeta::new(self.lock_pda(*nonce), false));
+            metas.push(AccountMeta::new(self.sender_ata(*nonce), false));
+        }
+        self.rt.process(Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data: instruction::SweepStaleLocks {}.data(),
+        })
+    }
+
+    pub fn lp_metadata_pda(&self) -> Pubkey {
+        Metadata::find_pda(&self.lp_mint).0
+    }
+
+    pub fn set_lp_metadata(&mut self, name: &str, symbol: &str, uri: &str) -> TxResult {
+        self.rt.send(
+            accounts::SetLpMetadata {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                lp_token_mint: self.lp_mint,
+                metadata: self.lp_metadata_pda(),
+                token_metadata_program: mpl_token_metadata::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::SetLpMetadata {
+                name: name.to_string(),
+                symbol: symbol.to_string(),
+                uri: uri.to_string(),
+            },
+        )
+    }
+
+    pub fn reconcile_vault(&mut self, credit_to_lps: bool) -> TxResult {
+        self.rt.send(
+            accounts::ReconcileVault {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                vault: self.vault,
+                fee_vault: self.fee_vault,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::ReconcileVault { credit_to_lps },
+        )
+    }
+
+    /// Create a pool-owned token account and attach it as the insurance vault.
+    pub fn initialize_insurance_vault(&mut self) -> Pubkey {
+        let insurance_vault = self.rt.create_token_account(self.mint, self.pool, 0);
+        self.rt
+            .send(
+                accounts::InitializeInsuranceVault {
+                    pool: self.pool,
+                    admin: self.admin,
+                    insurance_vault,
+                },
+                instruction::InitializeInsuranceVault {},
+            )
+            .unwrap();
+        insurance_vault
+    }
+
+    pub fn fund_insurance(&mut self, funder: &User, amount: u64) -> TxResult {
+        self.rt.send(
+            accounts::FundInsurance {
+                pool: self.pool,
+                funder: funder.key,
+                funder_stablecoin_ata: funder.ata,
+                insurance_vault: self.pool_state().insurance_vault,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::FundInsurance { amount },
+        )
+    }
+
+    pub fn claim_insurance(&mut self, amount: u64) -> TxResult {
+        self.rt.send(
+            accounts::ClaimInsurance {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                insurance_vault: self.pool_state().insurance_vault,
+                vault: self.vault,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::ClaimInsurance { amount },
+        )
+    }
+
+    pub fn enable_emergency_mode(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::PauseResumePool {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::EnableEmergencyMode {},
+        )
+    }
+
+    pub fn disable_emergency_mode(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::PauseResumePool {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::DisableEmergencyMode {},
+        )
+    }
+
+    pub fn pause_protocol(&mut self) -> TxResult {
+        self.pause_protocol_for(PauseReason::Maintenance)
+    }
+
+    pub fn pause_protocol_for(&mut self, reason: PauseReason) -> TxResult {
+        self.rt.send(
+            accounts::PauseResumeProtocol {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::PauseProtocol { reason },
+        )
+    }
+
+    pub fn resume_protocol(&mut self) -> TxResult {
+        self.rt.send(
+            accounts::PauseResumeProtocol {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::ResumeProtocol {},
+        )
+    }
+
+    pub fn collect_fees(&mut self, destination: Pubkey, amount: u64) -> TxResult {
+        let insurance_vault = self.insurance_vault();
+        self.rt.send(
+            accounts::CollectFees {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                fee_vault: self.fee_vault,
+                destination,
+                insurance_vault,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::CollectFees { amount },
+        )
+    }
+
+    /// The pool's insurance vault, if one has been initialized.
+    fn insurance_vault(&mut self) -> Option<Pubkey> {
+        let insurance_vault = self.pool_state().insurance_vault;
+        (insurance_vault != Pubkey::default()).then_some(insurance_vault)
+    }
+
+    pub fn set_insurance_fee(&mut self, insurance_fee_bps: u16) -> TxResult {
+        self.rt.send(
+            accounts::SetInsuranceFee {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetInsuranceFee { insurance_fee_bps },
+        )
+    }
+
+    /// Flip the protocol-wide pause flag directly on the config account.
+    pub fn set_config_paused(&mut self, paused: bool) {
+        let mut config = self.config_state();
+        config.paused = paused;
+        self.rt.set_state(&self.config, &config);
+    }
+
+    pub fn check_deposit(&mut self, amount: u64) -> u16 {
+        self.rt
+            .send(
+                accounts::CheckDeposit {
+                    pool: self.pool,
+                    config: self.config,
+                    lp_token_mint: self.lp_mint,
+                },
+                instruction::CheckDeposit { amount },
+            )
+            .unwrap();
+        self.rt.returned()
+    }
+
+    pub fn quote_deposit(&mut self, amount: u64) -> TxResult<u64> {
+        self.rt
+            .send(self.quote_accounts(), instruction::QuoteDeposit { amount })?;
+        Ok(self.rt.returned())
+    }
+
+    pub fn quote_withdraw(&mut self, lp_amount: u64) -> TxResult<u64> {
+        self.rt.send(
+            self.quote_accounts(),
+            instruction::QuoteWithdraw { lp_amount },
+        )?;
+        Ok(self.rt.returned())
+    }
+
+    pub fn lock_status(&mut self, nonce: u64) -> TxResult<LockStatus> {
+        self.rt.send(
+            accounts::GetLockStatus {
+                bridge_lock: self.lock_pda(nonce),
+            },
+            instruction::GetLockStatus {},
+        )?;
+        Ok(self.rt.returned())
+    }
+
+    pub fn pool_view(&mut self) -> TxResult<PoolView> {
+        self.rt.send(
+            accounts::GetPoolState {
+                pool: self.pool,
+                config: self.config,
+            },
+            instruction::GetPoolState {},
+        )?;
+        Ok(self.rt.returned())
+    }
+
+    pub fn share_price(&mut self) -> TxResult<u128> {
+        self.rt.send(
+            accounts::GetPoolState {
+                pool: self.pool,
+                config: self.config,
+            },
+            instruction::GetSharePrice {},
+        )?;
+        Ok(self.rt.returned())
+    }
+
+    fn quote_accounts(&self) -> accounts::QuoteLiquidity {
+        accounts::QuoteLiquidity {
+            pool: self.pool,
+            config: self.config,
+            lp_token_mint: self.lp_mint,
+        }
+    }
+
+    /// Pre-flight a lock from a sender who has never locked.
+    pub fn check_lock(&mut self, amount: u64) -> u16 {
+        self.check_lock_for(&Pubkey::new_unique(), amount)
+    }
+
+    pub fn check_lock_for(&mut self, sender: &Pubkey, amount: u64) -> u16 {
+        self.check_lock_to(sender, amount, CHAIN, RECIPIENT)
+    }
+
+    pub fn check_lock_to(
+        &mut self,
+        sender: &Pubkey,
+        amount: u64,
+        destination_chain_id: u64,
+        recipient_address: [u8; 32],
+    ) -> u16 {
+        self.check_lock_with(
+            sender,
+            LockParams {
+                amount,
+                destination_chain_id,
+                recipient_address,
+                client_tag: [0; 16],
+                recipient_checksum: None,
+            },
+        )
+    }
+
+    pub fn check_lock_with(&mut self, sender: &Pubkey, params: LockParams) -> u16 {
+        let user_lock_state = self.user_lock_pda(sender);
+        let chain_info = self.chain_pda(params.destination_chain_id);
+        self.rt
+            .send(
+                accounts::CheckLock {
+                    pool: self.pool,
+                    config: self.config,
+                    sender: *sender,
+                    user_lock_state: self.rt.exists(&user_lock_state).then_some(user_lock_state),
+                    chain_info: self.rt.exists(&chain_info).then_some(chain_info),
+                },
+                instruction::CheckLock {
+                    amount: params.amount,
+                    destination_chain_id: params.destination_chain_id,
+                    recipient_address: params.recipient_address,
+                    client_tag: params.client_tag,
+                    recipient_checksum: params.recipient_checksum,
+                },
+            )
+            .unwrap();
+        self.rt.returned()
+    }
+
+    pub fn release(&mut self, nonce: u64) -> TxResult {
+        let accounts = self.release_accounts(nonce);
+        self.rt
+            .send(accounts, instruction::ReleaseLockedLiquidity {})
+    }
+
+    pub fn release_partial(&mut self, nonce: u64, amount: u64) -> TxResult {
+        let accounts = self.release_accounts(nonce);
+        self.rt
+            .send(accounts, instruction::ReleasePartial { amount })
+    }
+
+    pub fn force_release(&mut self, nonce: u64) -> TxResult {
+        self.rt.send(
+            accounts::ForceRelease {
+                pool: self.pool,
+                config: self.config,
+                admin: self.admin,
+                bridge_lock: self.lock_pda(nonce),
+                vault: self.vault,
+                sender_stablecoin_ata: self.sender_ata(nonce),
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            },
+            instruction::ForceRelease {},
+        )
+    }
+
+    pub fn set_protocol_fee_share(&mut self, protocol_fee_share_bps: u16) -> TxResult {
+        self.rt.send(
+            accounts::SetProtocolFeeShare {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetProtocolFeeShare {
+                protocol_fee_share_bps,
+            },
+        )
+    }
+
+    pub fn set_require_unfreezable_mint(&mut self, required: bool) -> TxResult {
+        self.rt.send(
+            accounts::SetRequireUnfreezableMint {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetRequireUnfreezableMint { required },
+        )
+    }
+
+    pub fn set_stuck_lock_timeout(&mut self, seconds: u32) -> TxResult {
+        self.rt.send(
+            accounts::SetStuckLockTimeout {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetStuckLockTimeout { seconds },
+        )
+    }
+
+    pub fn set_min_lock_age(&mut self, seconds: u32) -> TxResult {
+        self.rt.send(
+            accounts::SetMinLockAge {
+                config: self.config,
+                admin: self.admin,
+            },
+            instruction::SetMinLockAge { seconds },
+        )
+    }
+
+    /// Release with an attestation: `ed25519` (see `attest`) runs just before the release.
+    pub fn release_with_attestation(
+        &mut self,
+        nonce: u64,
+        ed25519: Instruction,
+        signature: [u8; 64],
+    ) -> TxResult {
+        let instruction = Instruction {
+            program_id: crate::ID,
+            accounts: accounts::ReleaseWithAttestation {
+                pool: self.pool,
+                config: self.config,
+                bridge_lock: self.lock_pda(nonce),
+                vault: self.vault,
+                sender_stablecoin_ata: self.sender_ata(nonce),
+                instructions: sysvar::instructions::ID,
+                stablecoin_mint: self.mint,
+                token_program: self.token_program,
+            }
+            .to_account_metas(None),
+            data: instruction::ReleaseWithAttestation { signature }.data(),
+        };
+        self.rt.process_after(&[ed25519], instruction)
+    }
+
+    /// `signer`'s attestation that lock `nonce` may release `amount`: the Ed25519 program
+    /// instruction verifying it, and the signature.
+    pub fn attest(&self, signer: &Keypair, nonce: u64, amount: u64) -> (Instruction, [u8; 64]) {
+        ed25519_verify(
+            signer,
+            &crate::attestation_message(&self.pool, nonce, amount),
+        )
+    }
+
+    pub fn approve_release(
+        &mut self,
+        relayer: Pubkey,
+        nonce: u64,
+        action: ApprovalAction,
+        amount: u64,
+    ) -> TxResult {
+        self.rt.send(
+            accounts::ApproveRelease {
+                pool: self.pool,
+                config: self.config,
+                relayer,
+                bridge_lock: self.lock_pda(nonce),
+            },
+            instruction::ApproveRelease { action, amount },
+        )
+    }
+
+    fn release_accounts(&self, nonce: u64) -> accounts::ReleaseLockedLiquidity {
+        accounts::ReleaseLockedLiquidity {
+            pool: self.pool,
+            config: self.config,
+            relayer: self.relayer,
+            bridge_lock: self.lock_pda(nonce),
+            vault: self.vault,
+            sender_stablecoin_ata: self.sender_ata(nonce),
+            stablecoin_mint: self.mint,
+            token_program: self.token_program,
+        }
+    }
+
+    /// Stablecoin account of the lock's sender, where reverts refund to.
+    pub fn sender_ata(&self, nonce: u64) -> Pubkey {
+        let sender = self.lock_state(nonce).sender;
+        self.rt.token_account_of(&sender, &self.mint)
+    }
+}
@@ -0,0 +1,815 @@
+//! In-process runtime for handler tests.
+//!
+//! Instructions are serialized into the same input buffer the BPF loader
+//! builds and dispatched through `crate::entry`. CPIs land in the stubbed
+//! `sol_invoke_signed`, which runs the real SPL token processors, a minimal
+//! system program, and the two Token Metadata instructions the program uses. Account writes are committed only when the
+//! instruction succeeds. Instructions sent ahead of it in the same transaction
+//! are exposed through the instructions sysvar; Ed25519 program instructions
+//! among them are verified the way the runtime's precompile does.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Once;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::{
+    self, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER,
+};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use anchor_lang::solana_program::program_utils::limited_deserialize;
+use anchor_lang::solana_program::system_instruction::SystemInstruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction,
+};
+use anchor_lang::solana_program::{ed25519_program, system_program, sysvar};
+use anchor_lang::{Discriminator, InstructionData};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::metadata::mpl_token_metadata::accounts::Metadata;
+use anchor_spl::metadata::mpl_token_metadata::instructions::{
+    CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2InstructionArgs,
+};
+use anchor_spl::metadata::mpl_token_metadata::types::{Key, TokenStandard};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    TransferFee, TransferFeeAmount, TransferFeeConfig,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::{
+    TransferHook, TransferHookAccount,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    AccountType, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    StateWithExtensionsMut,
+};
+use borsh09::{BorshDeserialize as _, BorshSerialize as _};
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+
+pub type TxResult<T = ()> = std::result::Result<T, ProgramError>;
+
+pub const START_TIME: i64 = 1_700_000_000;
+pub const LAMPORTS: u64 = 100_000_000_000;
+
+thread_local! {
+    static NOW: Cell<i64> = const { Cell::new(START_TIME) };
+    static LOGS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let now = NOW.with(Cell::get);
+        let clock = Clock {
+            slot: (now - START_TIME) as u64,
+            unix_timestamp: now,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        entrypoint::SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        entrypoint::SUCCESS
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|r| *r.borrow_mut() = data.to_vec());
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let data = RETURN_DATA.with(|r| r.borrow().clone());
+        (!data.is_empty()).then_some((crate::ID, data))
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        LOGS.with(|l| l.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let signers: Vec<Pubkey> = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &crate::ID))
+            .collect::<std::result::Result<_, _>>()?;
+        let mut infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let mut info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            if meta.is_signer {
+                if !info.is_signer && !signers.contains(info.key) {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                info.is_signer = true;
+            }
+            infos.push(info);
+        }
+        let program_id = instruction.program_id;
+        if program_id == spl_token::ID {
+            spl_token::processor::Processor::process(&program_id, &infos, &instruction.data)
+        } else if program_id == spl_token_2022::ID {
+            spl_token_2022::processor::Processor::process(&program_id, &infos, &instruction.data)
+        } else if program_id == system_program::ID {
+            process_system(&infos, &instruction.data)
+        } else if program_id == mpl_token_metadata::ID {
+            process_token_metadata(&infos, &instruction.data)
+        } else {
+            Err(ProgramError::IncorrectProgramId)
+        }
+    }
+}
+
+fn process_system(infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let ix: SystemInstruction =
+        limited_deserialize(data, 1024).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let debit = |from: &AccountInfo, to: &AccountInfo, lamports: u64| -> ProgramResult {
+        let mut from_lamports = from.try_borrow_mut_lamports()?;
+        **from_lamports = from_lamports
+            .checked_sub(lamports)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **to.try_borrow_mut_lamports()? += lamports;
+        Ok(())
+    };
+    match ix {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            if infos[1].lamports() > 0 || *infos[1].owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            debit(&infos[0], &infos[1], lamports)?;
+            infos[1].realloc(space as usize, true)?;
+            infos[1].assign(&owner);
+            Ok(())
+        }
+        SystemInstruction::Transfer { lamports } => debit(&infos[0], &infos[1], lamports),
+        // As in the runtime, only system-owned accounts can be allocated (if still empty) or
+        // assigned.
+        SystemInstruction::Allocate { space } => {
+            if *infos[0].owner != system_program::ID || !infos[0].data_is_empty() {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            infos[0].realloc(space as usize, true)
+        }
+        SystemInstruction::Assign { owner } => {
+            if *infos[0].owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            infos[0].assign(&owner);
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Size the Token Metadata program allocates for every metadata account.
+const METADATA_LEN: usize = 679;
+
+/// CreateMetadataAccountV3 and UpdateMetadataAccountV2, with the authority checks the real
+/// program makes. Every other Token Metadata instruction is rejected.
+fn process_token_metadata(infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let invalid = ProgramError::InvalidInstructionData;
+    let (discriminator, mut args) = data.split_first().ok_or(invalid.clone())?;
+    let metadata_info = &infos[0];
+    let write = |metadata: &Metadata| -> ProgramResult {
+        let mut bytes = metadata.try_to_vec()?;
+        bytes.resize(METADATA_LEN, 0);
+        metadata_info.try_borrow_mut_data()?.copy_from_slice(&bytes);
+        Ok(())
+    };
+    match discriminator {
+        33 => {
+            let args = CreateMetadataAccountV3InstructionArgs::deserialize(&mut args)?;
+            let (mint_info, mint_authority, payer, update_authority) =
+                (&infos[1], &infos[2], &infos[3], &infos[4]);
+            if *metadata_info.key != Metadata::find_pda(mint_info.key).0 {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if *metadata_info.owner != system_program::ID || !metadata_info.data_is_empty() {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            let mint = spl_token::state::Mint::unpack_from_slice(&mint_info.data.borrow())?;
+            if !mint_authority.is_signer
+                || mint.mint_authority != COption::Some(*mint_authority.key)
+            {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let lamports = Rent::default().minimum_balance(METADATA_LEN);
+            **payer.try_borrow_mut_lamports()? = payer
+                .lamports()
+                .checked_sub(lamports)
+                .ok_or(ProgramError::InsufficientFunds)?;
+            **metadata_info.try_borrow_mut_lamports()? += lamports;
+            metadata_info.realloc(METADATA_LEN, true)?;
+            metadata_info.assign(&mpl_token_metadata::ID);
+            write(&Metadata {
+                key: Key::MetadataV1,
+                update_authority: *update_authority.key,
+                mint: *mint_info.key,
+                name: args.data.name,
+                symbol: args.data.symbol,
+                uri: args.data.uri,
+                seller_fee_basis_points: args.data.seller_fee_basis_points,
+                creators: args.data.creators,
+                primary_sale_happened: false,
+                is_mutable: args.is_mutable,
+                edition_nonce: None,
+                token_standard: Some(if mint.decimals > 0 {
+                    TokenStandard::Fungible
+                } else {
+                    TokenStandard::FungibleAsset
+                }),
+                collection: args.data.collection,
+                uses: args.data.uses,
+                collection_details: args.collection_details,
+                programmable_config: None,
+            })
+        }
+        15 => {
+            let args = UpdateMetadataAccountV2InstructionArgs::deserialize(&mut args)?;
+            let update_authority = &infos[1];
+            if *metadata_info.owner != mpl_token_metadata::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let mut metadata = Metadata::from_bytes(&metadata_info.data.borrow())?;
+            if !update_authority.is_signer || metadata.update_authority != *update_authority.key {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !metadata.is_mutable {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if let Some(data) = args.data {
+                metadata.name = data.name;
+                metadata.symbol = data.symbol;
+                metadata.uri = data.uri;
+                metadata.seller_fee_basis_points = data.seller_fee_basis_points;
+                metadata.creators = data.creators;
+                metadata.collection = data.collection;
+                metadata.uses = data.uses;
+            }
+            if let Some(new_update_authority) = args.new_update_authority {
+                metadata.update_authority = new_update_authority;
+            }
+            if let Some(is_mutable) = args.is_mutable {
+                metadata.is_mutable = is_mutable;
+            }
+            write(&metadata)
+        }
+        _ => Err(invalid),
+    }
+}
+
+/// What the Ed25519 precompile checks: every signature in the instruction verifies over its
+/// message, with all offsets pointing into the instruction's own data.
+fn verify_ed25519(data: &[u8]) -> ProgramResult {
+    let invalid = ProgramError::InvalidInstructionData;
+    let read_u16 = |at: usize| -> std::result::Result<usize, ProgramError> {
+        let bytes = data.get(at..at + 2).ok_or(invalid.clone())?;
+        Ok(usize::from(u16::from_le_bytes([bytes[0], bytes[1]])))
+    };
+    let slice = |offset: usize, len: usize| data.get(offset..offset + len).ok_or(invalid.clone());
+    let count = usize::from(*data.first().ok_or(invalid.clone())?);
+    for record in 0..count {
+        let at = 2 + record * 14;
+        if [at + 2, at + 6, at + 12]
+            .into_iter()
+            .any(|index| read_u16(index) != Ok(usize::from(u16::MAX)))
+        {
+            return Err(invalid);
+        }
+        let signature =
+            Signature::from_bytes(slice(read_u16(at)?, 64)?).map_err(|_| invalid.clone())?;
+        let public_key =
+            PublicKey::from_bytes(slice(read_u16(at + 4)?, 32)?).map_err(|_| invalid.clone())?;
+        let message = slice(read_u16(at + 8)?, read_u16(at + 10)?)?;
+        public_key
+            .verify(message, &signature)
+            .map_err(|_| invalid.clone())?;
+    }
+    Ok(())
+}
+
+pub fn set_time(unix_timestamp: i64) {
+    NOW.with(|n| n.set(unix_timestamp));
+}
+
+pub fn warp(seconds: i64) {
+    NOW.with(|n| n.set(n.get() + seconds));
+}
+
+pub fn now() -> i64 {
+    NOW.with(Cell::get)
+}
+
+/// Error an instruction is expected to fail with.
+/// Token account data, with or without Token-2022 extensions (which tag byte 165).
+fn is_token_account(data: &[u8]) -> bool {
+    data.len() == spl_token::state::Account::LEN
+        || data.get(spl_token::state::Account::LEN) == Some(&(AccountType::Account as u8))
+}
+
+pub fn bridge_err(error: crate::errors::BridgeError) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+/// Code a pre-flight check reports for `error`.
+pub fn code(error: crate::errors::BridgeError) -> u16 {
+    u32::from(error) as u16
+}
+
+/// Error for a failed Anchor account constraint.
+pub fn anchor_err(error: anchor_lang::error::ErrorCode) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Stored {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+#[derive(Default)]
+pub struct Runtime {
+    pub accounts: HashMap<Pubkey, Stored>,
+    pub logs: Vec<Vec<u8>>,
+    pub return_data: Vec<u8>,
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(Stubs));
+        });
+        set_time(START_TIME);
+        let mut runtime = Self::default();
+        for program in [
+            crate::ID,
+            spl_token::ID,
+            spl_token_2022::ID,
+            system_program::ID,
+            mpl_token_metadata::ID,
+        ] {
+            runtime.put(
+                program,
+                Stored {
+                    lamports: 1,
+                    executable: true,
+                    ..Stored::default()
+                },
+            );
+        }
+        let rent = Rent::default();
+        runtime.put(
+            sysvar::rent::ID,
+            Stored {
+                lamports: 1,
+                data: [
+                    &rent.lamports_per_byte_year.to_le_bytes()[..],
+                    &rent.exemption_threshold.to_le_bytes(),
+                    &[rent.burn_percent],
+                ]
+                .concat(),
+                owner: sysvar::ID,
+                executable: false,
+            },
+        );
+        runtime
+    }
+
+    pub fn put(&mut self, key: Pubkey, account: Stored) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Stored {
+        self.accounts.get(key).cloned().unwrap_or_default()
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts.get(key).is_some_and(|a| a.lamports > 0)
+    }
+
+    /// Fund a fresh wallet.
+    pub fn wallet(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.put(
+            key,
+            Stored {
+                lamports: LAMPORTS,
+                owner: system_program::ID,
+                ..Stored::default()
+            },
+        );
+        key
+    }
+
+    pub fn create_mint(
+        &mut self,
+        token_program: Pubkey,
+        authority: Pubkey,
+        decimals: u8,
+    ) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::Some(authority),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        self.put(
+            key,
+            Stored {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: token_program,
+                executable: false,
+            },
+        );
+        key
+    }
+
+    /// The classic SPL Token wrapped-SOL mint, at its fixed address.
+    pub fn create_native_mint(&mut self) -> Pubkey {
+        let key = spl_token::native_mint::ID;
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: spl_token::native_mint::DECIMALS,
+            is_initialized: true,
+            ..spl_token::state::Mint::default()
+        }
+        .pack_into_slice(&mut data);
+        self.put(
+            key,
+            Stored {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: spl_token::ID,
+                executable: false,
+            },
+        );
+        key
+    }
+
+    pub fn create_token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.put_token_account(key, mint, owner, amount);
+        key
+    }
+
+    /// A Token-2022 mint withholding `fee_bps` of every transfer.
+    pub fn create_transfer_fee_mint(
+        &mut self,
+        authority: Pubkey,
+        decimals: u8,
+        fee_bps: u16,
+    ) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        let mut data = vec![0; len];
+        let mut mint =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: fee_bps.into(),
+        };
+        let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = fee;
+        config.newer_transfer_fee = fee;
+        mint.base = spl_token_2022::state::Mint {
+            mint_authority: COption::Some(authority),
+            decimals,
+            is_initialized: true,
+            ..spl_token_2022::state::Mint::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        self.put(
+            key,
+            Stored {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: spl_token_2022::ID,
+                executable: false,
+            },
+        );
+        key
+    }
+
+    /// A Token-2022 mint carrying the TransferHook extension, with no hook program set.
+    pub fn create_transfer_hook_mint(&mut self, authority: Pubkey, decimals: u8) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferHook,
+        ])
+        .unwrap();
+        let mut data = vec![0; len];
+        let mut mint =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        mint.init_extension::<TransferHook>(true).unwrap().authority =
+            Some(authority).try_into().unwrap();
+        mint.base = spl_token_2022::state::Mint {
+            mint_authority: COption::Some(authority),
+            decimals,
+            is_initialized: true,
+            ..spl_token_2022::state::Mint::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        self.put(
+            key,
+            Stored {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: spl_token_2022::ID,
+                executable: false,
+            },
+        );
+        key
+    }
+
+    pub fn put_token_account(&mut self, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let mint_account = self.get(&mint);
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+        // Wrapped-SOL accounts hold their token balance as lamports above the rent reserve.
+        let native = mint == spl_token::native_mint::ID;
+        let base = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: if native {
+                COption::Some(rent)
+            } else {
+                COption::None
+            },
+            ..spl_token::state::Account::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        if mint_account.data.len() > spl_token::state::Mint::LEN {
+            // Extension mints need the matching account extensions (e.g. TransferFeeAmount).
+            let mint_extensions =
+                StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data)
+                    .unwrap()
+                    .get_extension_types()
+                    .unwrap();
+            let extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+            let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+                &extensions,
+            )
+            .unwrap();
+            data = vec![0; len];
+            let mut account =
+                StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(
+                    &mut data,
+                )
+                .unwrap();
+            for extension in extensions {
+                match extension {
+                    ExtensionType::TransferFeeAmount => {
+                        account.init_extension::<TransferFeeAmount>(true).unwrap();
+                    }
+                    ExtensionType::TransferHookAccount => {
+                        account.init_extension::<TransferHookAccount>(true).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+            account.init_account_type().unwrap();
+        }
+        base.pack_into_slice(&mut data[..spl_token::state::Account::LEN]);
+        let lamports = Rent::default().minimum_balance(data.len());
+        self.put(
+            key,
+            Stored {
+                lamports: if native { lamports + amount } else { lamports },
+                data,
+                owner: mint_account.owner,
+                executable: false,
+            },
+        );
+        self.set_mint_supply(mint, self.mint_supply(&mint) + amount);
+    }
+
+    /// Credit tokens to an account outside the program (e.g. a direct transfer).
+    pub fn mint_to(&mut self, key: Pubkey, amount: u64) {
+        let mut account = self.token_account(&key);
+        account.amount += amount;
+        account.pack_into_slice(&mut self.accounts.get_mut(&key).unwrap().data);
+        self.set_mint_supply(account.mint, self.mint_supply(&account.mint) + amount);
+    }
+
+    pub fn token_account(&self, key: &Pubkey) -> spl_token::state::Account {
+        spl_token::state::Account::unpack_from_slice(&self.get(key).data).unwrap()
+    }
+
+    /// The token account of `mint` held by `owner` (test wallets hold one per mint).
+    pub fn token_account_of(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let program = self.get(mint).owner;
+        *self
+            .accounts
+            .iter()
+            .find(|(_, account)| {
+                account.owner == program
+                    && is_token_account(&account.data)
+                    && account.data[..32] == mint.to_bytes()
+                    && account.data[32..64] == owner.to_bytes()
+            })
+            .unwrap()
+            .0
+    }
+
+    pub fn balance(&self, key: &Pubkey) -> u64 {
+        self.token_account(key).amount
+    }
+
+    pub fn mint(&self, key: &Pubkey) -> spl_token::state::Mint {
+        spl_token::state::Mint::unpack_from_slice(&self.get(key).data).unwrap()
+    }
+
+    pub fn mint_supply(&self, key: &Pubkey) -> u64 {
+        self.mint(key).supply
+    }
+
+    pub fn set_mint_supply(&mut self, key: Pubkey, supply: u64) {
+        let mut mint = self.mint(&key);
+        mint.supply = supply;
+        let account = self.accounts.get_mut(&key).unwrap();
+        mint.pack_into_slice(&mut account.data[..spl_token::state::Mint::LEN]);
+    }
+
+    /// Deserialize a program-owned account.
+    pub fn state<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        T::try_deserialize(&mut self.get(key).data.as_slice()).unwrap()
+    }
+
+    /// Overwrite a program-owned account, keeping its discriminator.
+    pub fn set_state<T: AccountSerialize>(&mut self, key: &Pubkey, value: &T) {
+        let account = self.accounts.get_mut(key).unwrap();
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).unwrap();
+        account.data[..data.len()].copy_from_slice(&data);
+    }
+
+    /// Events emitted by the most recent instruction.
+    pub fn events<T: anchor_lang::Event + Discriminator>(&self) -> Vec<T> {
+        self.logs
+            .iter()
+            .filter(|log| log.starts_with(&T::DISCRIMINATOR))
+            .map(|log| T::deserialize(&mut &log[8..]).unwrap())
+            .collect()
+    }
+
+    /// event_seq of every event emitted by the most recent instruction, in emission order.
+    /// event_seq is the last field of every event.
+    pub fn event_seqs(&self) -> Vec<u64> {
+        self.logs
+            .iter()
+            .map(|log| u64::from_le_bytes(log[log.len() - 8..].try_into().unwrap()))
+            .collect()
+    }
+
+    /// Return data set by the most recent instruction.
+    pub fn returned<T: AnchorDeserialize>(&self) -> T {
+        T::deserialize(&mut self.return_data.as_slice()).unwrap()
+    }
+
+    pub fn send(&mut self, accounts: impl ToAccountMetas, data: impl InstructionData) -> TxResult {
+        self.process(Instruction {
+            program_id: crate::ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        })
+    }
+
+    /// Process `instruction` as the last instruction of a transaction that starts with
+    /// `preceding`. Only Ed25519 program instructions are supported ahead of it.
+    pub fn process_after(
+        &mut self,
+        preceding: &[Instruction],
+        instruction: Instruction,
+    ) -> TxResult {
+        for ix in preceding {
+            assert_eq!(ix.program_id, ed25519_program::ID);
+            verify_ed25519(&ix.data)?;
+        }
+        let all: Vec<&Instruction> = preceding.iter().chain([&instruction]).collect();
+        let borrowed: Vec<BorrowedInstruction> = all
+            .iter()
+            .map(|ix| BorrowedInstruction {
+                program_id: &ix.program_id,
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|meta| BorrowedAccountMeta {
+                        pubkey: &meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: &ix.data,
+            })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        store_current_index(&mut data, preceding.len() as u16);
+        self.put(
+            sysvar::instructions::ID,
+            Stored {
+                lamports: 1,
+                data,
+                owner: sysvar::ID,
+                executable: false,
+            },
+        );
+        self.process(instruction)
+    }
+
+    pub fn process(&mut self, instruction: Instruction) -> TxResult {
+        LOGS.with(|l| l.borrow_mut().clear());
+        RETURN_DATA.with(|r| r.borrow_mut().clear());
+
+        let mut input = Vec::<u8>::new();
+        input.extend_from_slice(&(instruction.accounts.len() as u64).to_le_bytes());
+        for (index, meta) in instruction.accounts.iter().enumerate() {
+            if let Some(first) = instruction.accounts[..index]
+                .iter()
+                .position(|m| m.pubkey == meta.pubkey)
+            {
+                input.push(first as u8);
+                input.extend_from_slice(&[0; 7]);
+                continue;
+            }
+            let account = self.get(&meta.pubkey);
+            input.push(NON_DUP_MARKER);
+            input.push(meta.is_signer as u8);
+            input.push(meta.is_writable as u8);
+            input.push(account.executable as u8);
+            input.extend_from_slice(&[0; 4]);
+            input.extend_from_slice(meta.pubkey.as_ref());
+            input.extend_from_slice(account.owner.as_ref());
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+            input.extend_from_slice(&0u64.to_le_bytes());
+        }
+        input.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+        input.extend_from_slice(&instruction.data);
+        input.extend_from_slice(instruction.program_id.as_ref());
+
+        // u64-backed so every field the loader reads is aligned.
+        let mut aligned = vec![0u64; input.len().div_ceil(size_of::<u64>())];
+        let buffer = aligned.as_mut_ptr() as *mut u8;
+        unsafe { std::ptr::copy_nonoverlapping(input.as_ptr(), buffer, input.len()) };
+        let (program_id, infos, data) = unsafe { entrypoint::deserialize(buffer) };
+
+        let result = crate::entry(program_id, &infos, data);
+        self.logs = LOGS.with(|l| l.borrow().clone());
+        self.return_data = RETURN_DATA.with(|r| r.borrow().clone());
+        if result.is_ok() {
+            for info in &infos {
+                self.put(
+                    *info.key,
+                    Stored {
+                        lamports: info.lamports(),
+                        data: info.data.borrow().to_vec(),
+                        owner: *info.owner,
+                        executable: info.executable,
+                    },
+                );
+            }
+            self.accounts.retain(|key, account| {
+                account.lamports > 0 || account.executable || *key == sysvar::rent::ID
+            });
+        }
+        result
+    }
+}
@@ -0,0 +1,101 @@
+use super::fixture::{Bridge, PoolParams};
+use super::harness::bridge_err;
+use crate::errors::BridgeError;
+use crate::events::{FeesCollected, InsuranceDeposited, InsurancePaidOut};
+
+#[test]
+fn funded_insurance_pays_out_to_lps() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(2_000);
+    bridge.deposit(&lp, 2_000).unwrap();
+    let insurance_vault = bridge.initialize_insurance_vault();
+
+    let funder = bridge.user(500);
+    bridge.fund_insurance(&funder, 500).unwrap();
+    let event = &bridge.rt.events::<InsuranceDeposited>()[0];
+    assert_eq!((event.amount, event.insurance_balance), (500, 500));
+    assert_eq!(bridge.rt.balance(&insurance_vault), 500);
+
+    bridge.claim_insurance(300).unwrap();
+    let event = &bridge.rt.events::<InsurancePaidOut>()[0];
+    assert_eq!((event.amount, event.insurance_balance), (300, 200));
+    assert_eq!(bridge.rt.balance(&insurance_vault), 200);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 2_300);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.insurance_balance, 200);
+    assert_eq!(pool.total_liquidity, 2_300);
+    assert_eq!(pool.available_liquidity, 2_300);
+
+    assert_eq!(
+        bridge.claim_insurance(201),
+        Err(bridge_err(BridgeError::InsufficientInsurance))
+    );
+}
+
+#[test]
+fn insurance_payout_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.initialize_insurance_vault();
+    let funder = bridge.user(500);
+    bridge.fund_insurance(&funder, 500).unwrap();
+
+    bridge.admin = funder.key;
+    assert_eq!(
+        bridge.claim_insurance(100),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn collected_fees_accrue_insurance_that_can_be_drawn() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    });
+    let insurance_vault = bridge.initialize_insurance_vault();
+    bridge.set_insurance_fee(2_500).unwrap();
+    let lp = bridge.user(20_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    bridge.deposit(&lp, 10_000).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 200);
+    let treasury = bridge.rt.create_token_account(bridge.mint, bridge.admin, 0);
+
+    bridge.collect_fees(treasury, 200).unwrap();
+    let event = &bridge.rt.events::<FeesCollected>()[0];
+    assert_eq!((event.amount, event.insurance_amount), (150, 50));
+    assert_eq!(event.total_fees_collected, 200);
+    let event = &bridge.rt.events::<InsuranceDeposited>()[0];
+    assert_eq!((event.amount, event.insurance_balance), (50, 50));
+    assert_eq!(bridge.rt.balance(&treasury), 150);
+    assert_eq!(bridge.rt.balance(&insurance_vault), 50);
+    assert_eq!(bridge.pool_state().insurance_balance, 50);
+
+    bridge.claim_insurance(50).unwrap();
+    let pool = bridge.pool_state();
+    assert_eq!(pool.insurance_balance, 0);
+    assert_eq!(pool.available_liquidity, 19_850);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 19_850);
+}
+
+#[test]
+fn insurance_fee_needs_a_vault_and_admin() {
+    let mut bridge = Bridge::new();
+    assert_eq!(
+        bridge.set_insurance_fee(100),
+        Err(bridge_err(BridgeError::InvalidInsuranceVault))
+    );
+    // Zero routes nothing, so it never needs a vault.
+    bridge.set_insurance_fee(0).unwrap();
+
+    bridge.initialize_insurance_vault();
+    assert_eq!(
+        bridge.set_insurance_fee(10_001),
+        Err(bridge_err(BridgeError::InvalidFeeRate))
+    );
+    let user = bridge.user(0);
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.set_insurance_fee(100),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
@@ -0,0 +1,118 @@
+use super::fixture::Bridge;
+use super::harness::{bridge_err, code};
+use crate::errors::BridgeError;
+use crate::state::Pool;
+use crate::{assert_pool_invariant, error_code_of};
+
+#[test]
+fn invariant_holds_for_consistent_books() {
+    let pool = Pool {
+        total_liquidity: 100,
+        available_liquidity: 70,
+        locked_liquidity: 30,
+        ..Pool::default()
+    };
+    assert_eq!(error_code_of(assert_pool_invariant(&pool)), 0);
+}
+
+#[test]
+fn invariant_catches_corrupted_pool() {
+    let corrupted = Pool {
+        total_liquidity: 101,
+        available_liquidity: 70,
+        locked_liquidity: 30,
+        ..Pool::default()
+    };
+    assert_eq!(
+        error_code_of(assert_pool_invariant(&corrupted)),
+        code(BridgeError::InvalidPoolState)
+    );
+
+    let overflowing = Pool {
+        total_liquidity: u64::MAX,
+        available_liquidity: u64::MAX,
+        locked_liquidity: 1,
+        ..Pool::default()
+    };
+    assert_eq!(
+        error_code_of(assert_pool_invariant(&overflowing)),
+        code(BridgeError::InvalidPoolState)
+    );
+}
+
+#[test]
+fn mutating_instructions_reject_corrupted_pool() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(5_000);
+    bridge.deposit(&user, 2_000).unwrap();
+
+    let mut pool = bridge.pool_state();
+    pool.total_liquidity += 1;
+    bridge.rt.set_state(&bridge.pool, &pool);
+
+    let corrupted = Err(bridge_err(BridgeError::InvalidPoolState));
+    assert_eq!(bridge.lock(&user, 100), corrupted);
+    assert_eq!(bridge.deposit(&user, 100), corrupted);
+}
+
+#[test]
+fn reentrancy_guard_rejects_nested_invocations() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(5_000);
+    bridge.deposit(&user, 2_000).unwrap();
+    assert!(!bridge.pool_state().in_progress);
+
+    // An outer instruction persists the flag before its CPIs; a nested call sees it.
+    let mut pool = bridge.pool_state();
+    pool.in_progress = true;
+    bridge.rt.set_state(&bridge.pool, &pool);
+
+    let nested = Err(bridge_err(BridgeError::ReentrancyDetected));
+    assert_eq!(bridge.deposit(&user, 100), nested);
+    assert_eq!(bridge.withdraw(&user, 100), nested);
+    assert_eq!(bridge.lock(&user, 100), nested);
+}
+
+#[test]
+fn interleaved_flows_keep_locks_backed() {
+    let mut bridge = Bridge::new();
+    let users = [bridge.user(50_000), bridge.user(50_000)];
+    bridge.deposit(&users[0], 10_000).unwrap();
+
+    // Deterministic xorshift so failures replay.
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move |bound: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % bound
+    };
+    for _ in 0..200 {
+        let user = &users[next(2) as usize];
+        let locks = bridge.pool_state().next_lock_nonce;
+        let nonce = next(locks.max(1));
+        // Individual steps may legitimately fail (e.g. settling a settled lock); only the
+        // books after each step matter.
+        let step = if locks == 0 { 2 } else { next(6) };
+        let _ = match step {
+            0 => bridge.deposit(user, 1 + next(5_000)),
+            1 => bridge.withdraw(user, 1 + next(bridge.rt.balance(&user.lp_ata).max(1))),
+            2 => bridge.lock(user, 1 + next(2_000)),
+            3 => bridge.release_partial(nonce, 1 + next(500)),
+            4 => bridge.release(nonce),
+            _ => bridge.complete(nonce),
+        };
+
+        let pool = bridge.pool_state();
+        assert_pool_invariant(&pool).unwrap();
+        assert_eq!(
+            pool.total_liquidity - pool.locked_liquidity,
+            pool.available_liquidity
+        );
+        assert!(bridge.rt.balance(&bridge.vault) >= pool.locked_liquidity);
+    }
+    // The walk actually exercised both settlement paths.
+    let pool = bridge.pool_state();
+    assert!(pool.next_lock_nonce > 10 && pool.lifetime_released_volume > 0);
+    assert!(bridge.rt.balance(&bridge.relayer_payout) > 0);
+}
@@ -0,0 +1,994 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::system_program;
+
+use super::fixture::{chain_name, Bridge, PoolParams, CHAIN, RECIPIENT};
+use super::harness::{anchor_err, bridge_err, code, now, warp, Stored};
+use crate::errors::BridgeError;
+use crate::events::{
+    BatchItemFailed, BridgeIntent, BridgeIntentCompact, BridgeReverted, MaxLockedLiquidityUpdated,
+    RateLimitsUpdated, RequireLiquidityBackingUpdated, SupportedChainAdded, SupportedChainRemoved,
+};
+use crate::state::{
+    to_base_units, AddressFormat, BridgeLock, ChainLockCounter, LockParams, LockReceipt, Pool,
+    ReleaseReason, UserLockIndex, UserLockState,
+};
+
+fn cooldown_pool() -> Bridge {
+    Bridge::with(PoolParams {
+        lock_cooldown_seconds: 60,
+        ..PoolParams::default()
+    })
+}
+
+#[test]
+fn normal_user_is_throttled_by_cooldown() {
+    let mut bridge = cooldown_pool();
+    let user = bridge.user(1_000);
+
+    bridge.lock(&user, 100).unwrap();
+    assert_eq!(
+        bridge.lock(&user, 100),
+        Err(bridge_err(BridgeError::LockCooldownActive))
+    );
+    assert_eq!(
+        bridge.check_lock_for(&user.key, 100),
+        code(BridgeError::LockCooldownActive)
+    );
+
+    warp(60);
+    assert_eq!(bridge.check_lock_for(&user.key, 100), 0);
+    bridge.lock(&user, 100).unwrap();
+}
+
+#[test]
+fn rate_limits_can_be_retuned() {
+    let mut bridge = cooldown_pool();
+    let user = bridge.user(10_000);
+
+    bridge.update_rate_limits(500, 0).unwrap();
+    let event = &bridge.rt.events::<RateLimitsUpdated>()[0];
+    assert_eq!((event.pool, event.admin), (bridge.pool, bridge.admin));
+    assert_eq!(
+        (event.old_max_lock_per_tx, event.new_max_lock_per_tx),
+        (1_000_000_000_000, 500)
+    );
+    assert_eq!(
+        (
+            event.old_lock_cooldown_seconds,
+            event.new_lock_cooldown_seconds
+        ),
+        (60, 0)
+    );
+
+    // The cooldown is gone and the lower cap applies from the next lock.
+    bridge.lock(&user, 500).unwrap();
+    bridge.lock(&user, 500).unwrap();
+    assert_eq!(
+        bridge.lock(&user, 501),
+        Err(bridge_err(BridgeError::LockAmountExceedsLimit))
+    );
+
+    // A new cooldown counts from the user's last lock.
+    bridge.update_rate_limits(1_000, 120).unwrap();
+    assert_eq!(
+        bridge.lock(&user, 100),
+        Err(bridge_err(BridgeError::LockCooldownActive))
+    );
+    warp(120);
+    bridge.lock(&user, 1_000).unwrap();
+    warp(119);
+    assert_eq!(
+        bridge.lock(&user, 100),
+        Err(bridge_err(BridgeError::LockCooldownActive))
+    );
+    warp(1);
+    bridge.lock(&user, 100).unwrap();
+}
+
+#[test]
+fn rate_limits_are_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.update_rate_limits(500, 0),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn locked_liquidity_is_capped() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.set_max_locked_liquidity(1_000).unwrap();
+    let event = &bridge.rt.events::<MaxLockedLiquidityUpdated>()[0];
+    assert_eq!((event.old_max, event.new_max), (0, 1_000));
+
+    bridge.lock(&user, 600).unwrap();
+    let capped = Err(bridge_err(BridgeError::LockedCapExceeded));
+    assert_eq!(bridge.lock(&user, 401), capped);
+    assert_eq!(
+        bridge.check_lock_for(&user.key, 401),
+        code(BridgeError::LockedCapExceeded)
+    );
+    assert_eq!(
+        bridge.lock_batch(&user, &[batch_item(200, 1), batch_item(201, 2)]),
+        capped
+    );
+    bridge.lock(&user, 400).unwrap();
+    assert_eq!(bridge.pool_state().locked_liquidity, 1_000);
+
+    // Settling a lock frees room under the cap; 0 removes it.
+    bridge.release(0).unwrap();
+    bridge.lock(&user, 600).unwrap();
+    bridge.set_max_locked_liquidity(0).unwrap();
+    bridge.lock(&user, 5_000).unwrap();
+}
+
+#[test]
+fn locked_liquidity_cap_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_max_locked_liquidity(1_000),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn backed_locks_need_available_liquidity() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 2_000).unwrap();
+    let user = bridge.user(10_000);
+    bridge.set_require_liquidity_backing(true).unwrap();
+    let event = &bridge.rt.events::<RequireLiquidityBackingUpdated>()[0];
+    assert_eq!((event.pool, event.admin), (bridge.pool, bridge.admin));
+    assert_eq!((event.old_required, event.new_required), (false, true));
+
+    // An under-funded pool rejects the lock outright.
+    let short = Err(bridge_err(BridgeError::InsufficientLiquidity));
+    assert_eq!(bridge.lock(&user, 2_001), short);
+    assert_eq!(
+        bridge.check_lock_for(&user.key, 2_001),
+        code(BridgeError::InsufficientLiquidity)
+    );
+    assert_eq!(
+        bridge.lock_batch(&user, &[batch_item(1_200, 1), batch_item(1_200, 2)]),
+        short
+    );
+
+    // A well-funded one moves the backing out of available into reserved; LP value is kept.
+    let share_price = bridge.pool_state().share_price();
+    bridge.lock(&user, 1_200).unwrap();
+    assert!(bridge.lock_state(0).backed);
+    let pool = bridge.pool_state();
+    assert_eq!(
+        (pool.available_liquidity, pool.reserved_liquidity),
+        (800, 1_200)
+    );
+    assert_eq!(pool.lp_value(), 2_000);
+    assert_eq!(pool.share_price(), share_price);
+    assert_eq!(bridge.lock(&user, 801), short);
+
+    // Settling frees the reservation; locks made before the mode was turned off stay backed.
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.pool_state().available_liquidity, 2_000);
+    bridge.lock(&user, 300).unwrap();
+    bridge.set_require_liquidity_backing(false).unwrap();
+    bridge.lock(&user, 5_000).unwrap();
+    assert!(!bridge.lock_state(2).backed);
+    bridge.release(1).unwrap();
+    let pool = bridge.pool_state();
+    assert_eq!(
+        (pool.available_liquidity, pool.reserved_liquidity),
+        (2_000, 0)
+    );
+}
+
+#[test]
+fn liquidity_backing_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_require_liquidity_backing(true),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn exempt_user_bypasses_cooldown_but_not_limits() {
+    let mut bridge = Bridge::with(PoolParams {
+        lock_cooldown_seconds: 60,
+        max_lock_per_tx: 500,
+        ..PoolParams::default()
+    });
+    let maker = bridge.user(10_000);
+    bridge.set_cooldown_exemption(&maker.key, true).unwrap();
+
+    bridge.lock(&maker, 100).unwrap();
+    bridge.lock(&maker, 100).unwrap();
+    assert_eq!(
+        bridge.lock(&maker, 501),
+        Err(bridge_err(BridgeError::LockAmountExceedsLimit))
+    );
+
+    bridge.set_cooldown_exemption(&maker.key, false).unwrap();
+    assert_eq!(
+        bridge.lock(&maker, 100),
+        Err(bridge_err(BridgeError::LockCooldownActive))
+    );
+}
+
+#[test]
+fn cooldown_exemption_is_admin_only() {
+    let mut bridge = cooldown_pool();
+    let user = bridge.user(1_000);
+    bridge.admin = user.key;
+
+    assert_eq!(
+        bridge.set_cooldown_exemption(&user.key, true),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn registered_chain_name_appears_in_bridge_intent() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.set_chain_name(CHAIN, "ethereum").unwrap();
+
+    bridge.lock(&user, 100).unwrap();
+    let intent = &bridge.rt.events::<BridgeIntent>()[0];
+    assert_eq!(intent.destination_chain_id, CHAIN);
+    assert_eq!(intent.destination_chain_name, Some(chain_name("ethereum")));
+}
+
+#[test]
+fn chain_names_are_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_chain_name(CHAIN, "ethereum"),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn long_pause_arms_sweep_of_stale_locks() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    let user = bridge.user(1_000);
+    bridge.set_auto_revert_after(3_600).unwrap();
+
+    bridge.lock(&user, 300).unwrap();
+    bridge.lock(&user, 200).unwrap();
+    bridge.pause_pool().unwrap();
+    warp(4_000);
+    bridge.resume_pool().unwrap();
+    // Locked after the cutoff, so not yet stale.
+    bridge.lock(&user, 100).unwrap();
+
+    let pool = bridge.pool_state();
+    assert!(pool.auto_revert_pending);
+    let available = pool.available_liquidity;
+
+    assert_eq!(
+        bridge.sweep_stale_locks(&[2]),
+        Err(bridge_err(BridgeError::LockNotStale))
+    );
+    bridge.sweep_stale_locks(&[0, 1]).unwrap();
+    let reverted: Vec<_> = bridge
+        .rt
+        .events::<BridgeReverted>()
+        .iter()
+        .map(|event| (event.nonce, event.amount, event.reason))
+        .collect();
+    let swept = ReleaseReason::StaleLockSweep;
+    assert_eq!(reverted, [(0, 300, swept), (1, 200, swept)]);
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.available_liquidity, available);
+    assert_eq!(pool.total_liquidity, available + 100);
+    assert_eq!(bridge.rt.balance(&user.ata), 900);
+    assert_eq!(pool.locked_liquidity, 100);
+    assert!(bridge.lock_state(0).released && bridge.lock_state(1).released);
+    assert_eq!(
+        bridge.sweep_stale_locks(&[0]),
+        Err(bridge_err(BridgeError::AlreadyReleased))
+    );
+}
+
+#[test]
+fn short_pause_does_not_arm_sweep() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    let user = bridge.user(1_000);
+    bridge.set_auto_revert_after(3_600).unwrap();
+
+    bridge.lock(&user, 300).unwrap();
+    bridge.pause_pool().unwrap();
+    warp(600);
+    bridge.resume_pool().unwrap();
+
+    assert!(!bridge.pool_state().auto_revert_pending);
+    assert_eq!(
+        bridge.sweep_stale_locks(&[0]),
+        Err(bridge_err(BridgeError::AutoRevertNotActive))
+    );
+}
+
+#[test]
+fn lock_returns_created_pda_and_nonce() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 100).unwrap();
+    bridge.lock(&user, 100).unwrap();
+
+    let receipt = bridge.rt.returned::<LockReceipt>();
+    assert_eq!(receipt.nonce, 1);
+    assert_eq!(receipt.bridge_lock, bridge.lock_pda(1));
+    assert_eq!(bridge.lock_state(1).nonce, receipt.nonce);
+    assert_eq!(bridge.rt.events::<BridgeIntent>()[0].nonce, receipt.nonce);
+}
+
+#[test]
+fn failed_sweep_item_reports_index_and_reverts_batch() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.set_auto_revert_after(3_600).unwrap();
+    bridge.lock(&user, 300).unwrap();
+    bridge.lock(&user, 200).unwrap();
+    bridge.pause_pool().unwrap();
+    warp(4_000);
+    bridge.resume_pool().unwrap();
+    // Nonce 2 is newer than the cutoff and fails wherever it appears.
+    bridge.lock(&user, 100).unwrap();
+
+    for (batch, failed_at) in [([2, 0, 1], 0), ([0, 2, 1], 1), ([0, 1, 2], 2)] {
+        assert_eq!(
+            bridge.sweep_stale_locks(&batch),
+            Err(bridge_err(BridgeError::LockNotStale))
+        );
+        let failed = &bridge.rt.events::<BatchItemFailed>()[0];
+        assert_eq!(failed.index, failed_at);
+        assert_eq!(failed.reason_code, code(BridgeError::LockNotStale));
+
+        assert!(!bridge.lock_state(0).released && !bridge.lock_state(1).released);
+        assert_eq!(bridge.pool_state().locked_liquidity, 600);
+    }
+
+    bridge.sweep_stale_locks(&[1, 0]).unwrap();
+    let order: Vec<_> = bridge
+        .rt
+        .events::<BridgeReverted>()
+        .iter()
+        .map(|event| event.nonce)
+        .collect();
+    assert_eq!(order, [1, 0]);
+}
+
+#[test]
+fn window_limit_rejects_locks_within_window() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.set_lock_window(1_000, 3_600).unwrap();
+
+    bridge.lock(&user, 600).unwrap();
+    warp(1_800);
+    bridge.lock(&user, 400).unwrap();
+    assert_eq!(
+        bridge.lock(&user, 1),
+        Err(bridge_err(BridgeError::WindowLimitExceeded))
+    );
+    assert_eq!(bridge.check_lock(1), code(BridgeError::WindowLimitExceeded));
+    assert_eq!(bridge.pool_state().window_volume, 1_000);
+}
+
+#[test]
+fn window_limit_rolls_over_after_window_elapses() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.set_lock_window(1_000, 3_600).unwrap();
+
+    bridge.lock(&user, 1_000).unwrap();
+    let first_window = bridge.pool_state().window_start;
+    warp(3_600);
+    bridge.lock(&user, 1_000).unwrap();
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.window_start, first_window + 3_600);
+    assert_eq!(pool.window_volume, 1_000);
+}
+
+#[test]
+fn min_lock_amount_is_enforced_at_the_boundary() {
+    let mut bridge = Bridge::with(PoolParams {
+        min_lock_amount: 100,
+        ..PoolParams::default()
+    });
+    let user = bridge.user(1_000);
+
+    assert_eq!(
+        bridge.lock(&user, 99),
+        Err(bridge_err(BridgeError::LockAmountBelowMinimum))
+    );
+    assert_eq!(
+        bridge.check_lock(99),
+        code(BridgeError::LockAmountBelowMinimum)
+    );
+    bridge.lock(&user, 100).unwrap();
+}
+
+#[test]
+fn admin_updates_min_lock_amount() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 1).unwrap();
+
+    bridge.set_min_lock_amount(50).unwrap();
+    assert_eq!(bridge.pool_state().min_lock_amount, 50);
+    assert_eq!(
+        bridge.lock(&user, 49),
+        Err(bridge_err(BridgeError::LockAmountBelowMinimum))
+    );
+
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.set_min_lock_amount(0),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn locks_require_a_supported_destination() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+
+    // lock_for_bridge requires the chain's ChainInfo; check_lock reports the missing chain.
+    assert_eq!(
+        bridge.lock_to(&user, 100, 42, RECIPIENT),
+        Err(anchor_err(ErrorCode::AccountNotInitialized))
+    );
+    assert_eq!(
+        bridge.check_lock_to(&user.key, 100, 42, RECIPIENT),
+        code(BridgeError::UnsupportedChain)
+    );
+
+    bridge.add_supported_chain(42).unwrap();
+    let added = &bridge.rt.events::<SupportedChainAdded>()[0];
+    assert_eq!((added.chain_id, added.admin), (42, bridge.admin));
+    bridge.lock_to(&user, 100, 42, RECIPIENT).unwrap();
+
+    bridge.remove_supported_chain(42).unwrap();
+    assert_eq!(bridge.rt.events::<SupportedChainRemoved>()[0].chain_id, 42);
+    assert_eq!(
+        bridge.lock_to(&user, 100, 42, RECIPIENT),
+        Err(bridge_err(BridgeError::UnsupportedChain))
+    );
+    // The pending lock toward the removed chain can still be reverted.
+    bridge.release(0).unwrap();
+}
+
+#[test]
+fn supported_chains_are_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.add_supported_chain(42),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+    assert_eq!(
+        bridge.remove_supported_chain(CHAIN),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn all_zero_recipient_is_rejected() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+
+    assert_eq!(
+        bridge.lock_to(&user, 100, CHAIN, [0; 32]),
+        Err(bridge_err(BridgeError::InvalidRecipient))
+    );
+    assert_eq!(
+        bridge.check_lock_to(&user.key, 100, CHAIN, [0; 32]),
+        code(BridgeError::InvalidRecipient)
+    );
+
+    // A 20-byte EVM address right-aligned in the 32-byte field.
+    let mut evm = [0u8; 32];
+    evm[12..].copy_from_slice(&[0xab; 20]);
+    bridge.lock_to(&user, 100, CHAIN, evm).unwrap();
+    assert_eq!(bridge.lock_state(0).recipient_address, evm);
+}
+
+#[test]
+fn evm_chains_require_right_aligned_20_byte_recipients() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    let mut evm = [0u8; 32];
+    evm[12..].copy_from_slice(&[0xab; 20]);
+    let mut malformed = evm;
+    malformed[11] = 1;
+
+    // Without a registered format any non-zero recipient is accepted.
+    assert_eq!(
+        bridge.chain_state(CHAIN).address_format,
+        AddressFormat::Unspecified
+    );
+    bridge.lock_to(&user, 100, CHAIN, malformed).unwrap();
+
+    bridge
+        .set_chain_address_format(CHAIN, AddressFormat::Evm20)
+        .unwrap();
+    assert_eq!(
+        bridge.lock_to(&user, 100, CHAIN, malformed),
+        Err(bridge_err(BridgeError::InvalidRecipient))
+    );
+    assert_eq!(
+        bridge.check_lock_to(&user.key, 100, CHAIN, malformed),
+        code(BridgeError::InvalidRecipient)
+    );
+    assert_eq!(
+        bridge.lock_batch(&user, &[batch_item(100, 7)]),
+        Err(bridge_err(BridgeError::InvalidRecipient))
+    );
+    bridge.lock_to(&user, 100, CHAIN, evm).unwrap();
+
+    bridge
+        .set_chain_address_format(CHAIN, AddressFormat::Solana32)
+        .unwrap();
+    bridge.lock_to(&user, 100, CHAIN, RECIPIENT).unwrap();
+    assert!(bridge.chain_state(CHAIN).supported);
+}
+
+#[test]
+fn address_format_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_chain_address_format(CHAIN, AddressFormat::Evm20),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+fn checksummed(recipient_address: [u8; 32], recipient_checksum: Option<u8>) -> LockParams {
+    LockParams {
+        amount: 100,
+        destination_chain_id: CHAIN,
+        recipient_address,
+        client_tag: [0; 16],
+        recipient_checksum,
+    }
+}
+
+#[test]
+fn recipient_checksum_must_match_when_given() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge
+        .set_chain_address_format(CHAIN, AddressFormat::Evm20)
+        .unwrap();
+    let mut evm = [0u8; 32];
+    evm[12..].copy_from_slice(&[0xab; 20]);
+    let checksum = AddressFormat::Evm20.checksum(&evm);
+    let wrong = Some(checksum.wrapping_add(1));
+
+    assert_eq!(
+        bridge.lock_with(&user, checksummed(evm, wrong)),
+        Err(bridge_err(BridgeError::InvalidRecipient))
+    );
+    assert_eq!(
+        bridge.check_lock_with(&user.key, checksummed(evm, wrong)),
+        code(BridgeError::InvalidRecipient)
+    );
+    assert_eq!(
+        bridge.lock_batch(&user, &[checksummed(evm, wrong)]),
+        Err(bridge_err(BridgeError::InvalidRecipient))
+    );
+    bridge
+        .lock_with(&user, checksummed(evm, Some(checksum)))
+        .unwrap();
+    bridge.lock_with(&user, checksummed(evm, None)).unwrap();
+    assert_eq!(bridge.lock_state(0).recipient_address, evm);
+}
+
+#[test]
+fn recipient_checksum_covers_the_formats_address_bytes() {
+    let mut evm = [0u8; 32];
+    evm[12..].copy_from_slice(&[0xab; 20]);
+    let mut padded = evm;
+    padded[0] = 1;
+
+    // Evm20 hashes only the low 20 bytes; other formats hash all 32.
+    assert_eq!(
+        AddressFormat::Evm20.checksum(&evm),
+        AddressFormat::Evm20.checksum(&padded)
+    );
+    assert_ne!(
+        AddressFormat::Unspecified.checksum(&evm),
+        AddressFormat::Unspecified.checksum(&padded)
+    );
+    assert_eq!(
+        AddressFormat::Unspecified.checksum(&RECIPIENT),
+        AddressFormat::Solana32.checksum(&RECIPIENT)
+    );
+}
+
+#[test]
+fn lock_records_its_slot() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    warp(25);
+    bridge.lock(&user, 100).unwrap();
+
+    // The test clock advances one slot per second from the start time.
+    assert_eq!(bridge.rt.events::<BridgeIntent>()[0].locked_slot, 25);
+    assert_eq!(bridge.lock_state(0).locked_slot, 25);
+}
+
+#[test]
+fn lock_stores_and_emits_the_same_timestamp() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    warp(90);
+    bridge.lock(&user, 100).unwrap();
+
+    let event = &bridge.rt.events::<BridgeIntent>()[0];
+    let state: UserLockState = bridge.rt.state(&bridge.user_lock_pda(&user.key));
+    assert_eq!(event.timestamp, now());
+    assert_eq!(bridge.lock_state(0).locked_at, event.timestamp);
+    assert_eq!(state.last_lock_at, event.timestamp);
+}
+
+fn batch_item(amount: u64, seed: u8) -> LockParams {
+    LockParams {
+        amount,
+        destination_chain_id: CHAIN,
+        recipient_address: [seed; 32],
+        client_tag: [0; 16],
+        recipient_checksum: None,
+    }
+}
+
+#[test]
+fn batch_creates_consecutive_locks() {
+    let mut bridge = Bridge::new();
+    bridge.update_fee_rate(0, 0, 100).unwrap();
+    let user = bridge.user(10_000);
+    bridge.lock(&user, 1_000).unwrap();
+
+    let items = [
+        batch_item(1_000, 1),
+        batch_item(2_000, 2),
+        batch_item(3_000, 3),
+    ];
+    bridge.lock_batch(&user, &items).unwrap();
+
+    let receipts = bridge.rt.returned::<Vec<LockReceipt>>();
+    let intents = bridge.rt.events::<BridgeIntent>();
+    assert_eq!(intents.len(), 3);
+    for (index, item) in items.iter().enumerate() {
+        let nonce = index as u64 + 1;
+        assert_eq!(receipts[index].nonce, nonce);
+        assert_eq!(receipts[index].bridge_lock, bridge.lock_pda(nonce));
+        assert_eq!(intents[index].nonce, nonce);
+        assert_eq!(intents[index].gross_amount, item.amount);
+        let lock = bridge.lock_state(nonce);
+        assert_eq!(lock.amount, item.amount - item.amount / 100);
+        assert_eq!(lock.recipient_address, item.recipient_address);
+        assert_eq!(lock.sender, user.key);
+    }
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.next_lock_nonce, 4);
+    assert_eq!(pool.locked_liquidity, 990 + 5_940);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 10 + 60);
+    assert_eq!(bridge.rt.balance(&user.ata), 3_000);
+}
+
+#[test]
+fn batch_total_is_held_to_lock_limits() {
+    let mut bridge = Bridge::with(PoolParams {
+        max_lock_per_tx: 2_500,
+        ..PoolParams::default()
+    });
+    let user = bridge.user(10_000);
+    let items = [
+        batch_item(1_000, 1),
+        batch_item(1_000, 2),
+        batch_item(1_000, 3),
+    ];
+
+    // Every item is within max_lock_per_tx; their sum is not.
+    assert_eq!(
+        bridge.lock_batch(&user, &items),
+        Err(bridge_err(BridgeError::LockAmountExceedsLimit))
+    );
+
+    bridge.set_lock_window(2_500, 3_600).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    assert_eq!(
+        bridge.lock_batch(&user, &items[..2]),
+        Err(bridge_err(BridgeError::WindowLimitExceeded))
+    );
+    bridge.lock_batch(&user, &items[..1]).unwrap();
+    assert_eq!(bridge.pool_state().window_volume, 2_000);
+}
+
+#[test]
+fn batch_size_is_bounded() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+
+    assert_eq!(
+        bridge.lock_batch(&user, &[]),
+        Err(bridge_err(BridgeError::InvalidBatchSize))
+    );
+    let items = vec![batch_item(100, 1); BridgeLock::MAX_BATCH_LOCKS + 1];
+    assert_eq!(
+        bridge.lock_batch(&user, &items),
+        Err(bridge_err(BridgeError::InvalidBatchSize))
+    );
+    bridge
+        .lock_batch(&user, &items[..BridgeLock::MAX_BATCH_LOCKS])
+        .unwrap();
+}
+
+#[test]
+fn prefunded_batch_lock_address_is_still_created() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    // Anyone can send lamports to the next lock's predictable address.
+    let lock = bridge.lock_pda(0);
+    bridge.rt.put(
+        lock,
+        Stored {
+            lamports: 1,
+            owner: system_program::ID,
+            ..Stored::default()
+        },
+    );
+
+    bridge.lock_batch(&user, &[batch_item(500, 1)]).unwrap();
+    let stored = bridge.rt.get(&lock);
+    assert_eq!(stored.owner, crate::ID);
+    assert_eq!(stored.data.len(), 8 + BridgeLock::LEN);
+    assert_eq!(bridge.lock_state(0).amount, 500);
+}
+
+#[test]
+fn lock_nonces_are_never_reused() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 100).unwrap();
+    bridge.lock(&user, 100).unwrap();
+    assert_ne!(bridge.lock_pda(0), bridge.lock_pda(1));
+    assert_eq!(bridge.pool_state().next_lock_nonce, 2);
+
+    // A rewound nonce points init at an existing lock, which cannot be created again.
+    let mut pool = bridge.pool_state();
+    pool.next_lock_nonce = 1;
+    bridge.rt.set_state(&bridge.pool, &pool);
+    assert!(bridge.lock(&user, 100).is_err());
+    assert_eq!(bridge.lock_state(1).amount, 100);
+
+    // The nonce fails rather than wrapping or saturating onto a used address.
+    pool.next_lock_nonce = u64::MAX;
+    bridge.rt.set_state(&bridge.pool, &pool);
+    assert_eq!(
+        bridge.lock(&user, 100),
+        Err(bridge_err(BridgeError::MathOverflow))
+    );
+}
+
+#[test]
+fn compact_events_replace_the_full_intent_with_a_payload_hash() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+
+    bridge.lock(&user, 100).unwrap();
+    let intent = &bridge.rt.events::<BridgeIntent>()[0];
+    assert_eq!((intent.nonce, intent.amount), (0, 100));
+    assert_eq!(intent.recipient_address, RECIPIENT);
+    assert!(bridge.rt.events::<BridgeIntentCompact>().is_empty());
+
+    bridge.set_compact_events(true).unwrap();
+    bridge.lock(&user, 200).unwrap();
+    assert!(bridge.rt.events::<BridgeIntent>().is_empty());
+    let compact = &bridge.rt.events::<BridgeIntentCompact>()[0];
+    assert_eq!((compact.pool, compact.nonce), (bridge.pool, 1));
+    let lock = bridge.lock_state(1);
+    assert_eq!(compact.payload_hash, lock.intent_hash());
+    assert_ne!(compact.payload_hash, bridge.lock_state(0).intent_hash());
+
+    bridge.admin = user.key;
+    assert_eq!(
+        bridge.set_compact_events(false),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn user_lock_index_keeps_the_most_recent_nonces() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(100_000);
+    let other = bridge.user(1_000);
+    let index = bridge.user_lock_index_pda(&user.key);
+
+    for _ in 0..3 {
+        bridge.lock(&user, 10).unwrap();
+    }
+    bridge.lock(&other, 10).unwrap();
+    let state: UserLockIndex = bridge.rt.state(&index);
+    assert_eq!((state.pool, state.user), (bridge.pool, user.key));
+    assert_eq!(state.recent(), vec![0, 1, 2]);
+
+    bridge
+        .lock_batch(&user, &[batch_item(10, 1), batch_item(10, 2)])
+        .unwrap();
+    assert_eq!(
+        bridge.rt.state::<UserLockIndex>(&index).recent(),
+        vec![0, 1, 2, 4, 5]
+    );
+
+    // Past capacity the oldest nonces roll off.
+    for _ in 0..UserLockIndex::CAPACITY {
+        bridge.lock(&user, 10).unwrap();
+    }
+    let state: UserLockIndex = bridge.rt.state(&index);
+    assert_eq!(state.count as usize, UserLockIndex::CAPACITY);
+    let expected: Vec<u64> = (6..6 + UserLockIndex::CAPACITY as u64).collect();
+    assert_eq!(state.recent(), expected);
+    assert_eq!(
+        bridge
+            .rt
+            .state::<UserLockIndex>(&bridge.user_lock_index_pda(&other.key))
+            .recent(),
+        vec![3]
+    );
+}
+
+#[test]
+fn to_base_units_scales_by_mint_decimals() {
+    assert_eq!(to_base_units(5, 6), Some(5_000_000));
+    assert_eq!(to_base_units(5, 9), Some(5_000_000_000));
+    assert_eq!(to_base_units(0, 9), Some(0));
+    assert_eq!(to_base_units(u64::MAX / 1_000_000 + 1, 6), None);
+    assert_eq!(to_base_units(1, 20), None);
+
+    let pool = Pool {
+        stablecoin_decimals: 9,
+        ..Pool::default()
+    };
+    assert_eq!(pool.to_base_units(2), Some(2_000_000_000));
+}
+
+#[test]
+fn lock_limits_are_base_units() {
+    // The fixture's stablecoin has 6 decimals.
+    let pool = Bridge::new().pool_state();
+    assert_eq!(pool.stablecoin_decimals, 6);
+    let mut bridge = Bridge::with(PoolParams {
+        max_lock_per_tx: pool.to_base_units(5).unwrap(),
+        min_lock_amount: pool.to_base_units(1).unwrap(),
+        ..PoolParams::default()
+    });
+    let user = bridge.user(10_000_000);
+
+    assert_eq!(
+        bridge.lock(&user, 999_999),
+        Err(bridge_err(BridgeError::LockAmountBelowMinimum))
+    );
+    assert_eq!(
+        bridge.lock(&user, 5_000_001),
+        Err(bridge_err(BridgeError::LockAmountExceedsLimit))
+    );
+    bridge.lock(&user, 5_000_000).unwrap();
+}
+
+fn tagged(amount: u64, tag: u8) -> LockParams {
+    LockParams {
+        amount,
+        destination_chain_id: CHAIN,
+        recipient_address: RECIPIENT,
+        client_tag: [tag; 16],
+        recipient_checksum: None,
+    }
+}
+
+#[test]
+fn repeated_client_tag_is_rejected_within_the_window() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+
+    bridge.lock_with(&user, tagged(100, 7)).unwrap();
+    assert_eq!(bridge.lock_state(0).client_tag, [7; 16]);
+    assert_eq!(
+        bridge.lock_with(&user, tagged(100, 7)),
+        Err(bridge_err(BridgeError::DuplicateLock))
+    );
+    assert_eq!(
+        bridge.check_lock_with(&user.key, tagged(100, 7)),
+        code(BridgeError::DuplicateLock)
+    );
+    // Other tags, other senders, and the all-zero tag are unaffected.
+    bridge.lock_with(&user, tagged(100, 8)).unwrap();
+    bridge.lock_with(&user, tagged(100, 0)).unwrap();
+    bridge.lock_with(&user, tagged(100, 0)).unwrap();
+    let other = bridge.user(1_000);
+    bridge.lock_with(&other, tagged(100, 7)).unwrap();
+
+    warp(UserLockState::TAG_WINDOW_SECONDS);
+    bridge.lock_with(&user, tagged(100, 7)).unwrap();
+}
+
+#[test]
+fn repeated_client_tag_is_rejected_within_a_batch() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    let item = tagged(100, 3);
+    assert_eq!(
+        bridge.lock_batch(&user, &[item, item]),
+        Err(bridge_err(BridgeError::DuplicateLock))
+    );
+    bridge
+        .lock_batch(&user, &[tagged(100, 3), tagged(100, 4)])
+        .unwrap();
+    assert_eq!(
+        bridge.lock_with(&user, tagged(100, 4)),
+        Err(bridge_err(BridgeError::DuplicateLock))
+    );
+}
+
+#[test]
+fn each_destination_chain_numbers_its_own_locks() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.add_supported_chain(42).unwrap();
+
+    bridge.lock_to(&user, 100, CHAIN, RECIPIENT).unwrap();
+    bridge.lock_to(&user, 100, CHAIN, RECIPIENT).unwrap();
+    bridge.lock_to(&user, 100, 42, RECIPIENT).unwrap();
+    assert_eq!(bridge.rt.events::<BridgeIntent>()[0].chain_index, 0);
+    let to_42 = LockParams {
+        destination_chain_id: 42,
+        ..batch_item(100, 1)
+    };
+    bridge
+        .lock_batch(&user, &[to_42, batch_item(100, 2), to_42])
+        .unwrap();
+    let batch: Vec<_> = bridge
+        .rt
+        .events::<BridgeIntent>()
+        .iter()
+        .map(|event| (event.destination_chain_id, event.chain_index))
+        .collect();
+    assert_eq!(batch, [(42, 1), (CHAIN, 2), (42, 2)]);
+
+    let indexes: Vec<_> = (0..6)
+        .map(|nonce| bridge.lock_state(nonce).chain_index)
+        .collect();
+    assert_eq!(indexes, [0, 1, 0, 1, 2, 2]);
+    let counter: ChainLockCounter = bridge.rt.state(&bridge.chain_lock_counter_pda(42));
+    assert_eq!(
+        (counter.pool, counter.chain_id, counter.next_index),
+        (bridge.pool, 42, 3)
+    );
+}
+
+#[test]
+fn batch_creates_a_missing_chain_lock_counter() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    assert!(!bridge.rt.exists(&bridge.chain_lock_counter_pda(CHAIN)));
+    bridge
+        .lock_batch(&user, &[batch_item(100, 1), batch_item(100, 2)])
+        .unwrap();
+    bridge.lock(&user, 100).unwrap();
+    assert_eq!(bridge.lock_state(2).chain_index, 2);
+}
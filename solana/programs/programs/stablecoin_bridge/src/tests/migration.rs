@@ -0,0 +1,216 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+
+use super::fixture::{Bridge, PoolParams};
+use super::harness::{anchor_err, bridge_err};
+use crate::errors::BridgeError;
+use crate::events::AccountMigrated;
+use crate::state::{ApprovalAction, BridgeLock, Config, LockStatus, Pool};
+
+/// Data lengths of accounts created by the original (unversioned) program.
+const LEGACY_POOL_DATA: usize = 8 + 8 + 32 + 32 + 32 + 8 + 8 + 8 + 2 + 32 + 32 + 1 + 1 + 8 + 4 + 8;
+const LEGACY_LOCK_DATA: usize = 8 + 8 + 32 + 8 + 8 + 32 + 32 + 1 + 8;
+
+/// Cut an account back to the original layout: the fields it had, zero-padded to its old size.
+fn truncate_to_legacy(bridge: &mut Bridge, key: &Pubkey, fields: usize, data_len: usize) {
+    let data = &mut bridge.rt.accounts.get_mut(key).unwrap().data;
+    data.truncate(fields);
+    data.resize(data_len, 0);
+}
+
+#[test]
+fn legacy_pool_is_grown_and_restored() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 100,
+        ..PoolParams::default()
+    });
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    let before = bridge.pool_state();
+    // Everything up to next_lock_nonce, the original layout's last field.
+    let pool = bridge.pool;
+    truncate_to_legacy(&mut bridge, &pool, 216, LEGACY_POOL_DATA);
+
+    assert_eq!(
+        bridge.deposit(&user, 1_000),
+        Err(anchor_err(ErrorCode::AccountDidNotDeserialize))
+    );
+
+    bridge.migrate_pool().unwrap();
+    let event = &bridge.rt.events::<AccountMigrated>()[0];
+    assert_eq!(
+        (event.account, event.from_version, event.to_version),
+        (bridge.pool, 0, Pool::CURRENT_VERSION)
+    );
+    assert_eq!(bridge.rt.get(&bridge.pool).data.len(), 8 + Pool::LEN);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.version, Pool::CURRENT_VERSION);
+    assert_eq!(pool.fee_vault, bridge.fee_vault);
+    assert_eq!(pool.lp_supply, before.lp_supply);
+    assert_eq!(
+        (
+            pool.deposit_fee_bps,
+            pool.withdraw_fee_bps,
+            pool.bridge_fee_bps
+        ),
+        (100, 100, 100)
+    );
+    assert_eq!((pool.stablecoin_decimals, pool.lp_decimals), (6, 6));
+    assert_eq!(pool.available_liquidity, before.available_liquidity);
+
+    bridge.deposit(&user, 1_000).unwrap();
+    assert_eq!(
+        bridge.migrate_pool(),
+        Err(bridge_err(BridgeError::AlreadyMigrated))
+    );
+}
+
+#[test]
+fn legacy_config_is_rebuilt_with_its_admin_and_relayer() {
+    let mut bridge = Bridge::new();
+    let bump = bridge.config_state().bump;
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bridge.admin.as_ref());
+    data.extend_from_slice(bridge.relayer.as_ref());
+    data.extend_from_slice(&[1, bump]);
+    data.resize(8 + Config::LEGACY_LEN, 0);
+    bridge.rt.accounts.get_mut(&bridge.config).unwrap().data = data;
+
+    assert_eq!(
+        bridge.set_min_lock_age(60),
+        Err(anchor_err(ErrorCode::AccountDidNotDeserialize))
+    );
+
+    bridge.migrate_config().unwrap();
+    let event = &bridge.rt.events::<AccountMigrated>()[0];
+    assert_eq!(
+        (event.account, event.from_version, event.to_version),
+        (bridge.config, 0, Config::CURRENT_VERSION)
+    );
+    let config = bridge.config_state();
+    assert_eq!((config.admin, config.bump), (bridge.admin, bump));
+    assert_eq!(config.relayers[0], bridge.relayer);
+    assert_eq!(config.relayer_count, 1);
+    assert!(config.paused);
+    assert_eq!(config.protocol_fee_share_bps, 10_000);
+    assert_eq!(config.max_fee_bps, Config::DEFAULT_MAX_FEE_BPS);
+    assert!(!config.allow_transfer_hook_mints);
+    assert_eq!(config.version, Config::CURRENT_VERSION);
+
+    bridge.set_min_lock_age(60).unwrap();
+    assert_eq!(
+        bridge.migrate_config(),
+        Err(bridge_err(BridgeError::AlreadyMigrated))
+    );
+}
+
+#[test]
+fn version_2_config_grows_with_transfer_hook_mints_disallowed() {
+    let mut bridge = Bridge::new();
+    bridge.set_max_fee_bps(250).unwrap();
+    let mut config = bridge.config_state();
+    config.version = 2;
+    bridge.rt.set_state(&bridge.config, &config);
+    // Version 2 ended at max_fee_bps, one byte short of the current layout.
+    let data = &mut bridge.rt.accounts.get_mut(&bridge.config).unwrap().data;
+    data.truncate(8 + Config::LEN - 1);
+
+    bridge.migrate_config().unwrap();
+    assert_eq!(bridge.rt.get(&bridge.config).data.len(), 8 + Config::LEN);
+    let config = bridge.config_state();
+    assert_eq!(config.version, Config::CURRENT_VERSION);
+    assert_eq!(config.max_fee_bps, 250);
+    assert!(!config.allow_transfer_hook_mints);
+}
+
+#[test]
+fn legacy_bridge_locks_are_grown_and_defaulted() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    bridge.lock(&user, 300).unwrap();
+    bridge.release(1).unwrap();
+    for nonce in [0, 1] {
+        let lock = bridge.lock_pda(nonce);
+        truncate_to_legacy(&mut bridge, &lock, 129, LEGACY_LOCK_DATA);
+    }
+    assert_eq!(
+        bridge.lock_status(0),
+        Err(anchor_err(ErrorCode::AccountDidNotDeserialize))
+    );
+
+    bridge.migrate_bridge_lock(0).unwrap();
+    let event = &bridge.rt.events::<AccountMigrated>()[0];
+    assert_eq!(
+        (event.account, event.from_version, event.to_version),
+        (bridge.lock_pda(0), 0, BridgeLock::CURRENT_VERSION)
+    );
+    assert_eq!(bridge.lock_status(0), Ok(LockStatus::Pending));
+    bridge.release(0).unwrap();
+
+    // Released before partial releases existed: the whole amount counts as released.
+    bridge.migrate_bridge_lock(1).unwrap();
+    let lock = bridge.lock_state(1);
+    assert_eq!((lock.released_amount, lock.outstanding_amount()), (300, 0));
+    assert_eq!(bridge.lock_status(1), Ok(LockStatus::Released));
+    assert_eq!(
+        bridge.migrate_bridge_lock(1),
+        Err(bridge_err(BridgeError::AlreadyMigrated))
+    );
+}
+
+#[test]
+fn migrations_are_admin_only() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(1_000);
+    bridge.lock(&user, 400).unwrap();
+    let mut pool = bridge.pool_state();
+    pool.version = 0;
+    bridge.rt.set_state(&bridge.pool, &pool);
+    let mut config = bridge.config_state();
+    config.version = 0;
+    bridge.rt.set_state(&bridge.config, &config);
+
+    bridge.admin = user.key;
+    let unauthorized = Err(bridge_err(BridgeError::UnauthorizedAdmin));
+    assert_eq!(bridge.migrate_pool(), unauthorized);
+    assert_eq!(bridge.migrate_config(), unauthorized);
+    assert_eq!(bridge.migrate_bridge_lock(0), unauthorized);
+}
+
+#[test]
+fn unmigrated_accounts_block_every_mutating_instruction() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    bridge.lock(&user, 400).unwrap();
+    let mismatch = Err(bridge_err(BridgeError::AccountVersionMismatch));
+
+    let mut pool = bridge.pool_state();
+    pool.version = 0;
+    bridge.rt.set_state(&bridge.pool, &pool);
+    assert_eq!(bridge.deposit(&user, 1_000), mismatch);
+    assert_eq!(bridge.set_compact_events(true), mismatch);
+    assert_eq!(bridge.pause_pool(), mismatch);
+    let relayer = bridge.relayer;
+    assert_eq!(
+        bridge.approve_release(relayer, 0, ApprovalAction::Release, 400),
+        mismatch
+    );
+    // Read-only views still work.
+    assert!(bridge.pool_view().is_ok());
+    bridge.migrate_pool().unwrap();
+    bridge.set_compact_events(true).unwrap();
+
+    let mut config = bridge.config_state();
+    config.version = 0;
+    bridge.rt.set_state(&bridge.config, &config);
+    assert_eq!(bridge.set_min_lock_age(60), mismatch);
+    assert_eq!(bridge.add_relayer(Pubkey::new_unique()), mismatch);
+    assert_eq!(bridge.pause_protocol(), mismatch);
+    assert_eq!(bridge.set_compact_events(false), mismatch);
+    assert_eq!(bridge.lock(&user, 400), mismatch);
+    bridge.migrate_config().unwrap();
+    bridge.lock(&user, 400).unwrap();
+}
@@ -0,0 +1,19 @@
+// Shared support; not every helper is used by every test module.
+#[allow(dead_code)]
+mod fixture;
+#[allow(dead_code)]
+mod harness;
+
+mod admin;
+mod bridge;
+mod deposit;
+mod event_seq;
+mod fees;
+mod insurance;
+mod invariant;
+mod lock;
+mod migration;
+mod native_sol;
+mod preflight;
+mod token2022;
+mod withdraw;
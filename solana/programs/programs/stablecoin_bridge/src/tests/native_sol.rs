@@ -0,0 +1,99 @@
+use super::fixture::{Bridge, PoolParams, Stablecoin, CHAIN};
+use super::harness::bridge_err;
+use crate::errors::BridgeError;
+use crate::events::{BridgeIntent, LiquidityWithdrawn};
+use crate::state::LockReceipt;
+
+const SOL: u64 = 1_000_000_000;
+
+fn sol_pool() -> Bridge {
+    Bridge::with_stablecoin(Stablecoin::NativeSol, PoolParams::default())
+}
+
+#[test]
+fn native_sol_deposits_and_withdraws() {
+    let mut bridge = sol_pool();
+    let user = bridge.user(0);
+    let lamports = bridge.rt.get(&user.key).lamports;
+    let wsol_rent = bridge.rt.get(&user.ata).lamports;
+
+    bridge.deposit_sol(&user, 2 * SOL).unwrap();
+    // The wallet also pays rent for its new UserLpState.
+    let lp_state_rent = bridge.rt.get(&bridge.user_lp_pda(&user.key)).lamports;
+    let lamports = lamports - 2 * SOL - lp_state_rent;
+    assert_eq!(bridge.rt.get(&user.key).lamports, lamports);
+    assert_eq!(bridge.rt.balance(&user.ata), 0);
+    assert_eq!(bridge.rt.get(&user.ata).lamports, wsol_rent);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 2 * SOL);
+    assert_eq!(bridge.pool_state().available_liquidity, 2 * SOL);
+
+    // Withdrawing all LP unwraps the payout and closes the wSOL account.
+    let lp = bridge.rt.balance(&user.lp_ata);
+    bridge.withdraw_sol(&user, lp).unwrap();
+    let out = bridge.rt.events::<LiquidityWithdrawn>()[0].stablecoin_amount;
+    assert!(out > 0);
+    assert!(!bridge.rt.exists(&user.ata));
+    assert_eq!(
+        bridge.rt.get(&user.key).lamports,
+        lamports + out + wsol_rent
+    );
+    assert_eq!(bridge.rt.balance(&bridge.vault), 2 * SOL - out);
+}
+
+#[test]
+fn native_sol_lock_escrows_the_wrapped_lamports() {
+    let mut bridge = sol_pool();
+    let user = bridge.user(0);
+    let lamports = bridge.rt.get(&user.key).lamports;
+
+    bridge.lock_sol(&user, SOL).unwrap();
+    // The wallet also pays rent for the lock and the per-user and per-chain accounts.
+    let rent: u64 = [
+        bridge.lock_pda(0),
+        bridge.user_lock_pda(&user.key),
+        bridge.user_lock_index_pda(&user.key),
+        bridge.chain_lock_counter_pda(CHAIN),
+    ]
+    .iter()
+    .map(|key| bridge.rt.get(key).lamports)
+    .sum();
+    assert_eq!(bridge.rt.get(&user.key).lamports, lamports - SOL - rent);
+    assert_eq!(bridge.lock_state(0).amount, SOL);
+    assert_eq!(bridge.pool_state().locked_liquidity, SOL);
+    assert_eq!(bridge.rt.balance(&bridge.vault), SOL);
+
+    // The native variant returns the same receipt as lock_for_bridge.
+    let receipt = bridge.rt.returned::<LockReceipt>();
+    let intent = &bridge.rt.events::<BridgeIntent>()[0];
+    assert_eq!(
+        (receipt.bridge_lock, receipt.nonce),
+        (bridge.lock_pda(0), 0)
+    );
+    assert_eq!((intent.nonce, intent.amount), (receipt.nonce, SOL));
+
+    // A revert refunds wSOL, which the sender can unwrap themselves.
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), SOL);
+}
+
+#[test]
+fn sol_variants_need_a_wrapped_sol_pool() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+
+    let not_native = Err(bridge_err(BridgeError::NotNativeMint));
+    assert_eq!(bridge.deposit_sol(&user, 1_000), not_native);
+    assert_eq!(bridge.withdraw_sol(&user, 1_000), not_native);
+    assert_eq!(bridge.lock_sol(&user, 1_000), not_native);
+}
+
+#[test]
+fn native_sol_deposit_must_be_non_zero() {
+    let mut bridge = sol_pool();
+    let user = bridge.user(0);
+    assert_eq!(
+        bridge.deposit_sol(&user, 0),
+        Err(bridge_err(BridgeError::ZeroStablecoinAmount))
+    );
+}
@@ -0,0 +1,210 @@
+use super::fixture::{Bridge, PoolParams};
+use super::harness::{bridge_err, code};
+use crate::errors::BridgeError;
+use crate::state::{OperationKind, Pool};
+
+#[test]
+fn check_deposit_reports_each_failure() {
+    let mut bridge = Bridge::new();
+    assert_eq!(bridge.check_deposit(5_000), 0);
+    assert_eq!(
+        bridge.check_deposit(0),
+        code(BridgeError::ZeroStablecoinAmount)
+    );
+
+    bridge.set_config_paused(true);
+    assert_eq!(bridge.check_deposit(5_000), code(BridgeError::PoolPaused));
+    bridge.set_config_paused(false);
+
+    bridge.pause_pool().unwrap();
+    assert_eq!(bridge.check_deposit(5_000), code(BridgeError::PoolPaused));
+}
+
+#[test]
+fn check_deposit_reports_zero_lp_like_deposit() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    // A donation makes each share worth two units.
+    let donor = bridge.user(10_000);
+    bridge.deposit_and_burn(&donor, 10_000).unwrap();
+
+    assert_eq!(bridge.check_deposit(1), code(BridgeError::ZeroLpAmount));
+    let depositor = bridge.user(1);
+    assert_eq!(
+        bridge.deposit(&depositor, 1),
+        Err(bridge_err(BridgeError::ZeroLpAmount))
+    );
+}
+
+#[test]
+fn check_lock_reports_each_failure() {
+    let mut bridge = Bridge::with(PoolParams {
+        max_lock_per_tx: 500,
+        ..PoolParams::default()
+    });
+    assert_eq!(bridge.check_lock(500), 0);
+    assert_eq!(
+        bridge.check_lock(0),
+        code(BridgeError::ZeroStablecoinAmount)
+    );
+    assert_eq!(
+        bridge.check_lock(501),
+        code(BridgeError::LockAmountExceedsLimit)
+    );
+
+    bridge.set_config_paused(true);
+    assert_eq!(bridge.check_lock(100), code(BridgeError::PoolPaused));
+    bridge.set_config_paused(false);
+
+    bridge.pause_pool().unwrap();
+    assert_eq!(bridge.check_lock(100), code(BridgeError::PoolPaused));
+}
+
+#[test]
+fn check_lock_matches_lock_for_bridge() {
+    let mut bridge = Bridge::with(PoolParams {
+        max_lock_per_tx: 500,
+        ..PoolParams::default()
+    });
+    let user = bridge.user(1_000);
+
+    assert_eq!(
+        bridge.check_lock(501),
+        code(BridgeError::LockAmountExceedsLimit)
+    );
+    assert_eq!(
+        bridge.lock(&user, 501),
+        Err(bridge_err(BridgeError::LockAmountExceedsLimit))
+    );
+    assert_eq!(bridge.check_lock(500), 0);
+    bridge.lock(&user, 500).unwrap();
+    assert_eq!(bridge.lock_state(0).amount, 500);
+}
+
+#[test]
+fn effective_fee_matches_charged_fee() {
+    let mut bridge = Bridge::with(PoolParams {
+        fee_rate_bps: 30,
+        ..PoolParams::default()
+    });
+    assert_eq!(bridge.effective_fee(OperationKind::Deposit), 30);
+    assert_eq!(bridge.effective_fee(OperationKind::Withdraw), 0);
+    assert_eq!(bridge.effective_fee(OperationKind::Bridge), 0);
+
+    bridge.set_max_fee_bps(250).unwrap();
+    bridge.update_fee_rate(250, 40, 0).unwrap();
+    assert_eq!(bridge.effective_fee(OperationKind::Deposit), 250);
+    assert_eq!(bridge.effective_fee(OperationKind::Withdraw), 40);
+    let user = bridge.user(100_000);
+    bridge.deposit(&user, 100_000).unwrap();
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 100_000 * 250 / 10_000);
+}
+
+#[test]
+fn quotes_match_execution() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    let user = bridge.user(10_000);
+    bridge.deposit(&lp, 3_000).unwrap();
+    // Skew the share price so the quote exercises rounding.
+    let donor = bridge.user(700);
+    bridge.deposit_and_burn(&donor, 700).unwrap();
+
+    let quoted = bridge.quote_deposit(1_234).unwrap();
+    bridge.deposit(&user, 1_234).unwrap();
+    assert_eq!(bridge.rt.balance(&user.lp_ata), quoted);
+
+    let quoted = bridge.quote_withdraw(quoted).unwrap();
+    let before = bridge.rt.balance(&user.ata);
+    bridge
+        .withdraw(&user, bridge.rt.balance(&user.lp_ata))
+        .unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata) - before, quoted);
+}
+
+#[test]
+fn quotes_fail_like_execution() {
+    let mut bridge = Bridge::new();
+    assert_eq!(
+        bridge.quote_deposit(0),
+        Err(bridge_err(BridgeError::ZeroStablecoinAmount))
+    );
+    assert_eq!(
+        bridge.quote_withdraw(0),
+        Err(bridge_err(BridgeError::ZeroLpAmount))
+    );
+}
+
+#[test]
+fn pool_view_reports_derived_values() {
+    let mut bridge = Bridge::new();
+    let empty = bridge.pool_view().unwrap();
+    assert_eq!(empty.share_price, Pool::SHARE_PRICE_SCALE);
+    assert_eq!(empty.utilization_bps, 0);
+
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.deposit_and_burn(&user, 5_000).unwrap();
+    bridge.lock(&user, 2_000).unwrap();
+    bridge.pause_pool().unwrap();
+    bridge.set_config_paused(true);
+
+    let pool = bridge.pool_state();
+    let view = bridge.pool_view().unwrap();
+    assert_eq!(
+        (
+            view.total_liquidity,
+            view.available_liquidity,
+            view.locked_liquidity,
+            view.lp_supply
+        ),
+        (17_000, 15_000, 2_000, 10_000)
+    );
+    assert_eq!(
+        u128::from(view.share_price),
+        u128::from(pool.available_liquidity) * 1_000_000_000 / u128::from(pool.lp_supply)
+    );
+    assert_eq!(view.share_price, 1_500_000_000);
+    assert_eq!(
+        u64::from(view.utilization_bps),
+        pool.locked_liquidity * 10_000 / pool.total_liquidity
+    );
+    assert!(view.paused && view.protocol_paused);
+    assert!(!view.emergency_mode && !view.draining);
+}
+
+#[test]
+fn share_price_is_lp_value_per_lp_unit() {
+    let scale = u128::from(Pool::SHARE_PRICE_SCALE);
+    let mut bridge = Bridge::new();
+    assert_eq!(bridge.share_price(), Ok(scale));
+
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    assert_eq!(bridge.share_price(), Ok(scale));
+    bridge.deposit_and_burn(&user, 5_000).unwrap();
+    assert_eq!(bridge.share_price(), Ok(scale * 3 / 2));
+    // Escrowed lock funds are not LP value.
+    bridge.lock(&user, 3_000).unwrap();
+    assert_eq!(bridge.share_price(), Ok(scale * 3 / 2));
+
+    let mut pool = bridge.pool_state();
+    pool.lp_supply = 3;
+    bridge.rt.set_state(&bridge.pool, &pool);
+    assert_eq!(bridge.share_price(), Ok(scale * 15_000 / 3));
+}
+
+#[test]
+fn share_price_does_not_saturate_at_u64() {
+    let mut bridge = Bridge::new();
+    let mut pool = bridge.pool_state();
+    pool.total_liquidity = u64::MAX;
+    pool.available_liquidity = u64::MAX;
+    pool.lp_supply = 1;
+    bridge.rt.set_state(&bridge.pool, &pool);
+
+    let full = u128::from(u64::MAX) * u128::from(Pool::SHARE_PRICE_SCALE);
+    assert_eq!(bridge.share_price(), Ok(full));
+    assert_eq!(bridge.pool_view().unwrap().share_price, u64::MAX);
+}
@@ -0,0 +1,110 @@
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+
+use super::fixture::{Bridge, PoolParams, Stablecoin};
+use super::harness::bridge_err;
+use crate::errors::BridgeError;
+use crate::events::{AllowTransferHookMintsUpdated, BridgeIntent, LiquidityDeposited};
+
+#[test]
+fn plain_token_2022_mint_round_trips() {
+    let mut bridge = Bridge::with_stablecoin(Stablecoin::Token2022, PoolParams::default());
+    let user = bridge.user(10_000);
+
+    bridge.deposit(&user, 5_000).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    bridge.release(0).unwrap();
+    bridge.withdraw(&user, 1_000).unwrap();
+
+    let pool = bridge.pool_state();
+    assert_eq!(pool.total_liquidity, 4_000);
+    assert_eq!(pool.locked_liquidity, 0);
+    assert_eq!(bridge.rt.balance(&bridge.vault), 4_000);
+    assert_eq!(bridge.rt.balance(&user.ata), 6_000);
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 3_000);
+}
+
+#[test]
+fn transfer_fee_mint_credits_received_amounts() {
+    // 1% of every transfer is withheld on the destination account.
+    let mut bridge = Bridge::with_stablecoin(Stablecoin::TransferFee(100), PoolParams::default());
+    let user = bridge.user(20_000);
+
+    bridge.deposit(&user, 10_000).unwrap();
+    assert_eq!(
+        bridge.rt.events::<LiquidityDeposited>()[0].stablecoin_amount,
+        10_000
+    );
+    let pool = bridge.pool_state();
+    assert_eq!(pool.total_liquidity, 9_900);
+    assert_eq!(pool.available_liquidity, 9_900);
+    // LP is priced on the 9_900 received, less the MINIMUM_LIQUIDITY dead shares.
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 8_900);
+
+    bridge.lock(&user, 1_000).unwrap();
+    assert_eq!(bridge.rt.events::<BridgeIntent>()[0].amount, 990);
+    assert_eq!(bridge.lock_state(0).amount, 990);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.locked_liquidity, 990);
+    assert_eq!(pool.total_liquidity, 10_890);
+    assert!(bridge.rt.balance(&bridge.vault) >= pool.total_liquidity);
+}
+
+#[test]
+fn lp_mint_must_share_the_stablecoin_token_program() {
+    let mut bridge = Bridge::bare_with(Stablecoin::Token2022);
+    bridge.lp_mint = bridge.rt.create_mint(spl_token::ID, bridge.pool, 6);
+    bridge.initialize_config().unwrap();
+
+    assert_eq!(
+        bridge.initialize_pool(PoolParams::default()),
+        Err(bridge_err(BridgeError::InvalidPoolState))
+    );
+}
+
+#[test]
+fn transfer_hook_mints_are_rejected_unless_allowed() {
+    let mut bridge = Bridge::bare_with(Stablecoin::TransferHook);
+    bridge.initialize_config().unwrap();
+    assert_eq!(
+        bridge.initialize_pool(PoolParams::default()),
+        Err(bridge_err(BridgeError::TransferHookNotAllowed))
+    );
+
+    bridge.set_allow_transfer_hook_mints(true).unwrap();
+    let event = &bridge.rt.events::<AllowTransferHookMintsUpdated>()[0];
+    assert_eq!((event.old_allowed, event.new_allowed), (false, true));
+    assert_eq!(event.admin, bridge.admin);
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+    assert!(bridge.config_state().allow_transfer_hook_mints);
+}
+
+#[test]
+fn transfer_hook_lp_mints_are_rejected_unless_allowed() {
+    let mut bridge = Bridge::bare_with(Stablecoin::Token2022);
+    bridge.lp_mint = bridge.rt.create_transfer_hook_mint(bridge.pool, 6);
+    bridge.initialize_config().unwrap();
+    let not_allowed = Err(bridge_err(BridgeError::TransferHookNotAllowed));
+    assert_eq!(bridge.initialize_pool(PoolParams::default()), not_allowed);
+
+    let plain_lp_mint = bridge.rt.create_mint(spl_token_2022::ID, bridge.pool, 6);
+    let hook_lp_mint = bridge.lp_mint;
+    bridge.lp_mint = plain_lp_mint;
+    bridge.initialize_pool(PoolParams::default()).unwrap();
+    assert_eq!(bridge.migrate_lp_mint(hook_lp_mint), not_allowed);
+
+    bridge.set_allow_transfer_hook_mints(true).unwrap();
+    bridge.migrate_lp_mint(hook_lp_mint).unwrap();
+    assert_eq!(bridge.pool_state().lp_token_mint, hook_lp_mint);
+}
+
+#[test]
+fn set_allow_transfer_hook_mints_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_allow_transfer_hook_mints(true),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+    assert!(!bridge.config_state().allow_transfer_hook_mints);
+}
@@ -0,0 +1,298 @@
+use super::fixture::Bridge;
+use super::harness::{anchor_err, bridge_err, warp};
+use crate::errors::BridgeError;
+use crate::events::{
+    AutoPauseTriggered, EmergencyModeDisabled, EmergencyModeEnabled, WithdrawalFulfilled,
+    WithdrawalRequested,
+};
+use crate::state::{PauseReason, WithdrawalRequest};
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::ProgramError;
+
+#[test]
+fn withdraw_respects_min_stablecoin_out() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+
+    // 4_500 of 10_000 shares redeem for exactly 4_500.
+    assert_eq!(
+        bridge.withdraw_min(&user, 4_500, 4_501),
+        Err(bridge_err(BridgeError::SlippageExceeded))
+    );
+    assert_eq!(bridge.rt.balance(&user.lp_ata), 9_000);
+
+    bridge.withdraw_min(&user, 4_500, 4_500).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 4_500);
+}
+
+#[test]
+fn queued_request_is_fulfilled_after_a_lock_release() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    let other = bridge.user(5_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    bridge.deposit(&other, 5_000).unwrap();
+    let sender = bridge.user(1_000);
+    bridge.lock(&sender, 400).unwrap();
+    let share_price = bridge.pool_state().share_price();
+
+    bridge.request_withdrawal(&lp, 4_500).unwrap();
+    let event = &bridge.rt.events::<WithdrawalRequested>()[0];
+    assert_eq!(
+        (event.owner, event.lp_tokens_burned, event.stablecoin_amount),
+        (lp.key, 4_500, 4_500)
+    );
+    assert_eq!(bridge.rt.balance(&lp.lp_ata), 4_500);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.queued_withdrawals, 4_500);
+    assert_eq!(pool.available_liquidity, 15_000);
+    assert_eq!(pool.unqueued_liquidity(), 10_500);
+    assert_eq!(pool.lp_supply, 10_500);
+    // The queued amount leaves LP value together with its burned LP.
+    assert_eq!(pool.share_price(), share_price);
+    assert_eq!(
+        bridge.request_withdrawal(&lp, 1_000),
+        Err(ProgramError::AccountAlreadyInitialized)
+    );
+
+    bridge.release(0).unwrap();
+    bridge.fulfill_withdrawal(&lp).unwrap();
+    let event = &bridge.rt.events::<WithdrawalFulfilled>()[0];
+    assert_eq!((event.owner, event.stablecoin_amount), (lp.key, 4_500));
+    assert_eq!(bridge.rt.balance(&lp.ata), 4_500);
+    assert!(!bridge.rt.exists(&bridge.withdrawal_request_pda(&lp.key)));
+    let pool = bridge.pool_state();
+    assert_eq!(pool.queued_withdrawals, 0);
+    assert_eq!(pool.available_liquidity, 10_500);
+    assert_eq!(pool.unqueued_liquidity(), 10_500);
+    assert_eq!(pool.share_price(), share_price);
+    assert_eq!(
+        bridge.fulfill_withdrawal(&lp),
+        Err(anchor_err(ErrorCode::AccountNotInitialized))
+    );
+
+    // The other LP's redemption value was untouched by the queue.
+    bridge.withdraw(&other, 5_000).unwrap();
+    assert_eq!(bridge.rt.balance(&other.ata), 5_000);
+}
+
+#[test]
+fn queued_request_waits_for_backed_locks_to_settle() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    bridge.set_require_liquidity_backing(true).unwrap();
+    let sender = bridge.user(10_000);
+    bridge.lock(&sender, 8_000).unwrap();
+
+    // 4_500 LP is worth 4_500, but only 2_000 is free to pay out.
+    let short = Err(bridge_err(BridgeError::InsufficientLiquidity));
+    assert_eq!(bridge.withdraw(&lp, 4_500), short);
+    bridge.request_withdrawal(&lp, 4_500).unwrap();
+    let pool = bridge.pool_state();
+    assert_eq!(pool.queued_withdrawals, 4_500);
+    assert_eq!(pool.unqueued_liquidity(), 0);
+    assert_eq!(pool.lp_value(), 5_500);
+    assert_eq!(bridge.fulfill_withdrawal(&lp), short);
+    // Neither direct withdrawals nor backed locks may draw on what the queue is owed.
+    assert_eq!(bridge.withdraw(&lp, 100), short);
+    assert_eq!(bridge.lock(&sender, 100), short);
+
+    bridge.release(0).unwrap();
+    assert_eq!(bridge.pool_state().unqueued_liquidity(), 5_500);
+    bridge.fulfill_withdrawal(&lp).unwrap();
+    assert_eq!(bridge.rt.balance(&lp.ata), 4_500);
+    let pool = bridge.pool_state();
+    assert_eq!(pool.queued_withdrawals, 0);
+    assert_eq!(
+        (pool.available_liquidity, pool.reserved_liquidity),
+        (5_500, 0)
+    );
+}
+
+#[test]
+fn queued_request_charges_the_withdraw_fee_on_fulfillment() {
+    let mut bridge = Bridge::new();
+    let lp = bridge.user(10_000);
+    bridge.deposit(&lp, 10_000).unwrap();
+    bridge.set_max_fee_bps(200).unwrap();
+    bridge.update_fee_rate(0, 200, 0).unwrap();
+
+    bridge.request_withdrawal(&lp, 4_500).unwrap();
+    let request: WithdrawalRequest = bridge.rt.state(&bridge.withdrawal_request_pda(&lp.key));
+    assert_eq!((request.amount, request.protocol_fee), (4_410, 90));
+    assert_eq!(bridge.pool_state().queued_withdrawals, request.owed());
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 0);
+
+    bridge.fulfill_withdrawal(&lp).unwrap();
+    assert_eq!(bridge.rt.balance(&lp.ata), 4_410);
+    assert_eq!(bridge.rt.balance(&bridge.fee_vault), 90);
+    assert_eq!(bridge.pool_state().available_liquidity, 5_500);
+}
+
+#[test]
+fn emergency_mode_lets_lps_exit_a_paused_pool() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    bridge.pause_pool().unwrap();
+    let paused = Err(bridge_err(BridgeError::PoolPaused));
+    assert_eq!(bridge.withdraw(&user, 1_000), paused);
+
+    bridge.enable_emergency_mode().unwrap();
+    let event = &bridge.rt.events::<EmergencyModeEnabled>()[0];
+    assert_eq!((event.pool, event.admin), (bridge.pool, bridge.admin));
+
+    bridge.withdraw(&user, 1_000).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 6_000);
+    assert_eq!(bridge.deposit(&user, 1_000), paused);
+    assert_eq!(bridge.lock(&user, 100), paused);
+
+    // The protocol-wide pause still halts withdrawals.
+    bridge.pause_protocol().unwrap();
+    assert_eq!(bridge.withdraw(&user, 1_000), paused);
+    bridge.resume_protocol().unwrap();
+
+    bridge.disable_emergency_mode().unwrap();
+    assert_eq!(bridge.rt.events::<EmergencyModeDisabled>().len(), 1);
+    assert_eq!(bridge.withdraw(&user, 1_000), paused);
+}
+
+#[test]
+fn emergency_mode_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.enable_emergency_mode(),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn lp_lockup_blocks_withdrawals_until_it_elapses() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.set_min_lp_hold(3_600).unwrap();
+    bridge.deposit(&user, 5_000).unwrap();
+
+    let locked = Err(bridge_err(BridgeError::LpLockupActive));
+    warp(3_599);
+    assert_eq!(bridge.withdraw(&user, 1_000), locked);
+
+    warp(1);
+    bridge.withdraw(&user, 1_000).unwrap();
+    assert_eq!(bridge.rt.balance(&user.ata), 6_000);
+
+    // A new deposit pushes the lockup back by its share of the holder's LP.
+    bridge.deposit(&user, 1_000).unwrap();
+    assert_eq!(bridge.withdraw(&user, 1_000), locked);
+    warp(3_600);
+    bridge.withdraw(&user, 1_000).unwrap();
+}
+
+#[test]
+fn lp_lockup_defaults_to_off() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 5_000).unwrap();
+    bridge.withdraw(&user, 1_000).unwrap();
+}
+
+#[test]
+fn lp_lockup_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_min_lp_hold(60),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn outflow_past_the_limit_auto_pauses_the_pool() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(20_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    bridge.lock(&user, 1_000).unwrap();
+    bridge.set_outflow_limit(2_500, 3_600).unwrap();
+
+    bridge.withdraw(&user, 1_000).unwrap();
+    bridge.complete(0).unwrap();
+    assert!(bridge.rt.events::<AutoPauseTriggered>().is_empty());
+    assert!(!bridge.pool_state().paused);
+
+    // The withdrawal that crosses the limit settles; everything after it is blocked.
+    bridge.withdraw(&user, 1_000).unwrap();
+    let event = &bridge.rt.events::<AutoPauseTriggered>()[0];
+    assert_eq!(
+        (event.outflow_window_volume, event.max_outflow_per_window),
+        (3_000, 2_500)
+    );
+    assert!(bridge.pool_state().paused);
+    assert_eq!(
+        bridge.pool_state().pause_reason,
+        PauseReason::CircuitBreaker
+    );
+    let paused = Err(bridge_err(BridgeError::PoolPaused));
+    assert_eq!(bridge.withdraw(&user, 1_000), paused);
+    assert_eq!(bridge.complete(1), paused);
+
+    // Resuming starts a fresh window.
+    bridge.resume_pool().unwrap();
+    assert_eq!(bridge.pool_state().outflow_window_volume, 0);
+    bridge.complete(1).unwrap();
+    assert!(!bridge.pool_state().paused);
+}
+
+#[test]
+fn outflow_window_rolls_over() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    bridge.set_outflow_limit(1_500, 60).unwrap();
+
+    bridge.withdraw(&user, 1_000).unwrap();
+    warp(60);
+    bridge.withdraw(&user, 1_000).unwrap();
+    assert!(!bridge.pool_state().paused);
+    bridge.withdraw(&user, 1_000).unwrap();
+    assert!(bridge.pool_state().paused);
+}
+
+#[test]
+fn outflow_limit_is_admin_only() {
+    let mut bridge = Bridge::new();
+    bridge.admin = bridge.relayer;
+    assert_eq!(
+        bridge.set_outflow_limit(1_000, 60),
+        Err(bridge_err(BridgeError::UnauthorizedAdmin))
+    );
+}
+
+#[test]
+fn over_withdrawal_reports_insufficient_lp_balance() {
+    let mut bridge = Bridge::new();
+    let user = bridge.user(10_000);
+    bridge.deposit(&user, 10_000).unwrap();
+    let lp = bridge.rt.balance(&user.lp_ata);
+
+    assert_eq!(
+        bridge.withdraw(&user, lp + 1),
+        Err(bridge_err(BridgeError::InsufficientLpBalance))
+    );
+    bridge.withdraw(&user, lp).unwrap();
+}
+
+#[test]
+fn withdrawing_from_an_empty_pool_reports_no_lp_supply() {
+    let bridge = Bridge::new();
+    let (config, pool) = (bridge.config_state(), bridge.pool_state());
+    assert_eq!(pool.lp_supply, 0);
+    let result = crate::validate_withdraw(&config, &pool, None, 0, 100, 0);
+    assert_eq!(
+        result.map(|_| ()).map_err(ProgramError::from),
+        Err(bridge_err(BridgeError::NoLpSupply))
+    );
+}